@@ -0,0 +1,53 @@
+// Small client for a game started with `tersoli --listen <port or socket path>`.
+// Every stdin line is sent to the game and everything the game sends is printed, e.g.
+// cargo run --example client 7878
+// {"cmd":"command","command":"move_right"}
+use std::{
+    io::{self, BufRead, BufReader, Read, Write},
+    net::TcpStream,
+    thread,
+};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let address = std::env::args()
+        .nth(1)
+        .ok_or("usage: client <port, host:port or socket path>")?;
+
+    let (reader, mut writer): (Box<dyn Read + Send>, Box<dyn Write>) = if address.contains('/') {
+        connect_unix(&address)?
+    } else {
+        let address = match address.parse::<u16>() {
+            Ok(port) => format!("127.0.0.1:{}", port),
+            Err(_) => address,
+        };
+        let stream = TcpStream::connect(address)?;
+        (Box::new(stream.try_clone()?), Box::new(stream))
+    };
+
+    thread::spawn(move || {
+        for line in BufReader::new(reader).lines().map_while(Result::ok) {
+            println!("{}", line);
+        }
+        std::process::exit(0);
+    });
+
+    for line in io::stdin().lock().lines() {
+        writeln!(writer, "{}", line?)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(unix)]
+fn connect_unix(path: &str) -> io::Result<(Box<dyn Read + Send>, Box<dyn Write>)> {
+    let stream = std::os::unix::net::UnixStream::connect(path)?;
+    Ok((Box::new(stream.try_clone()?), Box::new(stream)))
+}
+
+#[cfg(not(unix))]
+fn connect_unix(_: &str) -> io::Result<(Box<dyn Read + Send>, Box<dyn Write>)> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "Unix domain sockets aren't supported on this platform",
+    ))
+}
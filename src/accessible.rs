@@ -0,0 +1,514 @@
+use std::io::{BufRead, Write};
+
+use crate::{
+    card::Card,
+    game::Game,
+    i18n::{self, Catalog, fill},
+    location::Location,
+    moves::Move,
+    protocol::{Request, Response, Session},
+    rules::Variant,
+    suit::Suit,
+};
+
+#[derive(PartialEq, Debug)]
+pub enum Action {
+    Look,
+    Read(Location),
+    Play(Move),
+    Moves,
+    Undo,
+    New,
+    Help,
+    Quit,
+}
+
+// A line based game for screen readers, every answer is a short sentence or one line per pile
+pub fn help(game: &Game) -> Vec<String> {
+    let text = i18n::text();
+    let reserve = match game.rules.reserve_size {
+        0 => "",
+        _ => text.reader_help_reserve,
+    };
+    vec![
+        fill(
+            text.reader_help_piles,
+            &[
+                &reserve,
+                &game.foundation.cards.len(),
+                &game.tableau.cards.len(),
+            ],
+        ),
+        text.reader_help_moves.to_string(),
+        text.reader_help_keys.to_string(),
+        text.reader_help_other.to_string(),
+    ]
+}
+
+pub fn describe_card(card: Card) -> String {
+    card_name(i18n::text(), card)
+}
+
+fn card_name(text: &Catalog, card: Card) -> String {
+    let rank = text.rank_names[card.rank as usize - 1];
+    let suit = match card.suit {
+        Suit::Spades => text.reader_suits[0],
+        Suit::Hearts => text.reader_suits[1],
+        Suit::Clubs => text.reader_suits[2],
+        Suit::Diamonds => text.reader_suits[3],
+    };
+
+    if text.reader_suit_first {
+        fill(text.reader_card, &[&suit, &rank])
+    } else {
+        fill(text.reader_card, &[&rank, &suit])
+    }
+}
+
+pub fn describe_location(location: Location) -> String {
+    let text = i18n::text();
+    match location {
+        Location::Stock => text.reader_piles[0].to_string(),
+        Location::Waste => text.reader_piles[1].to_string(),
+        Location::Reserve => text.reader_piles[2].to_string(),
+        Location::Tableau(column) => fill(text.reader_column, &[&(column + 1)]),
+        Location::Foundation(slot) => fill(text.reader_foundation, &[&(slot + 1)]),
+    }
+}
+
+fn cards_text(count: usize) -> String {
+    let text = i18n::text();
+    match count {
+        1 => text.reader_one_card.to_string(),
+        count => fill(text.reader_cards, &[&count]),
+    }
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+pub fn describe_pile(game: &Game, location: Location) -> String {
+    let text = i18n::text();
+    let contents = match location {
+        Location::Stock => match game.stock.cards.len() {
+            0 => text.reader_empty.to_string(),
+            count => cards_text(count),
+        },
+        Location::Waste | Location::Reserve | Location::Foundation(_) => {
+            let count = match location {
+                Location::Waste => game.waste.cards.len(),
+                Location::Reserve => game.reserve.cards.len(),
+                _ => 1,
+            };
+            match game.get_top_card(location) {
+                Some(card) if count > 1 => {
+                    format!("{}, {}", describe_card(card), cards_text(count))
+                }
+                Some(card) => describe_card(card),
+                None => text.reader_empty.to_string(),
+            }
+        }
+        Location::Tableau(column) => {
+            let hidden = game.tableau.cutoffs[column] as usize;
+            let mut parts = Vec::new();
+            if hidden > 0 {
+                parts.push(fill(text.reader_hidden, &[&hidden]));
+            }
+            parts.extend(
+                game.tableau
+                    .get_visible_cards(column)
+                    .into_iter()
+                    .map(describe_card),
+            );
+            if parts.is_empty() {
+                text.reader_empty.to_string()
+            } else {
+                parts.join(", ")
+            }
+        }
+    };
+
+    format!("{}: {}", capitalize(&describe_location(location)), contents)
+}
+
+pub fn describe_game(game: &Game) -> Vec<String> {
+    let mut piles = vec![Location::Stock, Location::Waste];
+    if game.rules.reserve_size > 0 {
+        piles.push(Location::Reserve);
+    }
+    piles.extend((0..game.foundation.cards.len()).map(Location::Foundation));
+    piles.extend((0..game.tableau.cards.len()).map(Location::Tableau));
+
+    piles
+        .into_iter()
+        .map(|location| describe_pile(game, location))
+        .collect()
+}
+
+// What else moves along with the card, like " and 2 cards on it"
+fn run_text(mv: Move) -> String {
+    match mv {
+        Move::TableauToTableau(_, _, count) if count > 1 => {
+            fill(i18n::text().reader_run, &[&cards_text(count - 1)])
+        }
+        _ => String::new(),
+    }
+}
+
+pub fn describe_move(game: &Game, mv: Move) -> String {
+    let text = i18n::text();
+    let Some(card) = game.moving_card(mv) else {
+        return text.reader_draw.to_string();
+    };
+
+    fill(
+        text.reader_move,
+        &[
+            &describe_card(card),
+            &run_text(mv),
+            &describe_location(mv.source()),
+            &describe_location(mv.destination()),
+        ],
+    )
+}
+
+// Says what happened, like "moved 7 of hearts to column 3, revealed jack of clubs"
+pub fn announce(before: &Game, after: &Game, mv: Move) -> String {
+    let text = i18n::text();
+    let mut line = match (mv, before.moving_card(mv)) {
+        (Move::Draw, _) if before.stock.cards.is_empty() => text.reader_recycled.to_string(),
+        (Move::Draw, _) => match after.waste.get_top_card() {
+            Some(card) => fill(text.reader_drew, &[&describe_card(card)]),
+            None => text.reader_drew_nothing.to_string(),
+        },
+        (_, Some(card)) => fill(
+            text.reader_moved,
+            &[
+                &describe_card(card),
+                &run_text(mv),
+                &describe_location(mv.destination()),
+            ],
+        ),
+        (_, None) => text.reader_moved_plain.to_string(),
+    };
+
+    if let Location::Tableau(column) = mv.source()
+        && after.tableau.cutoffs[column] < before.tableau.cutoffs[column]
+        && let Some(card) = after.tableau.get_top_card(mv.source())
+    {
+        line.push_str(&fill(text.reader_revealed, &[&describe_card(card)]));
+    }
+    if after.is_won() {
+        line.push_str(&fill(text.reader_won, &[&after.moves]));
+    }
+    line
+}
+
+fn parse_pile(word: &str, game: &Game) -> Option<(Location, bool)> {
+    let location = match word {
+        "s" | "stock" => Location::Stock,
+        "w" | "waste" => Location::Waste,
+        "r" | "reserve" if game.rules.reserve_size > 0 => Location::Reserve,
+        // Any foundation, the right one is picked for the card
+        "f" | "foundation" => return Some((Location::Foundation(0), true)),
+        _ => {
+            let number = |text: &str| text.parse::<usize>().ok()?.checked_sub(1);
+            match word.strip_prefix('f') {
+                Some(slot) => Location::Foundation(
+                    number(slot).filter(|slot| *slot < game.foundation.cards.len())?,
+                ),
+                None => Location::Tableau(
+                    number(word).filter(|column| *column < game.tableau.cards.len())?,
+                ),
+            }
+        }
+    };
+    Some((location, false))
+}
+
+pub fn parse_action(line: &str, game: &Game) -> Result<Action, String> {
+    let text = i18n::text();
+    let line = line.trim().to_lowercase();
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let unknown = || fill(text.reader_unknown, &[&line]);
+
+    match words.as_slice() {
+        ["l" | "look"] => Ok(Action::Look),
+        ["d" | "draw"] => Ok(Action::Play(Move::Draw)),
+        ["m" | "moves"] => Ok(Action::Moves),
+        ["u" | "undo"] => Ok(Action::Undo),
+        ["n" | "new"] => Ok(Action::New),
+        ["h" | "help" | "?"] => Ok(Action::Help),
+        ["q" | "quit"] => Ok(Action::Quit),
+        [word] => match parse_pile(word, game) {
+            Some((_, true)) => Ok(Action::Read(Location::Foundation(0))),
+            Some((location, _)) => Ok(Action::Read(location)),
+            None => Err(unknown()),
+        },
+        [from, to] => {
+            let (Some((from, false)), Some((to, any_slot))) =
+                (parse_pile(from, game), parse_pile(to, game))
+            else {
+                return Err(unknown());
+            };
+            if from == Location::Stock {
+                return Err(text.reader_draw_with_d.to_string());
+            }
+
+            let to = if any_slot {
+                let card = game
+                    .get_top_card(from)
+                    .ok_or_else(|| fill(text.reader_nothing_from, &[&describe_location(from)]))?;
+                let slot = game
+                    .foundation
+                    .find_slot(card)
+                    .ok_or_else(|| fill(text.reader_no_foundation, &[&describe_card(card)]))?;
+                Location::Foundation(slot)
+            } else {
+                to
+            };
+            game.move_between(from, to)
+                .map(Action::Play)
+                .ok_or_else(|| {
+                    fill(
+                        text.reader_cannot,
+                        &[&describe_location(from), &describe_location(to)],
+                    )
+                })
+        }
+        _ => Err(unknown()),
+    }
+}
+
+// The shortest thing to type for a move
+fn typed_move(mv: Move) -> String {
+    let word = |location: Location| match location {
+        Location::Stock => "s".to_string(),
+        Location::Waste => "w".to_string(),
+        Location::Reserve => "r".to_string(),
+        Location::Tableau(column) => (column + 1).to_string(),
+        Location::Foundation(_) => "f".to_string(),
+    };
+
+    match mv {
+        Move::Draw => "d".to_string(),
+        _ => format!("{} {}", word(mv.source()), word(mv.destination())),
+    }
+}
+
+pub fn respond(session: &mut Session, action: Action) -> Vec<String> {
+    let text = i18n::text();
+    match action {
+        Action::Look => describe_game(&session.game),
+        Action::Read(location) => vec![describe_pile(&session.game, location)],
+        Action::Moves => {
+            let moves = session.game.legal_moves();
+            let mut lines = vec![fill(text.reader_possible, &[&moves.len()])];
+            lines.extend(
+                moves
+                    .into_iter()
+                    .map(|mv| format!("{}: {}", typed_move(mv), describe_move(&session.game, mv))),
+            );
+            lines
+        }
+        Action::Play(mv) => {
+            let before = session.game.clone();
+            match session.handle(Request::Move { mv }) {
+                Response::Moved { .. } => vec![announce(&before, &session.game, mv)],
+                _ => vec![fill(text.reader_cant_move, &[&describe_move(&before, mv)])],
+            }
+        }
+        Action::Undo => match session.handle(Request::Undo) {
+            Response::Undone { mv: Move::Draw } => vec![text.reader_undid_draw.to_string()],
+            Response::Undone { mv } => {
+                vec![fill(
+                    text.reader_undid,
+                    &[&describe_move(&session.game, mv)],
+                )]
+            }
+            _ => vec![text.reader_nothing_to_undo.to_string()],
+        },
+        Action::New => {
+            session.handle(Request::New {
+                seed: None,
+                rules: None,
+            });
+            let mut lines = vec![fill(text.reader_new_game, &[&session.replay.seed])];
+            lines.extend(describe_game(&session.game));
+            lines
+        }
+        Action::Help => help(&session.game),
+        Action::Quit => vec![text.reader_bye.to_string()],
+    }
+}
+
+pub fn run(
+    session: &mut Session,
+    input: impl BufRead,
+    mut output: impl Write,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let variant = match session.game.rules.variant {
+        Variant::Klondike => "Klondike",
+        Variant::Canfield => "Canfield",
+    };
+    let text = i18n::text();
+    writeln!(
+        output,
+        "{}",
+        fill(
+            text.reader_welcome,
+            &[
+                &variant,
+                &session.game.rules.draw_count,
+                &session.replay.seed
+            ],
+        )
+    )?;
+    for line in describe_game(&session.game) {
+        writeln!(output, "{}", line)?;
+    }
+    write!(output, "> ")?;
+    output.flush()?;
+
+    for line in input.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            write!(output, "> ")?;
+            output.flush()?;
+            continue;
+        }
+
+        let lines = match parse_action(&line, &session.game) {
+            Ok(Action::Quit) => {
+                writeln!(output, "{}", text.reader_bye)?;
+                return Ok(());
+            }
+            Ok(action) => respond(session, action),
+            Err(message) => vec![message],
+        };
+        for line in lines {
+            writeln!(output, "{}", line)?;
+        }
+        write!(output, "> ")?;
+        output.flush()?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        i18n::{ENGLISH, FINNISH, GERMAN},
+        replay::Replay,
+        rules::Rules,
+    };
+
+    fn mock_session(position: &str) -> Session {
+        let mut replay = Replay::new(0, &Rules::klondike());
+        replay.position = Some(position.to_string());
+        Session::new(replay, false)
+    }
+
+    #[test]
+    fn test_describe() {
+        let session = mock_session(
+            "Kd|7h/|Jc9s/-/-/-/-/- \
+            AhAsAcAd2h2s2c2d3h3s3c3d4h4s4c4d5h5s5c5d6h6s6c6d7s7c7d8h8s8c8d9h9c9dThTsTcTdJhJsJdQhQsQcQdKhKsKc \
+            - -/-/-/- - A 0",
+        );
+        let lines = describe_game(&session.game);
+
+        assert_eq!(lines.len(), 13);
+        assert_eq!(lines[0], "Stock: 48 cards");
+        assert_eq!(lines[1], "Waste: empty");
+        assert_eq!(lines[2], "Foundation 1: empty");
+        assert_eq!(lines[6], "Column 1: 1 hidden, 7 of hearts");
+        assert_eq!(lines[7], "Column 2: jack of clubs, 9 of spades");
+        assert_eq!(lines[8], "Column 3: empty");
+    }
+
+    #[test]
+    fn test_card_names_follow_language() {
+        let card = Card::new(Suit::Hearts, 12);
+
+        assert_eq!(card_name(&ENGLISH, card), "queen of hearts");
+        assert_eq!(card_name(&GERMAN, card), "Herz Dame");
+        assert_eq!(card_name(&FINNISH, Card::new(Suit::Spades, 1)), "pata ässä");
+        assert_eq!(capitalize("ässä"), "Ässä");
+    }
+
+    #[test]
+    fn test_parse_action() {
+        let game = Game::new(Rules::klondike(), 1);
+
+        assert_eq!(parse_action(" L ", &game), Ok(Action::Look));
+        assert_eq!(parse_action("d", &game), Ok(Action::Play(Move::Draw)));
+        assert_eq!(
+            parse_action("3", &game),
+            Ok(Action::Read(Location::Tableau(2)))
+        );
+        assert_eq!(
+            parse_action("f2", &game),
+            Ok(Action::Read(Location::Foundation(1)))
+        );
+        assert_eq!(
+            parse_action("w 3", &game),
+            Ok(Action::Play(Move::WasteToTableau(2)))
+        );
+        assert!(parse_action("9", &game).is_err());
+        assert!(parse_action("r 3", &game).is_err());
+        assert!(parse_action("s 3", &game).is_err());
+        assert!(parse_action("w f", &game).is_err());
+    }
+
+    #[test]
+    fn test_announce() {
+        let mut session = mock_session(
+            "Kd|7h/8c|Ah/-/-/-/-/- \
+            2h2s2c2d3h3s3c3d4h4s4c4d5h5s5c5d6h6s6c6d7s7c7d8h8s8d9h9c9d9sThTsTcTdJhJsJdJcQhQsQcQdKhKsKc \
+            - As/-/Ac/Ad - A 0",
+        );
+
+        let action = parse_action("2 f", &session.game).unwrap();
+        let lines = respond(&mut session, action);
+        assert_eq!(
+            lines,
+            ["moved ace of hearts to foundation 2, revealed 8 of clubs"]
+        );
+
+        let action = parse_action("1 2", &session.game).unwrap();
+        let lines = respond(&mut session, action);
+        assert_eq!(
+            lines,
+            ["moved 7 of hearts to column 2, revealed king of diamonds"]
+        );
+
+        let lines = respond(&mut session, Action::Undo);
+        assert_eq!(lines, ["Took back 7 of hearts from column 1 to column 2"]);
+
+        let lines = respond(&mut session, Action::Play(Move::Draw));
+        assert_eq!(lines, ["drew king of clubs"]);
+    }
+
+    #[test]
+    fn test_run() {
+        let mut session = Session::new(Replay::new(1, &Rules::klondike()), false);
+        let input = "h\n\nbogus\n7\nq\nl\n";
+        let mut output = vec![];
+
+        run(&mut session, input.as_bytes(), &mut output).unwrap();
+        let text = String::from_utf8(output).unwrap();
+
+        assert!(text.starts_with("Tersoli, "));
+        assert!(text.contains("> I don't know 'bogus', type h for help\n"));
+        assert!(text.contains("> Column 7: 6 hidden, jack of spades\n"));
+        assert!(text.ends_with("> Bye\n"));
+    }
+}
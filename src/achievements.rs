@@ -0,0 +1,230 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    events::GameEvent,
+    i18n::{self, fill},
+    rules::Variant,
+    storage::{data_path, read_json, write_json},
+};
+
+pub struct Achievement {
+    pub id: &'static str,
+}
+
+impl Achievement {
+    fn index(&self) -> usize {
+        ACHIEVEMENTS
+            .iter()
+            .position(|achievement| achievement.id == self.id)
+            .unwrap_or_default()
+    }
+
+    pub fn title(&self) -> &'static str {
+        i18n::text().achievement_titles[self.index()]
+    }
+
+    pub fn description(&self) -> &'static str {
+        i18n::text().achievement_descriptions[self.index()]
+    }
+}
+
+pub const ACHIEVEMENTS: [Achievement; 9] = [
+    Achievement { id: "first_win" },
+    Achievement { id: "single_pass" },
+    Achievement { id: "speed_run" },
+    Achievement { id: "no_regrets" },
+    Achievement { id: "straight_up" },
+    Achievement { id: "economical" },
+    Achievement { id: "clear_sight" },
+    Achievement { id: "on_a_roll" },
+    Achievement { id: "demon_tamer" },
+];
+
+pub fn find(id: &str) -> Option<&'static Achievement> {
+    ACHIEVEMENTS.iter().find(|achievement| achievement.id == id)
+}
+
+// What the tracker needs to know about the game beyond its events
+pub struct Context {
+    pub variant: Variant,
+    pub secs: u64,
+    pub moves: u32,
+    pub streak: u32,
+}
+
+// Follows a single game's events and reports achievements as soon as they are earned
+#[derive(Default)]
+pub struct Tracker {
+    recycled: bool,
+    undone: bool,
+    took_back: bool,
+}
+
+impl Tracker {
+    pub fn observe(&mut self, event: GameEvent, context: &Context) -> Vec<&'static str> {
+        match event {
+            GameEvent::Recycled => self.recycled = true,
+            GameEvent::Undone => self.undone = true,
+            GameEvent::FromFoundation(_) => self.took_back = true,
+            GameEvent::Revealed {
+                hidden_left: 0,
+                stock_left,
+                ..
+            } if stock_left > 0 => return vec!["clear_sight"],
+            GameEvent::Won => return self.on_win(context),
+            _ => {}
+        }
+        Vec::new()
+    }
+
+    fn on_win(&self, context: &Context) -> Vec<&'static str> {
+        [
+            ("first_win", true),
+            ("single_pass", !self.recycled),
+            ("speed_run", context.secs < 180),
+            ("no_regrets", !self.undone),
+            ("straight_up", !self.took_back),
+            ("economical", context.moves < 100),
+            ("on_a_roll", context.streak >= 10),
+            ("demon_tamer", context.variant == Variant::Canfield),
+        ]
+        .into_iter()
+        .filter_map(|(id, earned)| earned.then_some(id))
+        .collect()
+    }
+}
+
+// Unlocked achievement ids with the date they were first earned
+#[derive(Serialize, Deserialize, Default)]
+pub struct Achievements {
+    pub unlocked: BTreeMap<String, String>,
+}
+
+impl Achievements {
+    pub fn path() -> PathBuf {
+        data_path("achievements.json")
+    }
+
+    pub fn load() -> Self {
+        read_json(&Self::path())
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        write_json(&Self::path(), self)
+    }
+
+    // Returns false when the achievement was already unlocked
+    pub fn unlock(&mut self, id: &str, date: &str) -> bool {
+        if self.unlocked.contains_key(id) {
+            return false;
+        }
+        self.unlocked.insert(id.to_string(), date.to_string());
+        true
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = fill(
+            i18n::text().achievements_title,
+            &[&self.unlocked.len(), &ACHIEVEMENTS.len()],
+        );
+        text.push('\n');
+        for achievement in ACHIEVEMENTS.iter() {
+            let status = match self.unlocked.get(achievement.id) {
+                Some(date) => format!("[x] {}", date),
+                None => "[ ]".to_string(),
+            };
+            text.push_str(&format!(
+                "  {:<14} {:<14} {}\n",
+                status,
+                achievement.title(),
+                achievement.description()
+            ));
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{card::Card, suit::Suit};
+
+    fn mock_context(secs: u64, moves: u32, streak: u32) -> Context {
+        Context {
+            variant: Variant::Klondike,
+            secs,
+            moves,
+            streak,
+        }
+    }
+
+    #[test]
+    fn test_clean_fast_win() {
+        let mut tracker = Tracker::default();
+        let context = mock_context(120, 90, 10);
+
+        assert!(tracker.observe(GameEvent::Drew(1), &context).is_empty());
+        assert_eq!(
+            tracker.observe(GameEvent::Won, &context),
+            [
+                "first_win",
+                "single_pass",
+                "speed_run",
+                "no_regrets",
+                "straight_up",
+                "economical",
+                "on_a_roll"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_spoiled_win() {
+        let mut tracker = Tracker::default();
+        let context = mock_context(600, 150, 1);
+
+        for event in [
+            GameEvent::Recycled,
+            GameEvent::Undone,
+            GameEvent::FromFoundation(Card::new(Suit::Hearts, 1)),
+        ] {
+            tracker.observe(event, &context);
+        }
+        assert_eq!(tracker.observe(GameEvent::Won, &context), ["first_win"]);
+    }
+
+    #[test]
+    fn test_clear_sight() {
+        let mut tracker = Tracker::default();
+        let context = mock_context(0, 0, 0);
+        let revealed = |hidden_left, stock_left| GameEvent::Revealed {
+            column: 0,
+            hidden_left,
+            stock_left,
+        };
+
+        assert!(tracker.observe(revealed(3, 10), &context).is_empty());
+        assert!(tracker.observe(revealed(0, 0), &context).is_empty());
+        assert_eq!(tracker.observe(revealed(0, 1), &context), ["clear_sight"]);
+    }
+
+    #[test]
+    fn test_unlock_and_text() {
+        let mut achievements = Achievements::default();
+
+        assert!(achievements.unlock("first_win", "2026-10-18"));
+        assert!(!achievements.unlock("first_win", "2026-10-19"));
+        assert_eq!(achievements.unlocked["first_win"], "2026-10-18");
+        assert_eq!(
+            find("first_win").map(|found| found.title()),
+            Some("First win")
+        );
+
+        let text = achievements.to_text();
+        assert!(text.starts_with("Achievements (1/9)\n"));
+        assert!(text.contains("[x] 2026-10-18 First win"));
+        assert!(text.contains("[ ]            Speed run"));
+    }
+}
@@ -0,0 +1,266 @@
+use std::{
+    fs,
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+    sync::{
+        Mutex,
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+    },
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    game::Game,
+    rules::Rules,
+    solver::{Outcome, solve},
+    storage::data_path,
+};
+
+pub struct DealResult {
+    pub seed: u64,
+    pub outcome: Outcome,
+    pub millis: u64,
+}
+
+#[derive(Default, PartialEq, Debug)]
+pub struct Summary {
+    pub deals: usize,
+    pub solvable: usize,
+    pub unsolvable: usize,
+    pub unknown: usize,
+    // Moves in all the solutions found, which aren't necessarily the shortest ones
+    pub solution_moves: usize,
+}
+
+impl Summary {
+    pub fn new(results: &[DealResult]) -> Self {
+        let mut summary = Self {
+            deals: results.len(),
+            ..Self::default()
+        };
+        for result in results {
+            match &result.outcome {
+                Outcome::Solved(moves) => {
+                    summary.solvable += 1;
+                    summary.solution_moves += moves.len();
+                }
+                Outcome::Unsolvable => summary.unsolvable += 1,
+                Outcome::Unknown => summary.unknown += 1,
+            }
+        }
+        summary
+    }
+
+    fn percent(&self, count: usize) -> f64 {
+        match self.deals {
+            0 => 0.0,
+            deals => count as f64 * 100.0 / deals as f64,
+        }
+    }
+
+    pub fn to_text(&self) -> String {
+        let mean = match self.solvable {
+            0 => "-".to_string(),
+            solvable => format!("{:.1}", self.solution_moves as f64 / solvable as f64),
+        };
+        format!(
+            "Deals: {}\n  Solvable: {} ({:.1}%)  Unsolvable: {} ({:.1}%)  Unknown: {} ({:.1}%)\n  Mean solution length: {} moves\n",
+            self.deals,
+            self.solvable,
+            self.percent(self.solvable),
+            self.unsolvable,
+            self.percent(self.unsolvable),
+            self.unknown,
+            self.percent(self.unknown),
+            mean
+        )
+    }
+}
+
+// One line per seed, solutions in the move notation with moves separated by spaces
+pub fn to_csv(results: &[DealResult]) -> String {
+    let mut csv = "seed,result,moves,millis,solution\n".to_string();
+    for result in results {
+        let (name, moves, solution) = match &result.outcome {
+            Outcome::Solved(moves) => {
+                let solution: Vec<String> = moves.iter().map(|mv| mv.to_string()).collect();
+                ("solvable", moves.len().to_string(), solution.join(" "))
+            }
+            Outcome::Unsolvable => ("unsolvable", String::new(), String::new()),
+            Outcome::Unknown => ("unknown", String::new(), String::new()),
+        };
+        csv.push_str(&format!(
+            "{},{},{},{},{}\n",
+            result.seed, name, moves, result.millis, solution
+        ));
+    }
+    csv
+}
+
+// Every core takes the next seed that nobody has started on yet. Progress is called with the
+// number of deals done after each one
+pub fn analyze(
+    rules: Rules,
+    seeds: RangeInclusive<u64>,
+    time_limit: Duration,
+    progress: impl Fn(usize) + Sync,
+) -> Vec<DealResult> {
+    let next = AtomicU64::new(*seeds.start());
+    let done = AtomicUsize::new(0);
+    let results = Mutex::new(Vec::new());
+    let workers = thread::available_parallelism().map_or(1, |count| count.get());
+
+    thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| {
+                loop {
+                    let seed = next.fetch_add(1, Ordering::Relaxed);
+                    if !seeds.contains(&seed) {
+                        break;
+                    }
+
+                    let started = Instant::now();
+                    let outcome = solve(&Game::new(rules, seed), started + time_limit);
+                    let result = DealResult {
+                        seed,
+                        outcome,
+                        millis: started.elapsed().as_millis() as u64,
+                    };
+                    if let Ok(mut results) = results.lock() {
+                        results.push(result);
+                    }
+                    progress(done.fetch_add(1, Ordering::Relaxed) + 1);
+                }
+            });
+        }
+    });
+
+    let mut results = results.into_inner().unwrap_or_default();
+    results.sort_by_key(|result| result.seed);
+    results
+}
+
+pub fn run(
+    rules: Rules,
+    seeds: RangeInclusive<u64>,
+    time_limit: Duration,
+    csv: Option<PathBuf>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    println!(
+        "Analyzing seeds {} to {} with {}, {:.1}s per deal",
+        seeds.start(),
+        seeds.end(),
+        rules.spec(),
+        time_limit.as_secs_f64()
+    );
+
+    let first = *seeds.start();
+    let total = seeds.clone().count();
+    let results = analyze(rules, seeds, time_limit, |done| {
+        eprint!("\r{}/{} deals", done, total);
+    });
+    eprintln!();
+    print!("{}", Summary::new(&results).to_text());
+
+    let path = csv.unwrap_or_else(|| {
+        let started = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        data_path("analysis").join(format!("{}-{}.csv", started, first))
+    });
+    write_csv(&path, &results)?;
+    println!("Per-seed results: {}", path.display());
+    Ok(())
+}
+
+fn write_csv(path: &Path, results: &[DealResult]) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        fs::create_dir_all(parent)?;
+    }
+
+    fs::write(path, to_csv(results))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::moves::Move;
+
+    fn mock_results() -> Vec<DealResult> {
+        vec![
+            DealResult {
+                seed: 1,
+                outcome: Outcome::Solved(vec![Move::Draw, Move::WasteToFoundation(0)]),
+                millis: 12,
+            },
+            DealResult {
+                seed: 2,
+                outcome: Outcome::Unsolvable,
+                millis: 3,
+            },
+            DealResult {
+                seed: 3,
+                outcome: Outcome::Unknown,
+                millis: 1000,
+            },
+            DealResult {
+                seed: 4,
+                outcome: Outcome::Solved(vec![Move::Draw; 4]),
+                millis: 20,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_summary() {
+        let summary = Summary::new(&mock_results());
+
+        assert_eq!(summary.solvable, 2);
+        assert_eq!(summary.solution_moves, 6);
+        let text = summary.to_text();
+        assert!(text.contains("Solvable: 2 (50.0%)"));
+        assert!(text.contains("Unknown: 1 (25.0%)"));
+        assert!(text.contains("Mean solution length: 3.0 moves"));
+        assert!(Summary::new(&[]).to_text().contains("length: - moves"));
+    }
+
+    #[test]
+    fn test_to_csv() {
+        let csv = to_csv(&mock_results());
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(lines[0], "seed,result,moves,millis,solution");
+        assert_eq!(lines[1], "1,solvable,2,12,S>W W>F1");
+        assert_eq!(lines[2], "2,unsolvable,,3,");
+        assert_eq!(lines.len(), 5);
+    }
+
+    #[test]
+    fn test_analyze() {
+        let done = AtomicUsize::new(0);
+        let results = analyze(
+            Rules::klondike(),
+            3..=6,
+            Duration::from_millis(50),
+            |count| {
+                done.fetch_max(count, Ordering::Relaxed);
+            },
+        );
+        assert_eq!(done.into_inner(), 4);
+
+        let seeds: Vec<u64> = results.iter().map(|result| result.seed).collect();
+        assert_eq!(seeds, [3, 4, 5, 6]);
+        for result in results {
+            if let Outcome::Solved(moves) = result.outcome {
+                let mut game = Game::new(Rules::klondike(), result.seed);
+                assert!(moves.iter().all(|mv| game.apply(*mv)));
+                assert!(game.is_won());
+            }
+        }
+    }
+}
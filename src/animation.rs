@@ -0,0 +1,386 @@
+use std::collections::VecDeque;
+
+use crate::{
+    card::Card, config::Config, events::GameEvent, game::Game, location::Location, moves::Move,
+};
+
+// Frame counts at normal speed, the app ticks about 60 times a second
+const SLIDE_FRAMES: f64 = 12.0;
+const FLIP_FRAMES: f64 = 10.0;
+const CASCADE_INTERVAL: f64 = 8.0;
+const CASCADE_FLIGHT: f64 = 240.0;
+const GRAVITY: f64 = 0.12;
+const BOUNCE: f64 = 0.8;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Effect {
+    Slide {
+        card: Card,
+        from: Location,
+        to: Location,
+        count: usize,
+        // Cards kept off the destination until the slide lands, a draw hides all it dealt
+        hidden: usize,
+    },
+    Flip {
+        card: Card,
+        column: usize,
+    },
+}
+
+pub struct Animation {
+    pub effect: Effect,
+    pub frame: u32,
+    pub frames: u32,
+}
+
+impl Animation {
+    pub fn progress(&self) -> f64 {
+        self.frame as f64 / self.frames as f64
+    }
+}
+
+// Cards bounce off the foundations one at a time when a game is won
+pub struct Cascade {
+    pub cards: Vec<(Card, usize)>,
+    pub time: f64,
+}
+
+impl Cascade {
+    pub fn new(game: &Game) -> Self {
+        let piles = &game.foundation.cards;
+        let depth = piles.iter().map(Vec::len).max().unwrap_or(0);
+
+        // Top cards of every pile first, like dealing them back off
+        let mut cards = Vec::new();
+        for level in 0..depth {
+            for (slot, pile) in piles.iter().enumerate() {
+                if let Some(Some(card)) = pile.len().checked_sub(level + 1).map(|i| pile[i]) {
+                    cards.push((card, slot));
+                }
+            }
+        }
+
+        Self { cards, time: 0.0 }
+    }
+
+    // How long the card at index has been in flight, if it has been launched yet
+    pub fn flight_time(&self, index: usize) -> Option<f64> {
+        let launched = self.time - index as f64 * CASCADE_INTERVAL;
+        (launched >= 0.0).then_some(launched)
+    }
+
+    pub fn velocity(index: usize) -> (f64, f64) {
+        let direction = if index.is_multiple_of(2) { -1.0 } else { 1.0 };
+        (
+            direction * (0.4 + (index * 7 % 5) as f64 * 0.15),
+            -(0.3 + (index * 3 % 4) as f64 * 0.2),
+        )
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.time > self.cards.len() as f64 * CASCADE_INTERVAL + CASCADE_FLIGHT
+    }
+}
+
+// Every cell the card passes through, so it leaves the classic trail behind
+pub fn cascade_path(
+    start: (f64, f64),
+    velocity: (f64, f64),
+    size: (f64, f64),
+    time: f64,
+) -> Vec<(f64, f64)> {
+    let (mut x, mut y) = start;
+    let (vx, mut vy) = velocity;
+    let floor = size.1 - 1.0;
+    let mut path = vec![(x, y)];
+
+    for _ in 0..time as u32 {
+        x += vx;
+        y += vy;
+        vy += GRAVITY;
+        if y > floor {
+            y = floor;
+            vy = -vy * BOUNCE;
+        }
+        if x < 0.0 || x >= size.0 {
+            break;
+        }
+        if path.last() != Some(&(x.round(), y.round())) {
+            path.push((x.round(), y.round()));
+        }
+    }
+
+    path
+}
+
+pub struct Animator {
+    pub enabled: bool,
+    pub speed: f64,
+    pub queue: VecDeque<Animation>,
+    pub cascade: Option<Cascade>,
+}
+
+impl Animator {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            enabled: config.animations,
+            speed: config.animation_speed.max(0.1),
+            queue: VecDeque::new(),
+            cascade: None,
+        }
+    }
+
+    fn frames(&self, base: f64) -> u32 {
+        (base / self.speed).round().max(1.0) as u32
+    }
+
+    fn push(&mut self, effect: Effect, base: f64) {
+        self.queue.push_back(Animation {
+            effect,
+            frame: 0,
+            frames: self.frames(base),
+        });
+    }
+
+    // Events are handled after the move, so cards are looked up where they landed
+    pub fn on_event(&mut self, event: GameEvent, game: &Game) {
+        if !self.enabled {
+            return;
+        }
+
+        match event {
+            GameEvent::Drew(drawn) => {
+                if let Some(card) = game.waste.get_top_card() {
+                    let slide = Effect::Slide {
+                        card,
+                        from: Location::Stock,
+                        to: Location::Waste,
+                        count: 1,
+                        hidden: drawn,
+                    };
+                    self.push(slide, SLIDE_FRAMES);
+                }
+            }
+            GameEvent::Moved(Move::Draw) => {}
+            GameEvent::Moved(mv) => {
+                let (from, to) = (mv.source(), mv.destination());
+                let count = match mv {
+                    Move::TableauToTableau(_, _, count) => count,
+                    _ => 1,
+                };
+                let card = match to {
+                    Location::Tableau(column) => game.tableau.cards[column]
+                        .len()
+                        .checked_sub(count)
+                        .map(|index| game.tableau.cards[column][index]),
+                    _ => game.get_top_card(to),
+                };
+
+                if let Some(card) = card {
+                    let slide = Effect::Slide {
+                        card,
+                        from,
+                        to,
+                        count,
+                        hidden: count,
+                    };
+                    self.push(slide, SLIDE_FRAMES);
+                }
+            }
+            GameEvent::Revealed { column, .. } => {
+                let cutoff = game.tableau.cutoffs[column] as usize;
+                if let Some(card) = game.tableau.cards[column].get(cutoff) {
+                    let flip = Effect::Flip {
+                        card: *card,
+                        column,
+                    };
+                    self.push(flip, FLIP_FRAMES);
+                }
+            }
+            GameEvent::Won => self.cascade = Some(Cascade::new(game)),
+            _ => {}
+        }
+    }
+
+    pub fn on_tick(&mut self) {
+        if let Some(animation) = self.queue.front_mut() {
+            animation.frame += 1;
+            if animation.frame >= animation.frames {
+                self.queue.pop_front();
+            }
+        }
+
+        if let Some(cascade) = &mut self.cascade {
+            cascade.time += self.speed;
+        }
+        self.cascade.take_if(|cascade| cascade.is_finished());
+    }
+
+    // Input waits while cards are moving, the win cascade doesn't hold anything up
+    pub fn is_busy(&self) -> bool {
+        !self.queue.is_empty()
+    }
+
+    pub fn current(&self) -> Option<&Animation> {
+        self.queue.front()
+    }
+
+    // Takes cards that are still on their way off a copy of the game, so they aren't drawn twice
+    pub fn hide(&self, game: &mut Game) {
+        if let Some(Animation {
+            effect: Effect::Slide { to, hidden, .. },
+            ..
+        }) = self.current()
+        {
+            match *to {
+                Location::Tableau(column) => {
+                    let cards = &mut game.tableau.cards[column];
+                    cards.truncate(cards.len().saturating_sub(*hidden));
+                }
+                Location::Waste => {
+                    let cards = &mut game.waste.cards;
+                    cards.truncate(cards.len().saturating_sub(*hidden));
+                }
+                Location::Foundation(_) => game.foundation.remove_card(*to),
+                _ => {}
+            }
+        }
+
+        for animation in self.queue.iter() {
+            if let Effect::Flip { column, .. } = animation.effect {
+                let len = game.tableau.cards[column].len() as u8;
+                let cutoff = &mut game.tableau.cutoffs[column];
+                *cutoff = (*cutoff + 1).min(len);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{rules::Rules, suit::Suit};
+
+    fn mock_animator(animations: bool) -> Animator {
+        Animator::new(&Config {
+            animations,
+            animation_speed: 2.0,
+            ..Config::default()
+        })
+    }
+
+    fn mock_won_game() -> Game {
+        Game::from_position(Rules::klondike(), "-/-/-/-/-/-/- - - Ks/Kh/Kc/Kd - A 0").unwrap()
+    }
+
+    #[test]
+    fn test_slide_and_flip() {
+        let mut game = Game::new(Rules::klondike(), 1);
+        game.tableau.cards[1] = vec![Card::new(Suit::Spades, 9), Card::new(Suit::Hearts, 1)];
+        game.tableau.cutoffs[1] = 1;
+        game.apply(Move::TableauToFoundation(1, 0));
+
+        let mut animator = mock_animator(true);
+        for event in game.events.drain() {
+            animator.on_event(event, &game);
+        }
+
+        assert!(animator.is_busy());
+        assert_eq!(animator.queue.len(), 2);
+        assert_eq!(
+            animator.current().map(|animation| animation.effect),
+            Some(Effect::Slide {
+                card: Card::new(Suit::Hearts, 1),
+                from: Location::Tableau(1),
+                to: Location::Foundation(0),
+                count: 1,
+                hidden: 1
+            })
+        );
+
+        let mut shown = game.clone();
+        animator.hide(&mut shown);
+        assert!(
+            shown
+                .foundation
+                .get_top_card(Location::Foundation(0))
+                .is_none()
+        );
+        assert_eq!(shown.tableau.cutoffs[1], 1);
+
+        for _ in 0..6 {
+            animator.on_tick();
+        }
+        assert!(matches!(
+            animator.current().map(|animation| animation.effect),
+            Some(Effect::Flip { column: 1, .. })
+        ));
+        for _ in 0..5 {
+            animator.on_tick();
+        }
+        assert!(!animator.is_busy());
+    }
+
+    #[test]
+    fn test_hide_whole_draw() {
+        let mut game = Game::new(Rules::klondike_by_threes(), 1);
+        game.events.drain();
+        game.apply(Move::Draw);
+        assert_eq!(game.waste.cards.len(), 3);
+
+        let mut animator = mock_animator(true);
+        for event in game.events.drain() {
+            animator.on_event(event, &game);
+        }
+        assert_eq!(animator.queue.len(), 1);
+
+        let mut shown = game.clone();
+        animator.hide(&mut shown);
+        assert!(shown.waste.cards.is_empty());
+
+        for _ in 0..6 {
+            animator.on_tick();
+        }
+        let mut shown = game.clone();
+        animator.hide(&mut shown);
+        assert_eq!(shown.waste.cards.len(), 3);
+    }
+
+    #[test]
+    fn test_disabled() {
+        let mut game = Game::new(Rules::klondike(), 1);
+        game.apply(Move::Draw);
+
+        let mut animator = mock_animator(false);
+        for event in game.events.drain() {
+            animator.on_event(event, &game);
+        }
+        assert!(!animator.is_busy());
+    }
+
+    #[test]
+    fn test_cascade() {
+        let game = mock_won_game();
+        let mut cascade = Cascade::new(&game);
+
+        assert_eq!(cascade.cards.len(), 52);
+        assert_eq!(cascade.cards[0], (Card::new(Suit::Spades, 13), 0));
+        assert_eq!(cascade.cards[4].0.rank, 12);
+        assert_eq!(cascade.flight_time(1), None);
+
+        cascade.time = 10.0;
+        assert_eq!(cascade.flight_time(1), Some(2.0));
+        assert!(!cascade.is_finished());
+    }
+
+    #[test]
+    fn test_cascade_path() {
+        let path = cascade_path((40.0, 2.0), (-1.0, 0.0), (80.0, 20.0), 500.0);
+
+        // It leaves the screen on the left well before the time runs out
+        assert!(path.len() > 30 && path.len() < 60);
+        assert!(path.iter().all(|&(x, y)| x >= 0.0 && y <= 19.0));
+        assert!(path.iter().any(|&(_, y)| y == 19.0));
+    }
+}
@@ -0,0 +1,139 @@
+use std::env;
+
+use ratatui::{buffer::Buffer, style::Color};
+
+// What the terminal can show, detected from the environment unless a flag says otherwise
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Capabilities {
+    pub unicode: bool,
+    pub color: bool,
+}
+
+impl Default for Capabilities {
+    fn default() -> Self {
+        Self {
+            unicode: true,
+            color: true,
+        }
+    }
+}
+
+impl Capabilities {
+    pub fn detect(unicode: Option<bool>, color: Option<bool>) -> Self {
+        Self::from_env(|name| env::var(name).ok(), unicode, color)
+    }
+
+    // Unicode needs a UTF-8 locale, color is off with NO_COLOR (https://no-color.org) or a dumb terminal
+    pub fn from_env(
+        var: impl Fn(&str) -> Option<String>,
+        unicode: Option<bool>,
+        color: Option<bool>,
+    ) -> Self {
+        let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+            .into_iter()
+            .find_map(|name| var(name).filter(|value| !value.is_empty()));
+        let utf8 = match locale {
+            Some(locale) => {
+                let locale = locale.to_lowercase();
+                locale.contains("utf-8") || locale.contains("utf8")
+            }
+            None => cfg!(windows),
+        };
+        let dumb = var("TERM").is_some_and(|term| term == "dumb");
+        let no_color = var("NO_COLOR").is_some_and(|value| !value.is_empty());
+
+        Self {
+            unicode: unicode.unwrap_or(utf8 && !dumb),
+            color: color.unwrap_or(!no_color && !dumb),
+        }
+    }
+
+    // Rewrites a drawn frame for terminals that can't show all of it
+    pub fn apply(&self, buffer: &mut Buffer) {
+        for cell in buffer.content.iter_mut() {
+            if !self.unicode
+                && let Some(ascii) = to_ascii(cell.symbol())
+            {
+                cell.set_symbol(ascii);
+            }
+            if !self.color {
+                cell.set_fg(Color::Reset).set_bg(Color::Reset);
+            }
+        }
+    }
+}
+
+// Every non-ASCII symbol the game draws, box drawing included
+pub fn to_ascii(symbol: &str) -> Option<&'static str> {
+    let ascii = match symbol {
+        "♠" => "S",
+        "♥" => "H",
+        "♣" => "C",
+        "♦" => "D",
+        "─" | "━" | "═" => "-",
+        "│" | "┃" | "║" => "|",
+        "┌" | "┐" | "└" | "┘" | "╭" | "╮" | "╰" | "╯" | "╔" | "╗" | "╚" | "╝" | "├" | "┤" | "┬"
+        | "┴" | "┼" => "+",
+        "░" | "▒" | "▓" | "█" => "#",
+        "·" | "•" => ".",
+        "↑" => "^",
+        "↓" => "v",
+        "←" | "◂" => "<",
+        "→" | "▸" => ">",
+        "…" => "~",
+        _ => return None,
+    };
+    Some(ascii)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::{layout::Rect, style::Style};
+
+    fn mock_env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: Vec<(String, String)> = vars
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        move |name| {
+            vars.iter()
+                .find(|(var, _)| var == name)
+                .map(|(_, value)| value.clone())
+        }
+    }
+
+    #[test]
+    fn test_detect() {
+        let detected = Capabilities::from_env(mock_env(&[("LANG", "en_US.UTF-8")]), None, None);
+        assert_eq!(detected, Capabilities::default());
+
+        let detected = Capabilities::from_env(
+            mock_env(&[("LANG", "en_US.UTF-8"), ("LC_ALL", "C"), ("NO_COLOR", "1")]),
+            None,
+            None,
+        );
+        assert!(!detected.unicode && !detected.color);
+
+        // An empty NO_COLOR doesn't count, and flags win over the environment
+        let detected = Capabilities::from_env(
+            mock_env(&[("TERM", "dumb"), ("NO_COLOR", "")]),
+            Some(true),
+            None,
+        );
+        assert!(detected.unicode && !detected.color);
+    }
+
+    #[test]
+    fn test_apply() {
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 6, 1));
+        buffer.set_string(0, 0, "╭10♥─", Style::default().fg(Color::LightRed));
+
+        Capabilities {
+            unicode: false,
+            color: false,
+        }
+        .apply(&mut buffer);
+        assert_eq!(buffer, Buffer::with_lines(["+10H- "]));
+    }
+}
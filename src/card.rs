@@ -1,6 +1,6 @@
 use crate::suit::Suit;
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub struct Card {
     pub suit: Suit,
     pub rank: u8,
@@ -0,0 +1,422 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    style::Style,
+    symbols::border,
+    text::Line,
+    widgets::{Block, Widget},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{card::Card, i18n, suit::Suit};
+
+const MAX_CARD_WIDTH: u16 = 15;
+const LARGE_CARD_WIDTH: u16 = 13;
+const MIN_CARD_WIDTH: u16 = 5;
+const MIN_CARD_HEIGHT: u16 = 3;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Face {
+    Up(Card),
+    Down,
+    Empty,
+}
+
+// Border of normal cards, picked in the settings
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Eq, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum CardStyle {
+    #[default]
+    Rounded,
+    Square,
+    Double,
+    Thick,
+}
+
+pub const CARD_STYLES: [CardStyle; 4] = [
+    CardStyle::Rounded,
+    CardStyle::Square,
+    CardStyle::Double,
+    CardStyle::Thick,
+];
+
+impl CardStyle {
+    pub fn name(&self) -> &'static str {
+        match self {
+            CardStyle::Rounded => "rounded",
+            CardStyle::Square => "square",
+            CardStyle::Double => "double",
+            CardStyle::Thick => "thick",
+        }
+    }
+
+    fn border_set(&self) -> border::Set {
+        match self {
+            CardStyle::Rounded => border::ROUNDED,
+            CardStyle::Square => border::PLAIN,
+            CardStyle::Double => border::DOUBLE,
+            CardStyle::Thick => border::THICK,
+        }
+    }
+}
+
+// Normal cards are bordered rectangles, compact ones a single line of text for small terminals
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum CardSize {
+    Compact,
+    Normal { width: u16, height: u16 },
+}
+
+impl CardSize {
+    // Compact cards take the whole row
+    pub fn width_in(&self, area: Rect) -> u16 {
+        match self {
+            CardSize::Compact => area.width,
+            CardSize::Normal { width, .. } => (*width).min(area.width),
+        }
+    }
+
+    pub fn height(&self) -> u16 {
+        match self {
+            CardSize::Compact => 1,
+            CardSize::Normal { height, .. } => *height,
+        }
+    }
+
+    // Cards fill the pile's width up to a point, and keep roughly a card's proportions
+    pub fn fit(area: Rect) -> Self {
+        let width = area.width.min(MAX_CARD_WIDTH);
+        let height = (width / 2 + 1).min(area.height);
+        if width < MIN_CARD_WIDTH || height < MIN_CARD_HEIGHT {
+            return CardSize::Compact;
+        }
+        CardSize::Normal { width, height }
+    }
+}
+
+pub fn suit_symbol(suit: Suit) -> &'static str {
+    match suit {
+        Suit::Spades => "♠",
+        Suit::Hearts => "♥",
+        Suit::Clubs => "♣",
+        Suit::Diamonds => "♦",
+    }
+}
+
+pub fn index_label(card: Card) -> String {
+    format!(
+        "{}{}",
+        i18n::text().rank_label(card.rank),
+        suit_symbol(card.suit)
+    )
+}
+
+pub fn get_card(suit: Suit, rank: u8) -> String {
+    let suit_str = suit_symbol(suit);
+
+    let rank_str = match rank {
+        0 => "",
+        rank => i18n::text().rank(rank),
+    };
+
+    format!("{} {}", rank_str, suit_str)
+}
+
+pub struct CardWidget {
+    pub face: Face,
+    pub size: CardSize,
+    pub style: Style,
+    pub border_style: Style,
+    pub back_style: Style,
+    pub card_style: CardStyle,
+}
+
+impl Widget for CardWidget {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let area = area.intersection(buf.area);
+        if area.is_empty() {
+            return;
+        }
+
+        if self.size == CardSize::Compact {
+            // Spelled out when the pile is wide enough
+            let text = match self.face {
+                Face::Up(card) => match get_card(card.suit, card.rank) {
+                    name if name.chars().count() <= area.width as usize => name,
+                    _ => index_label(card),
+                },
+                Face::Down => "░░░".to_string(),
+                Face::Empty => "···".to_string(),
+            };
+            let style = match self.face {
+                Face::Up(_) => self.style,
+                Face::Down => self.back_style,
+                Face::Empty => self.border_style,
+            };
+            buf.set_stringn(area.x, area.y, text, area.width as usize, style);
+            return;
+        }
+
+        // Indices sit in the top left and bottom right corners of the border,
+        // so they stay readable when the card is covered by the next one in a fan
+        let mut block = Block::bordered()
+            .border_set(self.card_style.border_set())
+            .border_style(self.border_style);
+        if let Face::Up(card) = self.face {
+            let label = index_label(card);
+            block = block
+                .title_top(Line::styled(label.clone(), self.style))
+                .title_bottom(Line::styled(label, self.style).alignment(Alignment::Right));
+        }
+
+        let inner = block.inner(area);
+        for y in inner.top()..inner.bottom() {
+            for x in inner.left()..inner.right() {
+                buf[(x, y)].reset();
+            }
+        }
+        block.render(area, buf);
+
+        match self.face {
+            // Large cards on big terminals spell out the rank above the suit
+            Face::Up(card) if area.width >= LARGE_CARD_WIDTH && inner.height >= 4 => {
+                let name = get_card(card.suit, card.rank);
+                let y = inner.y + inner.height / 2 - 1;
+                let text = Line::styled(name, self.style).alignment(Alignment::Center);
+                text.render(Rect::new(inner.x, y, inner.width, 1), buf);
+                Line::styled(suit_symbol(card.suit), self.style)
+                    .alignment(Alignment::Center)
+                    .render(Rect::new(inner.x, y + 1, inner.width, 1), buf);
+            }
+            Face::Up(card) if inner.height > 0 => {
+                let x = inner.x + inner.width.saturating_sub(1) / 2;
+                let y = inner.y + inner.height.saturating_sub(1) / 2;
+                buf.set_string(x, y, suit_symbol(card.suit), self.style);
+            }
+            Face::Down => {
+                for y in inner.top()..inner.bottom() {
+                    buf.set_string(
+                        inner.x,
+                        y,
+                        "░".repeat(inner.width as usize),
+                        self.back_style,
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+pub struct Fan {
+    pub size: CardSize,
+    pub rects: Vec<Rect>,
+    // Cards scrolled off the top of a column that doesn't fit even in compact mode
+    pub scrolled: usize,
+}
+
+// Row of each card when face-down cards are hidden_step apart and face-up ones visible_step,
+// the last face-down card always keeps one row so the stack stays visible
+fn fan_offsets(
+    hidden: usize,
+    visible: usize,
+    hidden_step: usize,
+    visible_step: usize,
+) -> Vec<usize> {
+    let mut offsets = Vec::with_capacity(hidden + visible);
+    let mut y = 0;
+    for index in 0..hidden + visible {
+        offsets.push(y);
+        y += match index {
+            index if index + 1 == hidden => hidden_step.max(1),
+            index if index < hidden => hidden_step,
+            _ => visible_step,
+        };
+    }
+    offsets
+}
+
+// Cards fanned downwards, packed tighter and tighter until they fit, then scrolled to the bottom
+pub fn fan_down(area: Rect, hidden: usize, visible: usize) -> Fan {
+    let rows = area.height as usize;
+    let normal = CardSize::fit(area);
+    let spacings = [(1, 2), (1, 1), (0, 1)];
+
+    let fitting = |size: CardSize| {
+        spacings.iter().find_map(|&(hidden_step, visible_step)| {
+            let offsets = fan_offsets(hidden, visible, hidden_step, visible_step);
+            let bottom = offsets.last().copied().unwrap_or(0) + size.height() as usize;
+            (bottom <= rows).then_some(offsets)
+        })
+    };
+    let (size, offsets) = match fitting(normal) {
+        Some(offsets) if normal != CardSize::Compact => (normal, offsets),
+        _ => (
+            CardSize::Compact,
+            fitting(CardSize::Compact).unwrap_or_else(|| fan_offsets(hidden, visible, 0, 1)),
+        ),
+    };
+
+    // The first row is left for a marker saying how many cards are above
+    let last = offsets.last().copied().unwrap_or(0);
+    let shift = if last < rows { 0 } else { last + 1 - rows };
+
+    let width = size.width_in(area);
+    let x = area.x + (area.width - width) / 2;
+    let mut scrolled = 0;
+    let rects = offsets
+        .iter()
+        .map(|&offset| match offset.checked_sub(shift) {
+            Some(row) if shift == 0 || row > 0 => {
+                Rect::new(x, area.y + row as u16, width, size.height())
+            }
+            _ => {
+                scrolled += 1;
+                Rect::new(x, area.y, width, 0)
+            }
+        })
+        .collect();
+
+    Fan {
+        size,
+        rects,
+        scrolled,
+    }
+}
+
+// Cards fanned to the right, like the last few cards on the waste pile
+pub fn fan_right(area: Rect, count: usize) -> Fan {
+    let step = 4;
+    let spread = step * count.saturating_sub(1) as u16;
+    let size = CardSize::fit(Rect {
+        width: area.width.saturating_sub(spread),
+        ..area
+    });
+
+    let width = size.width_in(area);
+    let height = size.height();
+    let rects = (0..count as u16)
+        .map(|index| match size {
+            CardSize::Compact => Rect::new(area.x, area.y + index, width, height),
+            _ => Rect::new(area.x + index * step, area.y, width, height),
+        })
+        .collect();
+
+    Fan {
+        size,
+        rects,
+        scrolled: 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mock_card(area: Rect, face: Face) -> Buffer {
+        let mut buf = Buffer::empty(area);
+        CardWidget {
+            face,
+            size: CardSize::fit(area),
+            style: Style::default(),
+            border_style: Style::default(),
+            back_style: Style::default(),
+            card_style: CardStyle::default(),
+        }
+        .render(area, &mut buf);
+        buf
+    }
+
+    #[test]
+    fn test_get_card() {
+        assert_eq!(get_card(Suit::Hearts, 8), "8 ♥");
+        assert_eq!(get_card(Suit::Spades, 13), "King ♠");
+        assert_eq!(index_label(Card::new(Suit::Clubs, 12)), "Q♣");
+    }
+
+    #[test]
+    fn test_card_size() {
+        assert_eq!(
+            CardSize::fit(Rect::new(0, 0, 20, 20)),
+            CardSize::Normal {
+                width: 15,
+                height: 8
+            }
+        );
+        assert_eq!(
+            CardSize::fit(Rect::new(0, 0, 7, 10)),
+            CardSize::Normal {
+                width: 7,
+                height: 4
+            }
+        );
+        assert_eq!(CardSize::fit(Rect::new(0, 0, 4, 10)), CardSize::Compact);
+        assert_eq!(CardSize::fit(Rect::new(0, 0, 10, 2)), CardSize::Compact);
+    }
+
+    #[test]
+    fn test_render_card() {
+        let area = Rect::new(0, 0, 7, 4);
+        let buf = mock_card(area, Face::Up(Card::new(Suit::Hearts, 10)));
+        assert_eq!(
+            buf,
+            Buffer::with_lines(["╭10♥──╮", "│  ♥  │", "│     │", "╰──10♥╯"])
+        );
+
+        let buf = mock_card(area, Face::Down);
+        assert_eq!(
+            buf,
+            Buffer::with_lines(["╭─────╮", "│░░░░░│", "│░░░░░│", "╰─────╯"])
+        );
+
+        let buf = mock_card(Rect::new(0, 0, 4, 1), Face::Up(Card::new(Suit::Spades, 13)));
+        assert_eq!(buf, Buffer::with_lines(["K♠  "]));
+    }
+
+    #[test]
+    fn test_fan_down() {
+        let area = Rect::new(0, 0, 9, 20);
+        let rows = |fan: &Fan| -> Vec<u16> { fan.rects.iter().map(|rect| rect.y).collect() };
+
+        let fan = fan_down(area, 2, 3);
+        assert_eq!(
+            fan.size,
+            CardSize::Normal {
+                width: 9,
+                height: 5
+            }
+        );
+        assert_eq!(rows(&fan), [0, 1, 2, 4, 6]);
+
+        // Tighter runs, then face-down cards sharing a row
+        let fan = fan_down(area, 6, 8);
+        assert_eq!(rows(&fan)[5..8], [5, 6, 7]);
+        let fan = fan_down(area, 6, 14);
+        assert_eq!(rows(&fan)[..7], [0, 0, 0, 0, 0, 0, 1]);
+        assert!(fan.size != CardSize::Compact);
+
+        // Then compact, then scrolled so the bottom card stays in view
+        let fan = fan_down(area, 6, 17);
+        assert_eq!(fan.size, CardSize::Compact);
+        let fan = fan_down(Rect::new(0, 0, 9, 5), 0, 8);
+        assert_eq!(fan.scrolled, 4);
+        assert_eq!(rows(&fan)[4..], [1, 2, 3, 4]);
+        assert_eq!(fan.rects[0].height, 0);
+    }
+
+    #[test]
+    fn test_fan_right() {
+        let fan = fan_right(Rect::new(0, 0, 20, 8), 3);
+
+        assert_eq!(
+            fan.size,
+            CardSize::Normal {
+                width: 12,
+                height: 7
+            }
+        );
+        assert_eq!(fan.rects[2].x, 8);
+        assert_eq!(fan_right(Rect::new(0, 0, 8, 8), 3).size, CardSize::Compact);
+    }
+}
@@ -0,0 +1,376 @@
+use std::{ops::RangeInclusive, path::PathBuf, time::Duration};
+
+use crate::{rules::Rules, scoring::Scoring};
+
+#[derive(PartialEq, Debug)]
+pub enum LeaderboardAction {
+    Show,
+    Export,
+    Import(PathBuf),
+}
+
+#[derive(PartialEq, Debug)]
+pub enum Mode {
+    Play,
+    Stats {
+        json: bool,
+    },
+    Replay {
+        path: PathBuf,
+    },
+    Headless {
+        omniscient: bool,
+    },
+    Accessible,
+    Race {
+        host: bool,
+        address: String,
+    },
+    Daily,
+    Leaderboard(LeaderboardAction),
+    Achievements,
+    Analyze {
+        seeds: RangeInclusive<u64>,
+        time_limit: Duration,
+        csv: Option<PathBuf>,
+    },
+}
+
+pub struct Options {
+    pub mode: Mode,
+    // None leaves the rules and the scoring to the config
+    pub rules: Option<Rules>,
+    pub seed: Option<u64>,
+    pub position: Option<String>,
+    pub listen: Option<String>,
+    pub scoring: Option<Scoring>,
+    // None leaves these to be detected from the environment
+    pub unicode: Option<bool>,
+    pub color: Option<bool>,
+}
+
+// Both ends are included, so 1..100 is a hundred deals
+fn parse_seeds(text: &str) -> Result<RangeInclusive<u64>, String> {
+    let error = || format!("Invalid seeds '{}', e.g. 1..1000", text);
+    let (first, last) = text.split_once("..").unwrap_or((text, text));
+    let first: u64 = first.parse().map_err(|_| error())?;
+    let last: u64 = last.parse().map_err(|_| error())?;
+    if first > last {
+        return Err(error());
+    }
+    Ok(first..=last)
+}
+
+pub fn parse_args(args: &[String]) -> Result<Options, String> {
+    let mut options = Options {
+        mode: Mode::Play,
+        rules: None,
+        seed: None,
+        position: None,
+        listen: None,
+        scoring: None,
+        unicode: None,
+        color: None,
+    };
+
+    let mut args = args.iter().peekable();
+
+    if let Some(subcommand) = args.next_if(|arg| !arg.starts_with("--")) {
+        options.mode = match subcommand.as_str() {
+            "stats" => Mode::Stats { json: false },
+            "daily" => Mode::Daily,
+            "achievements" => Mode::Achievements,
+            "analyze" => Mode::Analyze {
+                seeds: 1..=1000,
+                time_limit: Duration::from_secs(1),
+                csv: None,
+            },
+            "replay" => {
+                let path = args.next().ok_or("replay needs a replay file")?;
+                Mode::Replay {
+                    path: PathBuf::from(path),
+                }
+            }
+            "race" => {
+                let usage = "race needs 'host <port>' or 'join <address>'";
+                let host = match args.next().map(String::as_str) {
+                    Some("host") => true,
+                    Some("join") => false,
+                    _ => return Err(usage.to_string()),
+                };
+                let address = args.next().ok_or(usage)?;
+                Mode::Race {
+                    host,
+                    address: address.clone(),
+                }
+            }
+            "leaderboard" => match args.next_if(|arg| !arg.starts_with("--")) {
+                None => Mode::Leaderboard(LeaderboardAction::Show),
+                Some(action) if action == "export" => Mode::Leaderboard(LeaderboardAction::Export),
+                Some(action) if action == "import" => {
+                    let path = args.next().ok_or("leaderboard import needs a file")?;
+                    Mode::Leaderboard(LeaderboardAction::Import(PathBuf::from(path)))
+                }
+                Some(action) => return Err(format!("Unknown leaderboard action '{}'", action)),
+            },
+            _ => return Err(format!("Unknown subcommand '{}'", subcommand)),
+        };
+    }
+
+    while let Some(arg) = args.next() {
+        match (arg.as_str(), &mut options.mode) {
+            // The daily deal is the same for everyone, so it can't be changed
+            ("--rules", mode) if *mode != Mode::Daily => {
+                let spec = args.next().ok_or("--rules needs a value, e.g. canfield")?;
+                options.rules = Some(Rules::from_spec(spec)?);
+            }
+            ("--seed", mode) if *mode != Mode::Daily => {
+                let seed = args.next().ok_or("--seed needs a number")?;
+                options.seed = Some(
+                    seed.parse()
+                        .map_err(|_| format!("Invalid seed '{}'", seed))?,
+                );
+            }
+            ("--headless", Mode::Play) => options.mode = Mode::Headless { omniscient: false },
+            ("--omniscient", Mode::Headless { omniscient }) => *omniscient = true,
+            ("--accessible", Mode::Play) => options.mode = Mode::Accessible,
+            ("--position", Mode::Play | Mode::Headless { .. } | Mode::Accessible) => {
+                let position = args.next().ok_or("--position needs a quoted position")?;
+                options.position = Some(position.clone());
+            }
+            ("--listen", Mode::Play | Mode::Replay { .. } | Mode::Daily) => {
+                let address = args
+                    .next()
+                    .ok_or("--listen needs a port or a socket path")?;
+                options.listen = Some(address.clone());
+            }
+            ("--scoring", Mode::Play | Mode::Daily | Mode::Race { .. }) => {
+                let scoring = args.next().ok_or("--scoring needs standard or vegas")?;
+                options.scoring = Some(scoring.parse()?);
+            }
+            ("--json", Mode::Stats { json }) => *json = true,
+            ("--seeds", Mode::Analyze { seeds, .. }) => {
+                *seeds = parse_seeds(args.next().ok_or("--seeds needs a range, e.g. 1..1000")?)?;
+            }
+            ("--time-limit", Mode::Analyze { time_limit, .. }) => {
+                let secs = args.next().ok_or("--time-limit needs seconds per deal")?;
+                *time_limit = secs
+                    .parse()
+                    .ok()
+                    .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+                    .ok_or_else(|| format!("Invalid time limit '{}'", secs))?;
+            }
+            ("--csv", Mode::Analyze { csv, .. }) => {
+                let path = args.next().ok_or("--csv needs a file")?;
+                *csv = Some(PathBuf::from(path));
+            }
+            (
+                "--ascii" | "--unicode" | "--color" | "--no-color",
+                Mode::Play | Mode::Replay { .. } | Mode::Race { .. } | Mode::Daily,
+            ) => match arg.as_str() {
+                "--ascii" => options.unicode = Some(false),
+                "--unicode" => options.unicode = Some(true),
+                "--color" => options.color = Some(true),
+                _ => options.color = Some(false),
+            },
+            _ => return Err(format!("Unknown argument '{}'", arg)),
+        }
+    }
+
+    Ok(options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let options = parse_args(&args("")).unwrap();
+        assert_eq!(options.rules, None);
+
+        let options = parse_args(&args("--rules canfield")).unwrap();
+        assert_eq!(options.rules, Some(Rules::canfield()));
+
+        assert!(parse_args(&args("--rules")).is_err());
+        assert!(parse_args(&args("--jokers")).is_err());
+
+        let options = parse_args(&args("--seed 42")).unwrap();
+        assert_eq!(options.seed, Some(42));
+        assert!(parse_args(&args("--seed many")).is_err());
+    }
+
+    #[test]
+    fn test_parse_position() {
+        let mut line = args("--position");
+        line.push("|Ks/- - - - - A 0".to_string());
+
+        let options = parse_args(&line).unwrap();
+        assert_eq!(options.position, Some("|Ks/- - - - - A 0".to_string()));
+
+        assert!(parse_args(&args("--position")).is_err());
+        assert!(parse_args(&args("stats --position x")).is_err());
+    }
+
+    #[test]
+    fn test_parse_headless() {
+        let options = parse_args(&args("--headless --rules canfield --seed 3")).unwrap();
+        assert_eq!(options.mode, Mode::Headless { omniscient: false });
+        assert_eq!(options.rules, Some(Rules::canfield()));
+
+        let options = parse_args(&args("--headless --omniscient")).unwrap();
+        assert_eq!(options.mode, Mode::Headless { omniscient: true });
+
+        assert!(parse_args(&args("--omniscient")).is_err());
+
+        let options = parse_args(&args("--accessible --seed 3")).unwrap();
+        assert_eq!(options.mode, Mode::Accessible);
+        assert!(parse_args(&args("--headless --accessible")).is_err());
+        assert!(parse_args(&args("stats --headless")).is_err());
+    }
+
+    #[test]
+    fn test_parse_listen() {
+        let options = parse_args(&args("--listen 7878")).unwrap();
+        assert_eq!(options.listen, Some("7878".to_string()));
+
+        let options = parse_args(&args("replay game.json --listen /tmp/tersoli.sock")).unwrap();
+        assert_eq!(options.listen, Some("/tmp/tersoli.sock".to_string()));
+
+        assert!(parse_args(&args("--listen")).is_err());
+        assert!(parse_args(&args("stats --listen 7878")).is_err());
+    }
+
+    #[test]
+    fn test_parse_race_subcommand() {
+        let options = parse_args(&args("race host 7900 --rules canfield")).unwrap();
+        assert_eq!(
+            options.mode,
+            Mode::Race {
+                host: true,
+                address: "7900".to_string()
+            }
+        );
+        assert_eq!(options.rules, Some(Rules::canfield()));
+
+        let options = parse_args(&args("race join 192.168.1.5:7900")).unwrap();
+        assert_eq!(
+            options.mode,
+            Mode::Race {
+                host: false,
+                address: "192.168.1.5:7900".to_string()
+            }
+        );
+
+        assert!(parse_args(&args("race")).is_err());
+        assert!(parse_args(&args("race watch 7900")).is_err());
+        assert!(parse_args(&args("race join")).is_err());
+    }
+
+    #[test]
+    fn test_parse_daily_subcommand() {
+        let options = parse_args(&args("daily --listen 7878")).unwrap();
+        assert_eq!(options.mode, Mode::Daily);
+
+        assert!(parse_args(&args("daily --seed 4")).is_err());
+        assert!(parse_args(&args("daily --rules canfield")).is_err());
+    }
+
+    #[test]
+    fn test_parse_leaderboard_subcommand() {
+        let options = parse_args(&args("leaderboard")).unwrap();
+        assert_eq!(options.mode, Mode::Leaderboard(LeaderboardAction::Show));
+
+        let options = parse_args(&args("leaderboard export")).unwrap();
+        assert_eq!(options.mode, Mode::Leaderboard(LeaderboardAction::Export));
+
+        let options = parse_args(&args("leaderboard import team.tsv")).unwrap();
+        assert_eq!(
+            options.mode,
+            Mode::Leaderboard(LeaderboardAction::Import(PathBuf::from("team.tsv")))
+        );
+
+        assert!(parse_args(&args("leaderboard import")).is_err());
+        assert!(parse_args(&args("leaderboard delete")).is_err());
+    }
+
+    #[test]
+    fn test_parse_scoring() {
+        let options = parse_args(&args("--scoring vegas")).unwrap();
+        assert_eq!(options.scoring, Some(Scoring::Vegas));
+
+        assert!(parse_args(&args("--scoring golf")).is_err());
+        assert!(parse_args(&args("stats --scoring vegas")).is_err());
+    }
+
+    #[test]
+    fn test_parse_replay_subcommand() {
+        let options = parse_args(&args("replay game.json")).unwrap();
+        assert_eq!(
+            options.mode,
+            Mode::Replay {
+                path: PathBuf::from("game.json")
+            }
+        );
+
+        assert!(parse_args(&args("replay")).is_err());
+    }
+
+    #[test]
+    fn test_parse_stats_subcommand() {
+        let options = parse_args(&args("stats")).unwrap();
+        assert_eq!(options.mode, Mode::Stats { json: false });
+
+        let options = parse_args(&args("stats --json")).unwrap();
+        assert_eq!(options.mode, Mode::Stats { json: true });
+
+        let options = parse_args(&args("achievements")).unwrap();
+        assert_eq!(options.mode, Mode::Achievements);
+
+        assert!(parse_args(&args("--json")).is_err());
+        assert!(parse_args(&args("scores")).is_err());
+    }
+
+    #[test]
+    fn test_parse_analyze_subcommand() {
+        let options = parse_args(&args(
+            "analyze --seeds 1..10000 --rules draw3,passes=3 --time-limit 0.5 --csv out.csv",
+        ))
+        .unwrap();
+        assert_eq!(
+            options.mode,
+            Mode::Analyze {
+                seeds: 1..=10000,
+                time_limit: Duration::from_millis(500),
+                csv: Some(PathBuf::from("out.csv"))
+            }
+        );
+        assert_eq!(options.rules, Rules::from_spec("draw3,passes=3").ok());
+
+        let options = parse_args(&args("analyze --seeds 7")).unwrap();
+        assert!(matches!(options.mode, Mode::Analyze { seeds, .. } if seeds == (7..=7)));
+
+        assert!(parse_args(&args("analyze --seeds 10..1")).is_err());
+        assert!(parse_args(&args("analyze --seeds many")).is_err());
+        assert!(parse_args(&args("analyze --time-limit -1")).is_err());
+        assert!(parse_args(&args("--seeds 1..10")).is_err());
+    }
+
+    #[test]
+    fn test_parse_capabilities() {
+        let options = parse_args(&args("")).unwrap();
+        assert_eq!((options.unicode, options.color), (None, None));
+
+        let options = parse_args(&args("daily --ascii --no-color")).unwrap();
+        assert_eq!((options.unicode, options.color), (Some(false), Some(false)));
+
+        let options = parse_args(&args("replay game.json --unicode --color")).unwrap();
+        assert_eq!((options.unicode, options.color), (Some(true), Some(true)));
+
+        assert!(parse_args(&args("stats --ascii")).is_err());
+    }
+}
@@ -1,3 +1,7 @@
+use serde::Deserialize;
+
+#[derive(Copy, Clone, PartialEq, Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Command {
     MoveLeft,
     MoveRight,
@@ -5,5 +9,43 @@ pub enum Command {
     MoveDown,
     Select,
     AutoPlace,
+    Undo,
+    ToggleStats,
+    ToggleHelp,
+    ToggleCalendar,
+    PreviousMonth,
+    NextMonth,
+    ToggleLeaderboard,
+    CycleSort,
+    CycleTheme,
+    TypeChar(char),
+    DeleteChar,
+    SubmitName,
+    CancelName,
+    TogglePlayback,
+    StepForward,
+    StepBack,
+    SpeedUp,
+    SpeedDown,
+    ToggleMenu,
+    MenuUp,
+    MenuDown,
+    MenuSelect,
+    PreviousRules,
+    NextRules,
+    NewGame,
+    RestartDeal,
+    Confirm,
+    Cancel,
+    TogglePause,
+    ToggleSettings,
+    SettingUp,
+    SettingDown,
+    PreviousValue,
+    NextValue,
+    ToggleHistory,
+    HistoryBack,
+    HistoryForward,
+    HistoryLive,
     Quit,
 }
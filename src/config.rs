@@ -0,0 +1,117 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    card_view::CardStyle,
+    keymap::KeyPreset,
+    rules::Rules,
+    scoring::Scoring,
+    storage::{config_path, read_toml, write_toml},
+};
+
+// Missing keys fall back to their defaults, so older config files keep working
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(default)]
+pub struct Config {
+    pub animations: bool,
+    pub animation_speed: f64,
+    pub theme: String,
+    // Seconds without a key press before the game pauses itself, 0 turns it off
+    pub idle_pause_secs: u64,
+    // Rules and scoring of new deals when the command line doesn't pick them
+    pub draw_count: usize,
+    // 0 allows any number of passes through the stock
+    pub pass_limit: u32,
+    pub scoring: Scoring,
+    pub auto_move: bool,
+    pub card_style: CardStyle,
+    pub key_preset: KeyPreset,
+    // en, fi or de, empty follows the locale
+    pub language: String,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            animations: true,
+            animation_speed: 1.0,
+            theme: "classic".to_string(),
+            idle_pause_secs: 300,
+            draw_count: 1,
+            pass_limit: 0,
+            scoring: Scoring::default(),
+            auto_move: false,
+            card_style: CardStyle::default(),
+            key_preset: KeyPreset::default(),
+            language: String::new(),
+        }
+    }
+}
+
+impl Config {
+    pub fn path() -> PathBuf {
+        config_path("config.toml")
+    }
+
+    pub fn load() -> Self {
+        read_toml(&Self::path())
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        write_toml(&Self::path(), self)
+    }
+
+    pub fn apply_rules(&self, rules: Rules) -> Rules {
+        Rules {
+            draw_count: self.draw_count.max(1),
+            pass_limit: (self.pass_limit > 0).then_some(self.pass_limit),
+            ..rules
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_partial_config() {
+        let config: Config = toml::from_str("animation_speed = 2.5").unwrap();
+
+        assert!(config.animations);
+        assert_eq!(config.animation_speed, 2.5);
+        assert_eq!(config.idle_pause_secs, 300);
+        assert_eq!(toml::from_str::<Config>("").unwrap(), Config::default());
+    }
+
+    #[test]
+    fn test_write_config() {
+        let config = Config {
+            scoring: Scoring::Vegas,
+            card_style: CardStyle::Double,
+            key_preset: KeyPreset::Vim,
+            ..Config::default()
+        };
+        let text = toml::to_string(&config).unwrap();
+
+        assert!(text.contains("scoring = \"vegas\""));
+        assert!(text.contains("key_preset = \"vim\""));
+        assert_eq!(toml::from_str::<Config>(&text).unwrap(), config);
+    }
+
+    #[test]
+    fn test_apply_rules() {
+        let config = Config {
+            draw_count: 3,
+            pass_limit: 3,
+            ..Config::default()
+        };
+        let rules = config.apply_rules(Rules::canfield());
+
+        assert_eq!(rules.draw_count, 3);
+        assert_eq!(rules.pass_limit, Some(3));
+        assert_eq!(rules.reserve_size, Rules::canfield().reserve_size);
+        assert_eq!(Config::default().apply_rules(rules).pass_limit, None);
+    }
+}
@@ -0,0 +1,248 @@
+use std::{
+    collections::BTreeMap,
+    fmt,
+    path::PathBuf,
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    rules::Rules,
+    stats::GameResult,
+    storage::{data_path, read_json, write_json},
+};
+
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub struct Date {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl Date {
+    // Days are counted in UTC, so the whole team shares a deal wherever they are
+    pub fn today() -> Self {
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        Self::from_days((secs / 86400) as i64)
+    }
+
+    // Civil calendar conversions from Howard Hinnant's date algorithms
+    pub fn from_days(days: i64) -> Self {
+        let z = days + 719468;
+        let era = z.div_euclid(146097);
+        let doe = z.rem_euclid(146097);
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = (yoe + era * 400 + (month <= 2) as i64) as i32;
+
+        Self { year, month, day }
+    }
+
+    pub fn days(&self) -> i64 {
+        let year = self.year as i64 - (self.month <= 2) as i64;
+        let era = year.div_euclid(400);
+        let yoe = year.rem_euclid(400);
+        let mp = (self.month as i64 + 9) % 12;
+        let doy = (153 * mp + 2) / 5 + self.day as i64 - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+
+        era * 146097 + doe - 719468
+    }
+
+    // 0 is Monday
+    pub fn weekday(&self) -> u32 {
+        (self.days() + 3).rem_euclid(7) as u32
+    }
+
+    pub fn days_in_month(&self) -> u32 {
+        let next = match self.month {
+            12 => Date::new(self.year + 1, 1, 1),
+            month => Date::new(self.year, month + 1, 1),
+        };
+        (next.days() - Date::new(self.year, self.month, 1).days()) as u32
+    }
+
+    pub fn new(year: i32, month: u32, day: u32) -> Self {
+        Self { year, month, day }
+    }
+
+    pub fn add_months(&self, months: i32) -> Self {
+        let index = self.year * 12 + self.month as i32 - 1 + months;
+        Date::new(index.div_euclid(12), index.rem_euclid(12) as u32 + 1, 1)
+    }
+
+    // FNV-1a keeps the seed stable across Rust versions and platforms, unlike the std hasher.
+    // The deal itself stays the same because the stock shuffles with ChaCha8
+    pub fn seed(&self) -> u64 {
+        format!("tersoli-daily-{}", self)
+            .bytes()
+            .fold(0xcbf29ce484222325, |hash, byte| {
+                (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+            })
+    }
+
+    pub fn rules() -> Rules {
+        Rules::klondike()
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl FromStr for Date {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid date '{}', expected YYYY-MM-DD", text);
+        let mut parts = text.splitn(3, '-');
+        let mut next = || parts.next().ok_or_else(invalid);
+        let (year, month, day) = (next()?, next()?, next()?);
+
+        let date = Date::new(
+            year.parse().map_err(|_| invalid())?,
+            month.parse().map_err(|_| invalid())?,
+            day.parse().map_err(|_| invalid())?,
+        );
+        if !(1..=12).contains(&date.month) || date.day == 0 || date.day > date.days_in_month() {
+            return Err(invalid());
+        }
+        Ok(date)
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+pub struct DailyRecord {
+    pub attempts: Vec<GameResult>,
+}
+
+impl DailyRecord {
+    pub fn completed(&self) -> bool {
+        self.attempts.iter().any(|result| result.won)
+    }
+
+    pub fn best(&self) -> Option<&GameResult> {
+        self.attempts
+            .iter()
+            .filter(|result| result.won)
+            .min_by_key(|result| (result.secs, result.moves))
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct DailyHistory {
+    pub days: BTreeMap<String, DailyRecord>,
+}
+
+impl DailyHistory {
+    pub fn path() -> PathBuf {
+        data_path("daily.json")
+    }
+
+    pub fn load() -> Self {
+        read_json(&Self::path())
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        write_json(&Self::path(), self)
+    }
+
+    pub fn record(&mut self, date: Date, result: GameResult) {
+        self.days
+            .entry(date.to_string())
+            .or_default()
+            .attempts
+            .push(result);
+    }
+
+    pub fn get(&self, date: Date) -> Option<&DailyRecord> {
+        self.days.get(&date.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_days_round_trip() {
+        assert_eq!(Date::from_days(0), Date::new(1970, 1, 1));
+        assert_eq!(Date::new(2000, 3, 1).days(), 11017);
+
+        for days in [-1000, 0, 59, 10957, 19000, 20744, 30000] {
+            assert_eq!(Date::from_days(days).days(), days);
+        }
+    }
+
+    #[test]
+    fn test_calendar_helpers() {
+        assert_eq!(Date::new(1970, 1, 1).weekday(), 3);
+        assert_eq!(Date::new(2026, 10, 19).weekday(), 0);
+        assert_eq!(Date::new(2024, 2, 1).days_in_month(), 29);
+        assert_eq!(Date::new(2026, 12, 1).days_in_month(), 31);
+        assert_eq!(Date::new(2026, 12, 5).add_months(1), Date::new(2027, 1, 1));
+        assert_eq!(Date::new(2026, 1, 5).add_months(-1), Date::new(2025, 12, 1));
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        let date: Date = "2026-03-07".parse().unwrap();
+        assert_eq!(date, Date::new(2026, 3, 7));
+        assert_eq!(date.to_string(), "2026-03-07");
+
+        assert!("2026-02-30".parse::<Date>().is_err());
+        assert!("2026-13-01".parse::<Date>().is_err());
+        assert!("yesterday".parse::<Date>().is_err());
+    }
+
+    #[test]
+    fn test_seed_is_stable() {
+        let date = Date::new(2026, 10, 18);
+
+        assert_eq!(date.seed(), Date::new(2026, 10, 18).seed());
+        assert_ne!(date.seed(), Date::new(2026, 10, 19).seed());
+        assert_eq!(Date::new(2026, 1, 1).seed(), 0x238dd9ba7d65fe6a);
+    }
+
+    #[test]
+    fn test_history() {
+        let date = Date::new(2026, 10, 18);
+        let mut history = DailyHistory::default();
+        assert!(history.get(date).is_none());
+
+        history.record(
+            date,
+            GameResult {
+                won: false,
+                secs: 30,
+                moves: 10,
+            },
+        );
+        assert!(!history.get(date).unwrap().completed());
+
+        for (secs, moves) in [(300, 120), (200, 140)] {
+            history.record(
+                date,
+                GameResult {
+                    won: true,
+                    secs,
+                    moves,
+                },
+            );
+        }
+
+        let record = history.get(date).unwrap();
+        assert!(record.completed());
+        assert_eq!(record.best().map(|best| best.secs), Some(200));
+    }
+}
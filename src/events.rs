@@ -0,0 +1,49 @@
+use crate::{card::Card, moves::Move};
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum GameEvent {
+    Drew(usize),
+    Recycled,
+    ToTableau(Card),
+    ToFoundation(Card),
+    FromFoundation(Card),
+    Revealed {
+        column: usize,
+        hidden_left: usize,
+        stock_left: usize,
+    },
+    Moved(Move),
+    Undone,
+    Won,
+}
+
+// Game code emits events as moves happen, the app drains them once per tick and hands them to listeners
+#[derive(Clone, Default, Debug)]
+pub struct EventBus {
+    queue: Vec<GameEvent>,
+}
+
+impl EventBus {
+    pub fn emit(&mut self, event: GameEvent) {
+        self.queue.push(event);
+    }
+
+    pub fn drain(&mut self) -> Vec<GameEvent> {
+        std::mem::take(&mut self.queue)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_drain() {
+        let mut bus = EventBus::default();
+        bus.emit(GameEvent::Drew(1));
+        bus.emit(GameEvent::Recycled);
+
+        assert_eq!(bus.drain(), [GameEvent::Drew(1), GameEvent::Recycled]);
+        assert!(bus.drain().is_empty());
+    }
+}
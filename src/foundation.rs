@@ -1,13 +1,25 @@
-use crate::{card::Card, location::Location, suit::Suit, utils::get_card_suit_index};
+use crate::{
+    card::Card,
+    location::Location,
+    rules::{FoundationBase, Rules},
+};
 
+#[derive(Clone)]
 pub struct Foundation {
     pub cards: Vec<Vec<Option<Card>>>,
+    pub rules: Rules,
+    pub base_rank: u8,
 }
 
 impl Foundation {
-    pub fn new() -> Self {
+    pub fn new(rules: Rules) -> Self {
         Self {
-            cards: vec![vec![None], vec![None], vec![None], vec![None]],
+            cards: vec![vec![None]; 4 * rules.decks],
+            rules,
+            base_rank: match rules.foundation_base {
+                FoundationBase::Rank(rank) => rank,
+                FoundationBase::Random => 1,
+            },
         }
     }
 
@@ -19,46 +31,51 @@ impl Foundation {
         }
     }
 
-    pub fn get_top_card_by_suit(&self, suit: Suit) -> Option<Card> {
-        self.cards[get_card_suit_index(suit)]
-            .last()
-            .copied()
-            .unwrap_or_default()
+    fn pile_size(&self, slot: usize) -> usize {
+        self.cards[slot]
+            .iter()
+            .filter(|card| card.is_some())
+            .count()
     }
 
-    pub fn get_top_value(&self, location: Location) -> u8 {
-        match self.get_top_card(location) {
-            Some(card) => card.rank,
-            _ => 0,
-        }
-    }
-
-    pub fn add_card(&mut self, card: Card, to_suit: Suit) -> bool {
-        if card.suit != to_suit {
+    pub fn can_add_card(&self, card: Card, slot: usize) -> bool {
+        if slot >= self.cards.len() {
             return false;
         }
 
-        let card_suit_index = get_card_suit_index(card.suit);
-
-        let parent_card = match self.get_top_card_by_suit(card.suit) {
+        let parent_card = match self.get_top_card(Location::Foundation(slot)) {
             Some(parent) => parent,
-            _ => {
-                if card.rank == 1 {
-                    self.cards[card_suit_index].push(Some(card));
-                    return true;
-                }
-                return false;
-            }
+            _ => return card.rank == self.base_rank,
         };
 
-        if card.rank != parent_card.rank + 1 {
+        card.suit == parent_card.suit
+            && self.pile_size(slot) < 13
+            && Some(card.rank) == self.rules.next_rank(parent_card.rank)
+    }
+
+    pub fn add_card(&mut self, card: Card, slot: usize) -> bool {
+        if !self.can_add_card(card, slot) {
             return false;
         }
 
-        self.cards[card_suit_index].push(Some(card));
+        self.cards[slot].push(Some(card));
         true
     }
 
+    pub fn find_slot(&self, card: Card) -> Option<usize> {
+        let slots = 0..self.cards.len();
+
+        slots
+            .clone()
+            .find(|&slot| self.pile_size(slot) > 0 && self.can_add_card(card, slot))
+            .or_else(|| slots.clone().find(|&slot| self.can_add_card(card, slot)))
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.cards.len() == 4 * self.rules.decks
+            && (0..self.cards.len()).all(|slot| self.pile_size(slot) == 13)
+    }
+
     pub fn remove_card(&mut self, location: Location) {
         if let Location::Foundation(index) = location {
             self.cards[index].pop();
@@ -71,13 +88,14 @@ impl Foundation {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::suit::Suit;
 
     fn mock_foundation() -> Foundation {
-        let mut mock = Foundation::new();
+        let mut mock = Foundation::new(Rules::klondike());
 
-        mock.add_card(Card::new(Suit::Hearts, 1), Suit::Hearts);
-        mock.add_card(Card::new(Suit::Hearts, 2), Suit::Hearts);
-        mock.add_card(Card::new(Suit::Clubs, 1), Suit::Clubs);
+        mock.add_card(Card::new(Suit::Hearts, 1), 1);
+        mock.add_card(Card::new(Suit::Hearts, 2), 1);
+        mock.add_card(Card::new(Suit::Clubs, 1), 2);
 
         mock
     }
@@ -98,23 +116,12 @@ mod tests {
     }
 
     #[test]
-    fn test_get_top_card_by_suit() {
+    fn test_find_slot() {
         let foundation = mock_foundation();
 
-        let top_card = match foundation.get_top_card_by_suit(Suit::Clubs) {
-            Some(card) => card,
-            None => panic!("No card found for suit 3"),
-        };
-
-        assert_eq!(top_card.rank, 1);
-        assert_eq!(top_card.suit, Suit::Clubs);
-    }
-
-    #[test]
-    fn test_get_top_value() {
-        let foundation = mock_foundation();
-
-        assert_eq!(foundation.get_top_value(Location::Foundation(1)), 2);
+        assert_eq!(foundation.find_slot(Card::new(Suit::Clubs, 2)), Some(2));
+        assert_eq!(foundation.find_slot(Card::new(Suit::Spades, 1)), Some(0));
+        assert_eq!(foundation.find_slot(Card::new(Suit::Spades, 2)), None);
     }
 
     #[test]
@@ -123,36 +130,65 @@ mod tests {
 
         // Cards which shouldn't be accepted & added
         let wrong_rank_card = Card::new(Suit::Hearts, 8);
-        foundation.add_card(wrong_rank_card, wrong_rank_card.suit);
-        match foundation.get_top_card_by_suit(wrong_rank_card.suit) {
+        foundation.add_card(wrong_rank_card, 1);
+        match foundation.get_top_card(Location::Foundation(1)) {
             Some(card) => assert_ne!(card.rank, wrong_rank_card.rank),
             _ => panic!("No card found for suit"),
         }
 
         let wrong_suit_card = Card::new(Suit::Spades, 2);
-        foundation.add_card(wrong_suit_card, Suit::Clubs);
-        match foundation.get_top_card_by_suit(Suit::Clubs) {
+        foundation.add_card(wrong_suit_card, 2);
+        match foundation.get_top_card(Location::Foundation(2)) {
             Some(card) => assert_ne!(card.rank, wrong_suit_card.rank),
             _ => panic!("No card found for suit"),
         }
 
         // Card which is added to parent card
-        let add_to_parent_card = Card::new(Suit::Hearts, 2);
-        foundation.add_card(add_to_parent_card, add_to_parent_card.suit);
-        match foundation.get_top_card_by_suit(add_to_parent_card.suit) {
+        let add_to_parent_card = Card::new(Suit::Clubs, 2);
+        foundation.add_card(add_to_parent_card, 2);
+        match foundation.get_top_card(Location::Foundation(2)) {
             Some(card) => assert_eq!(card.rank, add_to_parent_card.rank),
             _ => panic!("No card found for suit"),
         }
 
         // Ace added as a first card
         let first_card = Card::new(Suit::Spades, 1);
-        foundation.add_card(first_card, Suit::Spades);
-        match foundation.get_top_card_by_suit(first_card.suit) {
+        foundation.add_card(first_card, 0);
+        match foundation.get_top_card(Location::Foundation(0)) {
             Some(card) => assert_eq!(card.rank, first_card.rank),
             _ => panic!("No card found for suit"),
         }
     }
 
+    #[test]
+    fn test_add_card_with_base_rank() {
+        let mut foundation = Foundation::new(Rules::canfield());
+        foundation.base_rank = 12;
+
+        assert!(!foundation.add_card(Card::new(Suit::Spades, 1), 0));
+        assert!(foundation.add_card(Card::new(Suit::Spades, 12), 0));
+        assert!(foundation.add_card(Card::new(Suit::Spades, 13), 0));
+        assert!(foundation.add_card(Card::new(Suit::Spades, 1), 0));
+        assert_eq!(
+            foundation
+                .get_top_card(Location::Foundation(0))
+                .unwrap()
+                .rank,
+            1
+        );
+    }
+
+    #[test]
+    fn test_double_deck_slots() {
+        let mut foundation = Foundation::new(Rules::double_klondike());
+        assert_eq!(foundation.cards.len(), 8);
+
+        assert!(foundation.add_card(Card::new(Suit::Hearts, 1), 0));
+        assert!(foundation.add_card(Card::new(Suit::Hearts, 1), 5));
+        assert!(!foundation.add_card(Card::new(Suit::Spades, 2), 5));
+        assert_eq!(foundation.find_slot(Card::new(Suit::Hearts, 2)), Some(0));
+    }
+
     #[test]
     fn test_remove() {
         let mut foundation = mock_foundation();
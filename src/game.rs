@@ -0,0 +1,517 @@
+use crate::{
+    card::Card,
+    events::{EventBus, GameEvent},
+    foundation::Foundation,
+    location::Location,
+    moves::Move,
+    notation,
+    reserve::Reserve,
+    rules::{FoundationBase, Rules},
+    stock::Stock,
+    tableau::Tableau,
+    utils::check_win,
+    waste::Waste,
+};
+
+#[derive(Clone)]
+pub struct Game {
+    pub rules: Rules,
+    pub seed: u64,
+    pub stock: Stock,
+    pub waste: Waste,
+    pub reserve: Reserve,
+    pub tableau: Tableau,
+    pub foundation: Foundation,
+    pub moves: u32,
+    pub events: EventBus,
+}
+
+impl Game {
+    pub fn new(rules: Rules, seed: u64) -> Self {
+        let mut game = Self {
+            rules,
+            seed,
+            stock: Stock::new(rules.decks, seed),
+            waste: Waste::new(),
+            reserve: Reserve::new(),
+            tableau: Tableau::new(rules),
+            foundation: Foundation::new(rules),
+            moves: 0,
+            events: EventBus::default(),
+        };
+
+        game.deal();
+        game
+    }
+
+    pub fn from_position(rules: Rules, position: &str) -> Result<Self, String> {
+        notation::parse_position(rules, position)
+    }
+
+    pub fn position(&self) -> String {
+        notation::format_position(self)
+    }
+
+    fn deal(&mut self) {
+        if self.rules.foundation_base == FoundationBase::Random {
+            let base_card = self.stock.deal();
+            self.foundation.base_rank = base_card.rank;
+            self.foundation.add_card(base_card, 0);
+        }
+
+        self.reserve
+            .initialize(&mut self.stock, self.rules.reserve_size);
+        self.tableau.initialize(&mut self.stock);
+    }
+
+    pub fn is_won(&self) -> bool {
+        check_win(&self.foundation)
+    }
+
+    pub fn apply(&mut self, mv: Move) -> bool {
+        if !self.in_bounds(mv) {
+            return false;
+        }
+
+        let hidden = self.tableau.hidden_count();
+        let applied = match mv {
+            Move::Draw => self.deal_from_stock(),
+            Move::WasteToTableau(column) => self.take_from_waste(column),
+            Move::ReserveToTableau(column) => self.take_from_reserve(column),
+            Move::WasteToFoundation(slot)
+            | Move::ReserveToFoundation(slot)
+            | Move::TableauToFoundation(_, slot) => self.place_in_foundation(mv.source(), slot),
+            Move::FoundationToTableau(slot, column) => self.take_from_foundation(slot, column),
+            Move::TableauToTableau(from, to, count) => {
+                self.tableau
+                    .move_run(Location::Tableau(from), Location::Tableau(to), count)
+            }
+        };
+
+        if !applied {
+            return false;
+        }
+
+        self.moves += 1;
+        self.events.emit(GameEvent::Moved(mv));
+        if let Location::Tableau(column) = mv.source()
+            && self.tableau.hidden_count() < hidden
+        {
+            self.events.emit(GameEvent::Revealed {
+                column,
+                hidden_left: self.tableau.hidden_count(),
+                stock_left: self.stock.cards.len(),
+            });
+        }
+        if self.is_won() {
+            self.events.emit(GameEvent::Won);
+        }
+
+        true
+    }
+
+    fn in_bounds(&self, mv: Move) -> bool {
+        [mv.source(), mv.destination()]
+            .iter()
+            .all(|location| match *location {
+                Location::Tableau(column) => column < self.tableau.cards.len(),
+                Location::Foundation(slot) => slot < self.foundation.cards.len(),
+                _ => true,
+            })
+    }
+
+    pub fn get_top_card(&self, location: Location) -> Option<Card> {
+        match location {
+            Location::Stock => self.stock.cards.last().copied(),
+            Location::Waste => self.waste.get_top_card(),
+            Location::Reserve => self.reserve.get_top_card(),
+            Location::Tableau(_) => self.tableau.get_top_card(location),
+            Location::Foundation(_) => self.foundation.get_top_card(location),
+        }
+    }
+
+    // The card a move picks up, looked up before the move is made
+    pub fn moving_card(&self, mv: Move) -> Option<Card> {
+        match mv {
+            Move::Draw => None,
+            Move::TableauToTableau(column, _, count) => {
+                let cards = self.tableau.cards.get(column)?;
+                cards.len().checked_sub(count).map(|index| cards[index])
+            }
+            _ => self.get_top_card(mv.source()),
+        }
+    }
+
+    pub fn move_between(&self, from: Location, to: Location) -> Option<Move> {
+        match (from, to) {
+            (Location::Tableau(column), Location::Tableau(target)) => {
+                let count = self.tableau.run_length(from, to)?;
+                Some(Move::TableauToTableau(column, target, count))
+            }
+            _ => Move::between(from, to),
+        }
+    }
+
+    pub fn auto_place_move(&self, from: Location) -> Option<Move> {
+        let card = match from {
+            Location::Tableau(_) | Location::Waste | Location::Reserve => {
+                self.get_top_card(from)?
+            }
+            _ => return None,
+        };
+
+        let slot = self.foundation.find_slot(card)?;
+        Move::between(from, Location::Foundation(slot))
+    }
+
+    // Same checks as apply, without changing anything
+    pub fn can_apply(&self, mv: Move) -> bool {
+        if !self.in_bounds(mv) {
+            return false;
+        }
+
+        let to_column = |card: Option<Card>, column: usize| {
+            card.is_some_and(|card| self.tableau.can_add_card(Location::Tableau(column), card))
+        };
+        match mv {
+            Move::Draw => {
+                !self.stock.cards.is_empty()
+                    || (!self.waste.cards.is_empty() && self.rules.can_recycle(self.stock.recycles))
+            }
+            Move::WasteToTableau(column) => to_column(self.waste.get_top_card(), column),
+            Move::ReserveToTableau(column) => to_column(self.reserve.get_top_card(), column),
+            Move::FoundationToTableau(slot, column) => {
+                to_column(self.get_top_card(Location::Foundation(slot)), column)
+            }
+            Move::WasteToFoundation(slot)
+            | Move::ReserveToFoundation(slot)
+            | Move::TableauToFoundation(_, slot) => self
+                .get_top_card(mv.source())
+                .is_some_and(|card| self.foundation.can_add_card(card, slot)),
+            Move::TableauToTableau(from, to, count) => {
+                self.tableau
+                    .can_move_run(Location::Tableau(from), Location::Tableau(to), count)
+            }
+        }
+    }
+
+    // Every pile the cards at a location could be moved onto
+    pub fn destinations(&self, from: Location) -> Vec<Location> {
+        let columns = (0..self.tableau.cards.len()).map(Location::Tableau);
+        let slots = (0..self.foundation.cards.len()).map(Location::Foundation);
+        columns
+            .chain(slots)
+            .filter(|to| {
+                self.move_between(from, *to)
+                    .is_some_and(|mv| self.can_apply(mv))
+            })
+            .collect()
+    }
+
+    pub fn legal_moves(&self) -> Vec<Move> {
+        let columns = (0..self.tableau.cards.len()).map(Location::Tableau);
+        let slots = (0..self.foundation.cards.len()).map(Location::Foundation);
+        let piles: Vec<Location> = [Location::Waste, Location::Reserve]
+            .into_iter()
+            .chain(columns.clone())
+            .chain(slots.clone())
+            .collect();
+
+        let mut candidates = vec![Move::Draw];
+        for from in piles.iter() {
+            for to in columns.clone().chain(slots.clone()) {
+                if let Some(mv) = self.move_between(*from, to) {
+                    candidates.push(mv);
+                }
+            }
+        }
+
+        candidates
+            .into_iter()
+            .filter(|mv| self.can_apply(*mv))
+            .collect()
+    }
+
+    fn deal_from_stock(&mut self) -> bool {
+        if self.stock.cards.is_empty() && self.waste.cards.is_empty() {
+            return false;
+        }
+
+        if self.stock.cards.is_empty() {
+            if !self.rules.can_recycle(self.stock.recycles) {
+                return false;
+            }
+            self.stock.reset(&self.waste);
+            self.waste.reset();
+            self.events.emit(GameEvent::Recycled);
+        }
+
+        let drawn = self.rules.draw_count.min(self.stock.cards.len());
+        for _ in 0..drawn {
+            self.waste.add(self.stock.deal());
+        }
+
+        self.events.emit(GameEvent::Drew(drawn));
+        true
+    }
+
+    fn take_from_waste(&mut self, column: usize) -> bool {
+        let card_to_place = match self.waste.get_top_card() {
+            Some(card) => card,
+            _ => return false,
+        };
+
+        if !self
+            .tableau
+            .can_add_card(Location::Tableau(column), card_to_place)
+        {
+            return false;
+        }
+
+        self.waste.remove();
+        self.tableau
+            .add_card(Location::Tableau(column), card_to_place);
+        self.events.emit(GameEvent::ToTableau(card_to_place));
+        true
+    }
+
+    fn take_from_reserve(&mut self, column: usize) -> bool {
+        let card_to_place = match self.reserve.get_top_card() {
+            Some(card) => card,
+            _ => return false,
+        };
+
+        if !self
+            .tableau
+            .can_add_card(Location::Tableau(column), card_to_place)
+        {
+            return false;
+        }
+
+        self.reserve.remove();
+        self.tableau
+            .add_card(Location::Tableau(column), card_to_place);
+        self.events.emit(GameEvent::ToTableau(card_to_place));
+        true
+    }
+
+    fn place_in_foundation(&mut self, from: Location, slot: usize) -> bool {
+        let card = match self.get_top_card(from) {
+            Some(card) => card,
+            _ => return false,
+        };
+
+        if !self.foundation.add_card(card, slot) {
+            return false;
+        }
+
+        match from {
+            Location::Waste => self.waste.remove(),
+            Location::Reserve => self.reserve.remove(),
+            Location::Tableau(index) => {
+                self.tableau.update_cutoffs(index);
+                self.tableau.cards[index].pop();
+            }
+            _ => unreachable!("Can't add other than waste, reserve or tableau card"),
+        }
+
+        self.events.emit(GameEvent::ToFoundation(card));
+        true
+    }
+
+    fn take_from_foundation(&mut self, slot: usize, column: usize) -> bool {
+        let location = Location::Foundation(slot);
+        let foundation_card = match self.foundation.get_top_card(location) {
+            Some(card) => card,
+            _ => return false,
+        };
+
+        if !self
+            .tableau
+            .can_add_card(Location::Tableau(column), foundation_card)
+        {
+            return false;
+        }
+
+        self.foundation.remove_card(location);
+        self.tableau
+            .add_card(Location::Tableau(column), foundation_card);
+        self.events.emit(GameEvent::FromFoundation(foundation_card));
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::suit::Suit;
+
+    #[test]
+    fn test_new_is_deterministic() {
+        let first = Game::new(Rules::klondike(), 42);
+        let second = Game::new(Rules::klondike(), 42);
+
+        assert_eq!(first.tableau.cards.len(), 7);
+        assert_eq!(first.stock.cards.len(), 52 - 28);
+        for (a, b) in first.stock.cards.iter().zip(second.stock.cards.iter()) {
+            assert_eq!((a.suit, a.rank), (b.suit, b.rank));
+        }
+    }
+
+    #[test]
+    fn test_new_canfield() {
+        let game = Game::new(Rules::canfield(), 7);
+
+        assert_eq!(game.reserve.cards.len(), 13);
+        assert_eq!(game.tableau.cards.len(), 4);
+        assert_eq!(game.stock.cards.len(), 52 - 1 - 13 - 4);
+        assert!(
+            game.foundation
+                .get_top_card(Location::Foundation(0))
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn test_apply_draw() {
+        let mut game = Game::new(Rules::klondike_by_threes(), 1);
+
+        assert!(game.apply(Move::Draw));
+        assert_eq!(game.waste.cards.len(), 3);
+        assert_eq!(game.moves, 1);
+    }
+
+    #[test]
+    fn test_apply_to_foundation_and_back() {
+        let mut game = Game::new(Rules::klondike(), 1);
+        game.tableau.cards[0] = vec![Card::new(Suit::Hearts, 1)];
+        game.tableau.cards[1] = vec![Card::new(Suit::Spades, 2)];
+        game.tableau.cutoffs = vec![0; 7];
+
+        assert_eq!(
+            game.auto_place_move(Location::Tableau(0)),
+            Some(Move::TableauToFoundation(0, 0))
+        );
+        assert!(game.apply(Move::TableauToFoundation(0, 0)));
+        assert!(game.tableau.cards[0].is_empty());
+
+        assert!(game.apply(Move::FoundationToTableau(0, 1)));
+        assert_eq!(game.tableau.cards[1].len(), 2);
+        assert_eq!(game.moves, 2);
+    }
+
+    #[test]
+    fn test_legal_moves() {
+        let mut game = Game::new(Rules::klondike(), 1);
+        game.tableau.cards[0] = vec![Card::new(Suit::Hearts, 1)];
+        game.tableau.cards[1] = vec![Card::new(Suit::Spades, 2)];
+        game.tableau.cutoffs = vec![0; 7];
+
+        let moves = game.legal_moves();
+        assert!(moves.contains(&Move::Draw));
+        assert!(moves.contains(&Move::TableauToFoundation(0, 0)));
+        assert!(!moves.contains(&Move::TableauToTableau(1, 0, 1)));
+        assert!(moves.iter().all(|mv| game.clone().apply(*mv)));
+    }
+
+    // Every move between two piles, including the ones that get rejected, with every run length
+    fn mock_candidates(game: &Game) -> Vec<Move> {
+        let columns = game.tableau.cards.len();
+        let mut locations = vec![Location::Stock, Location::Waste, Location::Reserve];
+        // One past the last pile of each kind as well
+        locations.extend((0..=columns).map(Location::Tableau));
+        locations.extend((0..=game.foundation.cards.len()).map(Location::Foundation));
+
+        let mut moves = vec![Move::Draw];
+        for from in locations.iter().copied() {
+            for to in locations.iter().copied() {
+                moves.extend(Move::between(from, to));
+                moves.extend(game.move_between(from, to));
+            }
+        }
+        for column in 0..columns {
+            for target in 0..=columns {
+                let count = game.tableau.cards[column].len() + 1;
+                moves
+                    .extend((0..=count).map(|count| Move::TableauToTableau(column, target, count)));
+            }
+        }
+        moves
+    }
+
+    #[test]
+    fn test_can_apply_matches_apply() {
+        for rules in [Rules::klondike(), Rules::canfield()] {
+            let mut game = Game::new(rules, 7);
+
+            for _ in 0..200 {
+                for mv in mock_candidates(&game) {
+                    assert_eq!(game.can_apply(mv), game.clone().apply(mv), "{}", mv);
+                }
+
+                let moves = game.legal_moves();
+                match moves.iter().rev().find(|mv| **mv != Move::Draw) {
+                    Some(mv) if !game.moves.is_multiple_of(3) => game.apply(*mv),
+                    _ => game.apply(Move::Draw),
+                };
+            }
+        }
+    }
+
+    #[test]
+    fn test_destinations() {
+        let mut game = Game::new(Rules::klondike(), 1);
+        game.tableau.cards = vec![Vec::new(); 7];
+        game.tableau.cards[0] = vec![Card::new(Suit::Hearts, 1)];
+        game.tableau.cards[1] = vec![Card::new(Suit::Clubs, 3), Card::new(Suit::Hearts, 2)];
+        game.tableau.cards[2] = vec![Card::new(Suit::Diamonds, 4)];
+        game.tableau.cutoffs = vec![0; 7];
+
+        assert_eq!(
+            game.destinations(Location::Tableau(0)),
+            (0..4).map(Location::Foundation).collect::<Vec<_>>()
+        );
+        assert!(
+            game.destinations(Location::Tableau(1))
+                .contains(&Location::Tableau(2))
+        );
+        assert!(game.destinations(Location::Stock).is_empty());
+    }
+
+    #[test]
+    fn test_apply_emits_events() {
+        let mut game = Game::new(Rules::klondike(), 1);
+        game.tableau.cards[0] = vec![Card::new(Suit::Spades, 9), Card::new(Suit::Hearts, 1)];
+        game.tableau.cutoffs[0] = 1;
+        game.events.drain();
+
+        assert!(game.apply(Move::TableauToFoundation(0, 0)));
+        assert!(game.apply(Move::Draw));
+        assert!(!game.apply(Move::FoundationToTableau(3, 0)));
+
+        assert_eq!(
+            game.events.drain(),
+            [
+                GameEvent::ToFoundation(Card::new(Suit::Hearts, 1)),
+                GameEvent::Moved(Move::TableauToFoundation(0, 0)),
+                GameEvent::Revealed {
+                    column: 0,
+                    hidden_left: 21,
+                    stock_left: 24
+                },
+                GameEvent::Drew(1),
+                GameEvent::Moved(Move::Draw),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_apply_rejects_out_of_bounds() {
+        let mut game = Game::new(Rules::klondike(), 1);
+
+        assert!(!game.apply(Move::WasteToTableau(12)));
+        assert!(!game.apply(Move::TableauToFoundation(0, 4)));
+        assert_eq!(game.moves, 0);
+        assert_eq!(game.moving_card(Move::TableauToTableau(7, 0, 1)), None);
+    }
+}
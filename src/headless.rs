@@ -0,0 +1,53 @@
+use std::io::{BufRead, Write};
+
+use crate::protocol::{Response, Session};
+
+pub fn run(
+    session: &mut Session,
+    input: impl BufRead,
+    mut output: impl Write,
+) -> Result<(), Box<dyn std::error::Error>> {
+    writeln!(
+        output,
+        "{}",
+        serde_json::to_string(&Response::State(session.state()))?
+    )?;
+    output.flush()?;
+
+    for line in input.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        writeln!(output, "{}", session.handle_line(&line))?;
+        output.flush()?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{replay::Replay, rules::Rules};
+
+    #[test]
+    fn test_run() {
+        let mut session = Session::new(Replay::new(3, &Rules::klondike()), true);
+        let input = "{\"cmd\":\"seed\"}\n\n{\"cmd\":\"state\"}\n";
+        let mut output = vec![];
+
+        run(&mut session, input.as_bytes(), &mut output).unwrap();
+        let lines: Vec<String> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(str::to_string)
+            .collect();
+
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with(r#"{"event":"state""#));
+        assert_eq!(lines[1], r#"{"event":"seed","seed":3}"#);
+        assert!(lines[2].starts_with(r#"{"event":"state""#));
+    }
+}
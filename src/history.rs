@@ -0,0 +1,183 @@
+use crate::{
+    card_view::index_label,
+    events::GameEvent,
+    game::Game,
+    i18n::{self, fill},
+    location::Location,
+    moves::Move,
+    replay::Replay,
+};
+
+// A line of the move history, with the number of moves played once it happened
+#[derive(Clone, PartialEq, Debug)]
+pub struct Entry {
+    pub position: usize,
+    pub text: String,
+}
+
+// The entries and the position being looked at, worked out again only once the moves change
+#[derive(Default)]
+pub struct Cache {
+    pub entries: Option<Vec<Entry>>,
+    pub preview: Option<(usize, Game)>,
+}
+
+impl Cache {
+    pub fn invalidate(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn update(&mut self, replay: &Replay, show: bool, preview: Option<usize>) {
+        if show && self.entries.is_none() {
+            self.entries = Some(entries(replay));
+        }
+        match preview {
+            Some(position)
+                if self.preview.as_ref().map(|(cached, _)| *cached) != Some(position) =>
+            {
+                self.preview = Some((position, replay.game_at(position)));
+            }
+            Some(_) => {}
+            None => self.preview = None,
+        }
+    }
+}
+
+pub fn pile_name(location: Location) -> String {
+    let text = i18n::text();
+    match location {
+        Location::Stock => text.history_piles[0].to_string(),
+        Location::Waste => text.history_piles[1].to_string(),
+        Location::Reserve => text.history_piles[2].to_string(),
+        Location::Tableau(column) => fill(text.history_column, &[&(column + 1)]),
+        Location::Foundation(slot) => fill(text.history_foundation, &[&(slot + 1)]),
+    }
+}
+
+// Plays the moves again from the deal, reading what each one turned up from its events
+pub fn entries(replay: &Replay) -> Vec<Entry> {
+    let text = i18n::text();
+    let mut game = replay.game_at(0);
+    game.events.drain();
+
+    let mut entries = vec![Entry {
+        position: 0,
+        text: text.history_deal.to_string(),
+    }];
+    for (index, timed) in replay.moves.iter().enumerate() {
+        let mv = timed.mv;
+        let card = game.moving_card(mv);
+        if !game.apply(mv) {
+            break;
+        }
+
+        let position = index + 1;
+        let mut lines = Vec::new();
+        let card = match mv {
+            Move::Draw => game.waste.get_top_card(),
+            _ => card,
+        };
+        let mut line = format!(
+            "{} → {}",
+            pile_name(mv.source()),
+            pile_name(mv.destination())
+        );
+        if let Some(card) = card {
+            line.push_str(&format!(": {}", index_label(card)));
+        }
+        if let Move::TableauToTableau(_, _, count) = mv
+            && count > 1
+        {
+            line.push_str(&format!(" +{}", count - 1));
+        }
+
+        for event in game.events.drain() {
+            match event {
+                GameEvent::Recycled => lines.push(text.history_recycled.to_string()),
+                GameEvent::Revealed { column, .. } => {
+                    let location = Location::Tableau(column);
+                    if let Some(card) = game.get_top_card(location) {
+                        lines.push(fill(
+                            text.history_reveals,
+                            &[&pile_name(location), &index_label(card)],
+                        ));
+                    }
+                }
+                GameEvent::Moved(_) => lines.push(std::mem::take(&mut line)),
+                _ => {}
+            }
+        }
+        entries.extend(lines.into_iter().map(|text| Entry { position, text }));
+    }
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::Rules;
+
+    fn mock_replay(position: &str, moves: &[Move]) -> Replay {
+        let mut replay = Replay::new(0, &Rules::klondike());
+        replay.position = Some(position.to_string());
+        for mv in moves {
+            replay.push(0, *mv);
+        }
+        replay
+    }
+
+    #[test]
+    fn test_entries() {
+        let position = "Kh|As/|KsQsJsTs9s8s7s6s5s4s3s2s/-/-/-/-/- Kd - -/Qh/Kc/Qd - A 0";
+        let replay = mock_replay(
+            position,
+            &[Move::TableauToFoundation(0, 0), Move::Draw, Move::Draw],
+        );
+
+        let texts: Vec<(usize, String)> = entries(&replay)
+            .into_iter()
+            .map(|entry| (entry.position, entry.text))
+            .collect();
+        assert_eq!(
+            texts,
+            [
+                (0, "Deal".to_string()),
+                (1, "T1 → F1: A♠".to_string()),
+                (1, "T1 reveals K♥".to_string()),
+                (2, "Stock → Waste: K♦".to_string()),
+                (3, "Stock recycled".to_string()),
+                (3, "Stock → Waste: K♦".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cache() {
+        let position = "Kh|As/|KsQsJsTs9s8s7s6s5s4s3s2s/-/-/-/-/- Kd - -/Qh/Kc/Qd - A 0";
+        let mut replay = mock_replay(position, &[Move::TableauToFoundation(0, 0)]);
+        let mut cache = Cache::default();
+
+        cache.update(&replay, false, Some(0));
+        assert!(cache.entries.is_none());
+        assert_eq!(cache.preview.as_ref().unwrap().1.position(), position);
+
+        cache.update(&replay, true, None);
+        assert_eq!(cache.entries.as_ref().unwrap().len(), 3);
+        assert!(cache.preview.is_none());
+
+        // Stays as it was until it is told that the moves changed
+        replay.push(0, Move::Draw);
+        cache.update(&replay, true, None);
+        assert_eq!(cache.entries.as_ref().unwrap().len(), 3);
+        cache.invalidate();
+        cache.update(&replay, true, None);
+        assert_eq!(cache.entries.as_ref().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn test_pile_name() {
+        assert_eq!(pile_name(Location::Tableau(3)), "T4");
+        assert_eq!(pile_name(Location::Foundation(0)), "F1");
+        assert_eq!(pile_name(Location::Reserve), "Reserve");
+    }
+}
@@ -0,0 +1,1180 @@
+use std::{env, fmt::Display, sync::OnceLock};
+
+use crate::suit::Suit;
+
+// Text shown by the game screens. Templates have a {} for each value filled in, in the same
+// order in every language
+pub struct Catalog {
+    pub code: &'static str,
+    pub ranks: [&'static str; 13],
+    pub rank_labels: [&'static str; 13],
+    // Spades, hearts, clubs and diamonds
+    pub suits: [&'static str; 4],
+
+    pub in_stock: &'static str,
+    pub in_stock_pass: &'static str,
+    pub waste: &'static str,
+    pub reserve: &'static str,
+    pub hidden: &'static str,
+    pub foundation: &'static str,
+    pub you_win: &'static str,
+    pub too_small: &'static str,
+    pub too_small_need: &'static str,
+    pub too_small_hint: &'static str,
+
+    pub stats_title: &'static str,
+    pub no_games: &'static str,
+    pub stats_played: &'static str,
+    pub stats_streaks: &'static str,
+    pub stats_bests: &'static str,
+    pub stats_headers: [&'static str; 10],
+    pub months: [&'static str; 12],
+    pub weekdays: [&'static str; 7],
+    pub calendar_title: &'static str,
+    pub calendar_moves: &'static str,
+    pub calendar_tried: &'static str,
+    pub leaderboard_title: &'static str,
+    pub no_high_scores: &'static str,
+    pub leaderboard_headers: [&'static str; 6],
+    pub name_entry_title: &'static str,
+    pub name_entry_score: &'static str,
+    pub name_entry_name: &'static str,
+    pub name_entry_hint: &'static str,
+
+    pub help_title: &'static str,
+    pub help_keys: &'static str,
+    pub help_rules: &'static str,
+    pub help_pile_titles: &'static str,
+    pub help_in_stock: &'static str,
+    pub help_hidden: &'static str,
+    pub help_reserve: &'static str,
+    // Ranks inside a sentence of the rules
+    pub rank_names: [&'static str; 13],
+    pub rules_columns: &'static str,
+    pub rules_decks: &'static str,
+    pub rules_foundation_rank: &'static str,
+    pub rules_foundation_random: &'static str,
+    pub rules_columns_down: &'static str,
+    pub rules_wrap_around: &'static str,
+    pub rules_empty_rank: &'static str,
+    pub rules_empty_any: &'static str,
+    pub rules_empty_none: &'static str,
+    pub rules_reserve: &'static str,
+    pub rules_draw_one: &'static str,
+    pub rules_draw: &'static str,
+    pub rules_passes: &'static str,
+    pub rules_passes_unlimited: &'static str,
+    // Descriptions of the keys in the help overlay
+    pub key_move_left: &'static str,
+    pub key_move_right: &'static str,
+    pub key_move_up: &'static str,
+    pub key_move_down: &'static str,
+    pub key_select: &'static str,
+    pub key_auto_place: &'static str,
+    pub key_undo: &'static str,
+    pub key_new_game: &'static str,
+    pub key_restart: &'static str,
+    pub key_pause: &'static str,
+    pub key_stats: &'static str,
+    pub key_calendar: &'static str,
+    pub key_leaderboard: &'static str,
+    pub key_theme: &'static str,
+    pub key_settings: &'static str,
+    pub key_history: &'static str,
+    pub key_history_back: &'static str,
+    pub key_menu: &'static str,
+    pub key_help: &'static str,
+    pub key_quit: &'static str,
+    pub key_play_pause: &'static str,
+    pub key_next_move: &'static str,
+    pub key_previous_move: &'static str,
+    pub key_faster: &'static str,
+    pub key_slower: &'static str,
+    pub key_close: &'static str,
+    pub key_sort: &'static str,
+    pub key_previous_month: &'static str,
+    pub key_next_month: &'static str,
+    pub key_up: &'static str,
+    pub key_down: &'static str,
+    pub key_previous_rules: &'static str,
+    pub key_next_rules: &'static str,
+    pub key_choose: &'static str,
+    pub key_back: &'static str,
+    pub key_previous_value: &'static str,
+    pub key_next_value: &'static str,
+    pub key_resume: &'static str,
+    pub key_earlier_move: &'static str,
+    pub key_later_move: &'static str,
+    pub key_hide_history: &'static str,
+    pub key_leave: &'static str,
+    pub key_keep_playing: &'static str,
+
+    pub menu_title: &'static str,
+    pub menu_continue: &'static str,
+    pub menu_new_game: &'static str,
+    pub menu_restart: &'static str,
+    pub menu_rules: &'static str,
+    pub menu_stats: &'static str,
+    pub menu_settings: &'static str,
+    pub menu_quit: &'static str,
+    pub menu_hint: &'static str,
+
+    pub settings_title: &'static str,
+    // In the order of the settings screen
+    pub settings_labels: [&'static str; 8],
+    pub settings_next_deal: &'static str,
+    pub settings_saved: &'static str,
+    pub settings_hint: &'static str,
+    pub on: &'static str,
+    pub off: &'static str,
+    pub unlimited: &'static str,
+
+    pub paused: &'static str,
+    pub paused_time: &'static str,
+    pub paused_hint: &'static str,
+    pub confirm_title: &'static str,
+    pub confirm_new_game: &'static str,
+    pub confirm_restart: &'static str,
+    pub confirm_quit: &'static str,
+    pub confirm_loss: &'static str,
+    pub confirm_hint: &'static str,
+
+    pub history_title: &'static str,
+    pub history_preview: &'static str,
+    pub history_hint: &'static str,
+    pub history_deal: &'static str,
+    // Stock, waste and reserve
+    pub history_piles: [&'static str; 3],
+    pub history_column: &'static str,
+    pub history_foundation: &'static str,
+    pub history_reveals: &'static str,
+    pub history_recycled: &'static str,
+
+    pub replay_status: &'static str,
+    pub replay_playing: &'static str,
+    pub replay_finished: &'static str,
+    pub replay_paused: &'static str,
+    pub server_status: &'static str,
+    pub race_title: &'static str,
+    pub race_won: &'static str,
+    pub race_lost: &'static str,
+    pub race_opponent_left: &'static str,
+    pub racing: &'static str,
+    pub race_moves: &'static str,
+    pub achievement_unlocked: &'static str,
+    pub save_failed: &'static str,
+    pub achievements_title: &'static str,
+    // In the order of the achievements list
+    pub achievement_titles: [&'static str; 9],
+    pub achievement_descriptions: [&'static str; 9],
+    pub theme: &'static str,
+    pub theme_error: &'static str,
+
+    // The screen reader mode, which names cards like "7 of hearts" or "Herz 7"
+    pub reader_suits: [&'static str; 4],
+    pub reader_suit_first: bool,
+    // Stock, waste and reserve
+    pub reader_piles: [&'static str; 3],
+    pub reader_welcome: &'static str,
+    pub reader_help_piles: &'static str,
+    pub reader_help_reserve: &'static str,
+    pub reader_help_moves: &'static str,
+    pub reader_help_keys: &'static str,
+    pub reader_help_other: &'static str,
+    pub reader_card: &'static str,
+    pub reader_column: &'static str,
+    pub reader_foundation: &'static str,
+    pub reader_one_card: &'static str,
+    pub reader_cards: &'static str,
+    pub reader_empty: &'static str,
+    pub reader_hidden: &'static str,
+    pub reader_draw: &'static str,
+    pub reader_run: &'static str,
+    pub reader_move: &'static str,
+    pub reader_recycled: &'static str,
+    pub reader_drew: &'static str,
+    pub reader_drew_nothing: &'static str,
+    pub reader_moved: &'static str,
+    pub reader_moved_plain: &'static str,
+    pub reader_revealed: &'static str,
+    pub reader_won: &'static str,
+    pub reader_unknown: &'static str,
+    pub reader_draw_with_d: &'static str,
+    pub reader_nothing_from: &'static str,
+    pub reader_no_foundation: &'static str,
+    pub reader_cannot: &'static str,
+    pub reader_possible: &'static str,
+    pub reader_cant_move: &'static str,
+    pub reader_undid_draw: &'static str,
+    pub reader_undid: &'static str,
+    pub reader_nothing_to_undo: &'static str,
+    pub reader_new_game: &'static str,
+    pub reader_bye: &'static str,
+}
+
+pub const ENGLISH: Catalog = Catalog {
+    code: "en",
+    ranks: [
+        "Ace", "2", "3", "4", "5", "6", "7", "8", "9", "10", "Jack", "Queen", "King",
+    ],
+    rank_labels: [
+        "A", "2", "3", "4", "5", "6", "7", "8", "9", "10", "J", "Q", "K",
+    ],
+    suits: ["Spades", "Hearts", "Clubs", "Diamonds"],
+
+    in_stock: "In stock: {}",
+    in_stock_pass: "In stock: {} (pass {}/{})",
+    waste: "Waste pile",
+    reserve: "Reserve: {}",
+    hidden: "Hidden: {}",
+    foundation: "Foundation",
+    you_win: "You win!",
+    too_small: "Terminal too small",
+    too_small_need: "Need {}x{}, have {}x{}",
+    too_small_hint: "Resize the window or press q to quit",
+
+    stats_title: "Statistics (t or Esc to close)",
+    no_games: "No games played yet.",
+    stats_played: "  Games played: {}  Wins: {} ({}%)",
+    stats_streaks: "  Current streak: {}  Best streak: {}",
+    stats_bests: "  Fastest win: {}  Fewest moves: {}  Average time: {}",
+    stats_headers: [
+        "Variant", "Rules", "Played", "Wins", "Win %", "Streak", "Best", "Fastest", "Fewest",
+        "Average",
+    ],
+    months: [
+        "January",
+        "February",
+        "March",
+        "April",
+        "May",
+        "June",
+        "July",
+        "August",
+        "September",
+        "October",
+        "November",
+        "December",
+    ],
+    weekdays: ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"],
+    calendar_title: "Daily challenge: {} {}, {} completed (←/→ month, c or Esc to close)",
+    calendar_moves: "{} moves",
+    calendar_tried: "tried",
+    leaderboard_title: "High scores: {} scoring, sorted by {} (s to sort, l or Esc to close)",
+    no_high_scores: "No high scores for these rules yet.",
+    leaderboard_headers: ["#", "Name", "Score", "Time", "Moves", "Date"],
+    name_entry_title: "You win! New high score",
+    name_entry_score: "Score {} ({}), {} in {} moves",
+    name_entry_name: "Name: {}_",
+    name_entry_hint: "ENTER to save, ESC to skip",
+
+    help_title: "Help (? or Esc to close)",
+    help_keys: "Keys",
+    help_rules: "Rules",
+    help_pile_titles: "Pile titles",
+    help_in_stock: "In stock: N  cards left to draw, with the current pass when passes are limited",
+    help_hidden: "Hidden: N    face-down cards under the face-up ones in that column",
+    help_reserve: "Reserve: N   cards left in the reserve",
+    rank_names: [
+        "ace", "2", "3", "4", "5", "6", "7", "8", "9", "10", "jack", "queen", "king",
+    ],
+    rules_columns: "{} with {} tableau columns",
+    rules_decks: "{} with {} decks and {} tableau columns",
+    rules_foundation_rank: "Build foundations up by suit, starting with the {}",
+    rules_foundation_random: "Build foundations up by suit from the rank of the first card dealt to them",
+    rules_columns_down: "Build columns down in alternating colors",
+    rules_wrap_around: "Ranks wrap around, so a king goes on an ace and an ace on a 2",
+    rules_empty_rank: "Only a {} can fill an empty column",
+    rules_empty_any: "Any card can fill an empty column",
+    rules_empty_none: "Empty columns stay empty",
+    rules_reserve: "The reserve starts with {} cards, only its top card can be played",
+    rules_draw_one: "Draw 1 card at a time",
+    rules_draw: "Draw {} cards at a time",
+    rules_passes: "{} passes through the stock",
+    rules_passes_unlimited: "as many passes through the stock as you like",
+
+    key_move_left: "Move left",
+    key_move_right: "Move right",
+    key_move_up: "Move up",
+    key_move_down: "Move down",
+    key_select: "Pick up cards, or put them down on the selected pile",
+    key_auto_place: "Send the selected card to a foundation",
+    key_undo: "Undo the last move",
+    key_new_game: "New game",
+    key_restart: "Restart this deal",
+    key_pause: "Pause and hide the board",
+    key_stats: "Statistics",
+    key_calendar: "Daily challenge calendar",
+    key_leaderboard: "High scores",
+    key_theme: "Next color theme",
+    key_settings: "Settings",
+    key_history: "Move history",
+    key_history_back: "Look back through the moves",
+    key_menu: "Menu",
+    key_help: "This help",
+    key_quit: "Quit",
+    key_play_pause: "Play or pause",
+    key_next_move: "Next move",
+    key_previous_move: "Previous move",
+    key_faster: "Faster",
+    key_slower: "Slower",
+    key_close: "Close",
+    key_sort: "Sort by",
+    key_previous_month: "Previous month",
+    key_next_month: "Next month",
+    key_up: "Up",
+    key_down: "Down",
+    key_previous_rules: "Previous rules",
+    key_next_rules: "Next rules",
+    key_choose: "Choose",
+    key_back: "Back to the game",
+    key_previous_value: "Previous value",
+    key_next_value: "Next value",
+    key_resume: "Resume",
+    key_earlier_move: "Earlier move",
+    key_later_move: "Later move",
+    key_hide_history: "Hide the history",
+    key_leave: "Leave the game",
+    key_keep_playing: "Keep playing",
+
+    menu_title: "Menu",
+    menu_continue: "Continue",
+    menu_new_game: "New game",
+    menu_restart: "Restart this deal",
+    menu_rules: "Rules: ◂ {} ▸",
+    menu_stats: "Statistics",
+    menu_settings: "Settings",
+    menu_quit: "Quit",
+    menu_hint: "ENTER to choose, ESC to go back",
+
+    settings_title: "Settings",
+    settings_labels: [
+        "Draw count",
+        "Passes through the stock",
+        "Scoring",
+        "Auto-move to foundations",
+        "Animations",
+        "Theme",
+        "Card style",
+        "Keys",
+    ],
+    settings_next_deal: "next deal",
+    settings_saved: "Saved as you change them",
+    settings_hint: "LEFT / RIGHT to change, ESC to close",
+    on: "on",
+    off: "off",
+    unlimited: "unlimited",
+
+    paused: "Paused",
+    paused_time: "Time {}",
+    paused_hint: "P or SPACE to resume",
+    confirm_title: "Leave this game?",
+    confirm_new_game: "Start a new game?",
+    confirm_restart: "Restart this deal?",
+    confirm_quit: "Quit?",
+    confirm_loss: "The game in progress will count as a loss.",
+    confirm_hint: "Y to leave, N to keep playing",
+
+    history_title: "Moves",
+    history_preview: "Move {} of {}",
+    history_hint: "↑/↓ browse, ESC back",
+    history_deal: "Deal",
+    history_piles: ["Stock", "Waste", "Reserve"],
+    history_column: "T{}",
+    history_foundation: "F{}",
+    history_reveals: "{} reveals {}",
+    history_recycled: "Stock recycled",
+
+    replay_status: " Replay {} | move {}/{} | speed {}x | SPACE play/pause, ←/→ step, +/- speed, q quit",
+    replay_playing: "Playing",
+    replay_finished: "Finished",
+    replay_paused: "Paused",
+    server_status: " Listening on {} | {} connected",
+    race_title: "Opponent",
+    race_won: "You won the race!",
+    race_lost: "Opponent won",
+    race_opponent_left: "Opponent left",
+    racing: "Racing",
+    race_moves: "Moves: {}",
+    achievement_unlocked: "Achievement unlocked",
+    save_failed: "Couldn't save",
+    achievements_title: "Achievements ({}/{})",
+    achievement_titles: [
+        "First win",
+        "Single pass",
+        "Speed run",
+        "No regrets",
+        "Straight up",
+        "Economical",
+        "Clear sight",
+        "On a roll",
+        "Demon tamer",
+    ],
+    achievement_descriptions: [
+        "Win a game",
+        "Win without recycling the stock",
+        "Win in under 3 minutes",
+        "Win without undoing a move",
+        "Win without taking a card back off a foundation",
+        "Win in under 100 moves",
+        "Reveal every tableau card before the stock runs out",
+        "Win 10 games in a row",
+        "Win a game of Canfield",
+    ],
+    theme: "Theme",
+    theme_error: "Theme not loaded",
+
+    reader_suits: ["spades", "hearts", "clubs", "diamonds"],
+    reader_suit_first: false,
+    reader_piles: ["stock", "waste", "reserve"],
+    reader_welcome: "Tersoli, {} drawing {}, seed {}. Type h for help.",
+    reader_help_piles: "Type a pile to read it: s for stock, w for waste,{} f1 to f{} for foundations, 1 to {} for columns.",
+    reader_help_reserve: " r for reserve,",
+    reader_help_moves: "Type two piles to move between them, like w 3, 5 f or 2 7. f alone picks the foundation.",
+    reader_help_keys: "d draws from the stock, l reads the whole table, m lists the possible moves, u takes back a move.",
+    reader_help_other: "n starts a new game, h repeats this help and q quits.",
+    reader_card: "{} of {}",
+    reader_column: "column {}",
+    reader_foundation: "foundation {}",
+    reader_one_card: "1 card",
+    reader_cards: "{} cards",
+    reader_empty: "empty",
+    reader_hidden: "{} hidden",
+    reader_draw: "draw from the stock",
+    reader_run: " and {} on it",
+    reader_move: "{}{} from {} to {}",
+    reader_recycled: "turned the waste over into the stock",
+    reader_drew: "drew {}",
+    reader_drew_nothing: "drew nothing",
+    reader_moved: "moved {}{} to {}",
+    reader_moved_plain: "moved",
+    reader_revealed: ", revealed {}",
+    reader_won: ". You won in {} moves!",
+    reader_unknown: "I don't know '{}', type h for help",
+    reader_draw_with_d: "Draw from the stock with d",
+    reader_nothing_from: "Nothing to move from {}",
+    reader_no_foundation: "No foundation takes the {}",
+    reader_cannot: "Nothing from {} can go to {}",
+    reader_possible: "{} possible moves",
+    reader_cant_move: "Can't move {}",
+    reader_undid_draw: "Took back the draw",
+    reader_undid: "Took back {}",
+    reader_nothing_to_undo: "Nothing to take back",
+    reader_new_game: "New game, seed {}",
+    reader_bye: "Bye",
+};
+
+pub const FINNISH: Catalog = Catalog {
+    code: "fi",
+    ranks: [
+        "Ässä", "2", "3", "4", "5", "6", "7", "8", "9", "10", "Jätkä", "Rouva", "Kuningas",
+    ],
+    rank_labels: [
+        "A", "2", "3", "4", "5", "6", "7", "8", "9", "10", "J", "Q", "K",
+    ],
+    suits: ["Padat", "Hertat", "Ristit", "Ruudut"],
+
+    in_stock: "Pakassa: {}",
+    in_stock_pass: "Pakassa: {} (kierros {}/{})",
+    waste: "Poistopakka",
+    reserve: "Reservi: {}",
+    hidden: "Piilossa: {}",
+    foundation: "Kohdepino",
+    you_win: "Voitit!",
+    too_small: "Pääte on liian pieni",
+    too_small_need: "Tarvitaan {}x{}, nyt {}x{}",
+    too_small_hint: "Suurenna ikkunaa tai lopeta painamalla q",
+
+    stats_title: "Tilastot (t tai Esc sulkee)",
+    no_games: "Ei vielä pelattuja pelejä.",
+    stats_played: "  Pelattu: {}  Voitot: {} ({} %)",
+    stats_streaks: "  Nykyinen putki: {}  Paras putki: {}",
+    stats_bests: "  Nopein voitto: {}  Vähiten siirtoja: {}  Keskimääräinen aika: {}",
+    stats_headers: [
+        "Muunnelma",
+        "Säännöt",
+        "Pelattu",
+        "Voitot",
+        "Voitto %",
+        "Putki",
+        "Paras",
+        "Nopein",
+        "Vähiten",
+        "Keskim.",
+    ],
+    months: [
+        "tammikuu",
+        "helmikuu",
+        "maaliskuu",
+        "huhtikuu",
+        "toukokuu",
+        "kesäkuu",
+        "heinäkuu",
+        "elokuu",
+        "syyskuu",
+        "lokakuu",
+        "marraskuu",
+        "joulukuu",
+    ],
+    weekdays: ["ma", "ti", "ke", "to", "pe", "la", "su"],
+    calendar_title: "Päivän haaste: {} {}, {} ratkaistu (←/→ kuukausi, c tai Esc sulkee)",
+    calendar_moves: "{} siirtoa",
+    calendar_tried: "yritetty",
+    leaderboard_title: "Parhaat tulokset: pisteytys {}, järjestys {} (s järjestää, l tai Esc sulkee)",
+    no_high_scores: "Näillä säännöillä ei ole vielä tuloksia.",
+    leaderboard_headers: ["#", "Nimi", "Pisteet", "Aika", "Siirrot", "Päivä"],
+    name_entry_title: "Voitit! Uusi ennätys",
+    name_entry_score: "Pisteet {} ({}), aika {}, {} siirtoa",
+    name_entry_name: "Nimi: {}_",
+    name_entry_hint: "ENTER tallentaa, ESC ohittaa",
+
+    help_title: "Ohje (? tai Esc sulkee)",
+    help_keys: "Näppäimet",
+    help_rules: "Säännöt",
+    help_pile_titles: "Pinojen otsikot",
+    help_in_stock: "Pakassa: N   nostamatta olevat kortit ja kierros, jos kierroksia on rajattu",
+    help_hidden: "Piilossa: N  kuvapuoli alaspäin olevat kortit sarakkeen näkyvien alla",
+    help_reserve: "Reservi: N   reserviin jääneet kortit",
+    rank_names: [
+        "ässä", "2", "3", "4", "5", "6", "7", "8", "9", "10", "jätkä", "rouva", "kuningas",
+    ],
+    rules_columns: "{}, {} saraketta",
+    rules_decks: "{}, {} pakkaa ja {} saraketta",
+    rules_foundation_rank: "Kohdepinot rakennetaan maittain nousevasti, ensimmäisenä {}",
+    rules_foundation_random: "Kohdepinot nousevasti maittain, alkaen niihin ensin jaetun kortin arvosta",
+    rules_columns_down: "Sarakkeet rakennetaan laskevasti vuorotellen eri väreistä",
+    rules_wrap_around: "Arvot jatkuvat ympäri, joten kuningas käy ässän ja ässä kakkosen päälle",
+    rules_empty_rank: "Vain {} voi täyttää tyhjän sarakkeen",
+    rules_empty_any: "Mikä tahansa kortti voi täyttää tyhjän sarakkeen",
+    rules_empty_none: "Tyhjät sarakkeet jäävät tyhjiksi",
+    rules_reserve: "Reservissä on alussa {} korttia, vain päällimmäistä voi pelata",
+    rules_draw_one: "Nosta 1 kortti kerrallaan",
+    rules_draw: "Nosta {} korttia kerrallaan",
+    rules_passes: "{} kierrosta pakan läpi",
+    rules_passes_unlimited: "rajattomasti kierroksia pakan läpi",
+
+    key_move_left: "Siirry vasemmalle",
+    key_move_right: "Siirry oikealle",
+    key_move_up: "Siirry ylös",
+    key_move_down: "Siirry alas",
+    key_select: "Nosta kortit tai laske ne valitulle pinolle",
+    key_auto_place: "Siirrä valittu kortti kohdepinoon",
+    key_undo: "Peru viimeisin siirto",
+    key_new_game: "Uusi peli",
+    key_restart: "Aloita jako alusta",
+    key_pause: "Tauko, pöytä piilotetaan",
+    key_stats: "Tilastot",
+    key_calendar: "Päivän haasteen kalenteri",
+    key_leaderboard: "Parhaat tulokset",
+    key_theme: "Seuraava väriteema",
+    key_settings: "Asetukset",
+    key_history: "Siirtohistoria",
+    key_history_back: "Selaa aiempia siirtoja",
+    key_menu: "Valikko",
+    key_help: "Tämä ohje",
+    key_quit: "Lopeta",
+    key_play_pause: "Toista tai pidä tauko",
+    key_next_move: "Seuraava siirto",
+    key_previous_move: "Edellinen siirto",
+    key_faster: "Nopeammin",
+    key_slower: "Hitaammin",
+    key_close: "Sulje",
+    key_sort: "Järjestä",
+    key_previous_month: "Edellinen kuukausi",
+    key_next_month: "Seuraava kuukausi",
+    key_up: "Ylös",
+    key_down: "Alas",
+    key_previous_rules: "Edelliset säännöt",
+    key_next_rules: "Seuraavat säännöt",
+    key_choose: "Valitse",
+    key_back: "Takaisin peliin",
+    key_previous_value: "Edellinen arvo",
+    key_next_value: "Seuraava arvo",
+    key_resume: "Jatka",
+    key_earlier_move: "Aiempi siirto",
+    key_later_move: "Myöhempi siirto",
+    key_hide_history: "Piilota historia",
+    key_leave: "Poistu pelistä",
+    key_keep_playing: "Jatka peliä",
+
+    menu_title: "Valikko",
+    menu_continue: "Jatka",
+    menu_new_game: "Uusi peli",
+    menu_restart: "Aloita jako alusta",
+    menu_rules: "Säännöt: ◂ {} ▸",
+    menu_stats: "Tilastot",
+    menu_settings: "Asetukset",
+    menu_quit: "Lopeta",
+    menu_hint: "ENTER valitsee, ESC palaa",
+
+    settings_title: "Asetukset",
+    settings_labels: [
+        "Nostettavat kortit",
+        "Kierrokset pakan läpi",
+        "Pisteytys",
+        "Automaattisiirrot",
+        "Animaatiot",
+        "Teema",
+        "Korttien tyyli",
+        "Näppäimet",
+    ],
+    settings_next_deal: "seuraava jako",
+    settings_saved: "Muutokset tallentuvat heti",
+    settings_hint: "VASEN / OIKEA muuttaa, ESC sulkee",
+    on: "päällä",
+    off: "pois",
+    unlimited: "rajaton",
+
+    paused: "Tauko",
+    paused_time: "Aika {}",
+    paused_hint: "P tai VÄLILYÖNTI jatkaa",
+    confirm_title: "Poistutaanko pelistä?",
+    confirm_new_game: "Aloitetaanko uusi peli?",
+    confirm_restart: "Aloitetaanko jako alusta?",
+    confirm_quit: "Lopetetaanko?",
+    confirm_loss: "Kesken oleva peli lasketaan häviöksi.",
+    confirm_hint: "Y poistuu, N jatkaa peliä",
+
+    history_title: "Siirrot",
+    history_preview: "Siirto {}/{}",
+    history_hint: "↑/↓ selaa, ESC palaa",
+    history_deal: "Jako",
+    history_piles: ["Pakka", "Poisto", "Reservi"],
+    history_column: "S{}",
+    history_foundation: "K{}",
+    history_reveals: "{} paljastaa {}",
+    history_recycled: "Pakka käännetty",
+
+    replay_status: " Toisto {} | siirto {}/{} | nopeus {}x | VÄLILYÖNTI toisto/tauko, ←/→ askel, +/- nopeus, q lopeta",
+    replay_playing: "käynnissä",
+    replay_finished: "päättynyt",
+    replay_paused: "tauolla",
+    server_status: " Kuunnellaan osoitteessa {} | {} yhdistetty",
+    race_title: "Vastustaja",
+    race_won: "Voitit kisan!",
+    race_lost: "Vastustaja voitti",
+    race_opponent_left: "Vastustaja poistui",
+    racing: "Kisa käynnissä",
+    race_moves: "Siirrot: {}",
+    achievement_unlocked: "Saavutus avattu",
+    save_failed: "Tallennus epäonnistui",
+    achievements_title: "Saavutukset ({}/{})",
+    achievement_titles: [
+        "Ensimmäinen voitto",
+        "Yksi kierros",
+        "Pikajuoksu",
+        "Ei katumista",
+        "Suoraan ylös",
+        "Säästeliäs",
+        "Selvä näkymä",
+        "Vauhdissa",
+        "Demonin kesyttäjä",
+    ],
+    achievement_descriptions: [
+        "Voita peli",
+        "Voita kääntämättä pakkaa",
+        "Voita alle 3 minuutissa",
+        "Voita perumatta yhtään siirtoa",
+        "Voita ottamatta korttia takaisin kohdepinosta",
+        "Voita alle 100 siirrolla",
+        "Paljasta kaikki sarakkeiden kortit ennen kuin pakka loppuu",
+        "Voita 10 peliä putkeen",
+        "Voita Canfield-peli",
+    ],
+    theme: "Teema",
+    theme_error: "Teemaa ei ladattu",
+
+    reader_suits: ["pata", "hertta", "risti", "ruutu"],
+    reader_suit_first: true,
+    reader_piles: ["pakka", "poistopakka", "reservi"],
+    reader_welcome: "Tersoli, {}, nostetaan {}, siemen {}. Ohje: h.",
+    reader_help_piles: "Lue pino kirjoittamalla sen nimi: s pakka, w poistopakka,{} f1–f{} kohdepinot, 1–{} sarakkeet.",
+    reader_help_reserve: " r reservi,",
+    reader_help_moves: "Siirrä kirjoittamalla kaksi pinoa, esimerkiksi w 3, 5 f tai 2 7. Pelkkä f valitsee kohdepinon.",
+    reader_help_keys: "d nostaa pakasta, l lukee koko pöydän, m luettelee mahdolliset siirrot, u peruu siirron.",
+    reader_help_other: "n aloittaa uuden pelin, h toistaa tämän ohjeen ja q lopettaa.",
+    reader_card: "{} {}",
+    reader_column: "sarake {}",
+    reader_foundation: "kohdepino {}",
+    reader_one_card: "1 kortti",
+    reader_cards: "{} korttia",
+    reader_empty: "tyhjä",
+    reader_hidden: "{} piilossa",
+    reader_draw: "nosto pakasta",
+    reader_run: " ja sen päällä {}",
+    reader_move: "{}{} kohteesta {} kohteeseen {}",
+    reader_recycled: "poistopakka käännettiin pakaksi",
+    reader_drew: "nostettiin {}",
+    reader_drew_nothing: "mitään ei nostettu",
+    reader_moved: "siirrettiin {}{} kohteeseen {}",
+    reader_moved_plain: "siirretty",
+    reader_revealed: ", paljastui {}",
+    reader_won: ". Voitit {} siirrolla!",
+    reader_unknown: "En tunne komentoa '{}', ohje: h",
+    reader_draw_with_d: "Nosta pakasta komennolla d",
+    reader_nothing_from: "Kohteessa {} ei ole siirrettävää",
+    reader_no_foundation: "Mikään kohdepino ei ota korttia {}",
+    reader_cannot: "Kohteesta {} ei voi siirtää kohteeseen {}",
+    reader_possible: "{} mahdollista siirtoa",
+    reader_cant_move: "Ei voi siirtää: {}",
+    reader_undid_draw: "Nosto peruttiin",
+    reader_undid: "Peruttiin {}",
+    reader_nothing_to_undo: "Ei mitään peruttavaa",
+    reader_new_game: "Uusi peli, siemen {}",
+    reader_bye: "Hei hei",
+};
+
+pub const GERMAN: Catalog = Catalog {
+    code: "de",
+    ranks: [
+        "Ass", "2", "3", "4", "5", "6", "7", "8", "9", "10", "Bube", "Dame", "König",
+    ],
+    rank_labels: [
+        "A", "2", "3", "4", "5", "6", "7", "8", "9", "10", "B", "D", "K",
+    ],
+    suits: ["Pik", "Herz", "Kreuz", "Karo"],
+
+    in_stock: "Im Stapel: {}",
+    in_stock_pass: "Im Stapel: {} (Runde {}/{})",
+    waste: "Ablagestapel",
+    reserve: "Reserve: {}",
+    hidden: "Verdeckt: {}",
+    foundation: "Zielstapel",
+    you_win: "Gewonnen!",
+    too_small: "Terminal zu klein",
+    too_small_need: "Benötigt {}x{}, vorhanden {}x{}",
+    too_small_hint: "Fenster vergrößern oder mit q beenden",
+
+    stats_title: "Statistik (t oder Esc zum Schließen)",
+    no_games: "Noch keine Spiele gespielt.",
+    stats_played: "  Gespielt: {}  Siege: {} ({} %)",
+    stats_streaks: "  Aktuelle Serie: {}  Beste Serie: {}",
+    stats_bests: "  Schnellster Sieg: {}  Wenigste Züge: {}  Durchschnittszeit: {}",
+    stats_headers: [
+        "Variante",
+        "Regeln",
+        "Gespielt",
+        "Siege",
+        "Sieg %",
+        "Serie",
+        "Beste",
+        "Schnellste",
+        "Wenigste",
+        "Schnitt",
+    ],
+    months: [
+        "Januar",
+        "Februar",
+        "März",
+        "April",
+        "Mai",
+        "Juni",
+        "Juli",
+        "August",
+        "September",
+        "Oktober",
+        "November",
+        "Dezember",
+    ],
+    weekdays: ["Mo", "Di", "Mi", "Do", "Fr", "Sa", "So"],
+    calendar_title: "Tägliche Herausforderung: {} {}, {} geschafft (←/→ Monat, c oder Esc zum Schließen)",
+    calendar_moves: "{} Züge",
+    calendar_tried: "versucht",
+    leaderboard_title: "Bestenliste: Wertung {}, sortiert nach {} (s sortiert, l oder Esc zum Schließen)",
+    no_high_scores: "Noch keine Bestwerte für diese Regeln.",
+    leaderboard_headers: ["#", "Name", "Punkte", "Zeit", "Züge", "Datum"],
+    name_entry_title: "Gewonnen! Neuer Bestwert",
+    name_entry_score: "Punkte {} ({}), {} in {} Zügen",
+    name_entry_name: "Name: {}_",
+    name_entry_hint: "ENTER speichert, ESC überspringt",
+
+    help_title: "Hilfe (? oder Esc zum Schließen)",
+    help_keys: "Tasten",
+    help_rules: "Regeln",
+    help_pile_titles: "Stapeltitel",
+    help_in_stock: "Im Stapel: N  noch zu ziehende Karten, mit der Runde wenn Runden begrenzt sind",
+    help_hidden: "Verdeckt: N   verdeckte Karten unter den offenen in dieser Spalte",
+    help_reserve: "Reserve: N    Karten, die noch in der Reserve liegen",
+    rank_names: [
+        "Ass", "2", "3", "4", "5", "6", "7", "8", "9", "10", "Bube", "Dame", "König",
+    ],
+    rules_columns: "{} mit {} Spalten",
+    rules_decks: "{} mit {} Kartenspielen und {} Spalten",
+    rules_foundation_rank: "Zielstapel nach Farbe aufsteigend bauen, beginnend mit {}",
+    rules_foundation_random: "Zielstapel nach Farbe aufsteigend, ab dem Wert der ersten ausgeteilten Karte",
+    rules_columns_down: "Spalten absteigend in abwechselnden Farben bauen",
+    rules_wrap_around: "Die Werte laufen im Kreis, ein König passt auf ein Ass und ein Ass auf eine 2",
+    rules_empty_rank: "Leere Spalten dürfen nur mit {} gefüllt werden",
+    rules_empty_any: "Jede Karte darf eine leere Spalte füllen",
+    rules_empty_none: "Leere Spalten bleiben leer",
+    rules_reserve: "Die Reserve beginnt mit {} Karten, nur die oberste ist spielbar",
+    rules_draw_one: "1 Karte pro Zug ziehen",
+    rules_draw: "{} Karten pro Zug ziehen",
+    rules_passes: "{} Runden durch den Stapel",
+    rules_passes_unlimited: "beliebig viele Runden durch den Stapel",
+
+    key_move_left: "Nach links",
+    key_move_right: "Nach rechts",
+    key_move_up: "Nach oben",
+    key_move_down: "Nach unten",
+    key_select: "Karten aufnehmen oder auf den gewählten Stapel legen",
+    key_auto_place: "Gewählte Karte auf einen Zielstapel legen",
+    key_undo: "Letzten Zug zurücknehmen",
+    key_new_game: "Neues Spiel",
+    key_restart: "Dieses Spiel neu starten",
+    key_pause: "Pause, Spielfeld ausblenden",
+    key_stats: "Statistik",
+    key_calendar: "Kalender der täglichen Herausforderung",
+    key_leaderboard: "Bestenliste",
+    key_theme: "Nächstes Farbschema",
+    key_settings: "Einstellungen",
+    key_history: "Zugverlauf",
+    key_history_back: "Frühere Züge ansehen",
+    key_menu: "Menü",
+    key_help: "Diese Hilfe",
+    key_quit: "Beenden",
+    key_play_pause: "Abspielen oder pausieren",
+    key_next_move: "Nächster Zug",
+    key_previous_move: "Vorheriger Zug",
+    key_faster: "Schneller",
+    key_slower: "Langsamer",
+    key_close: "Schließen",
+    key_sort: "Sortieren nach",
+    key_previous_month: "Vorheriger Monat",
+    key_next_month: "Nächster Monat",
+    key_up: "Hoch",
+    key_down: "Runter",
+    key_previous_rules: "Vorherige Regeln",
+    key_next_rules: "Nächste Regeln",
+    key_choose: "Auswählen",
+    key_back: "Zurück zum Spiel",
+    key_previous_value: "Vorheriger Wert",
+    key_next_value: "Nächster Wert",
+    key_resume: "Fortsetzen",
+    key_earlier_move: "Früherer Zug",
+    key_later_move: "Späterer Zug",
+    key_hide_history: "Verlauf ausblenden",
+    key_leave: "Spiel verlassen",
+    key_keep_playing: "Weiterspielen",
+
+    menu_title: "Menü",
+    menu_continue: "Weiterspielen",
+    menu_new_game: "Neues Spiel",
+    menu_restart: "Dieses Spiel neu starten",
+    menu_rules: "Regeln: ◂ {} ▸",
+    menu_stats: "Statistik",
+    menu_settings: "Einstellungen",
+    menu_quit: "Beenden",
+    menu_hint: "ENTER wählt, ESC geht zurück",
+
+    settings_title: "Einstellungen",
+    settings_labels: [
+        "Karten pro Zug",
+        "Runden durch den Stapel",
+        "Wertung",
+        "Automatisch ablegen",
+        "Animationen",
+        "Farbschema",
+        "Kartenstil",
+        "Tasten",
+    ],
+    settings_next_deal: "nächstes Spiel",
+    settings_saved: "Änderungen werden sofort gespeichert",
+    settings_hint: "LINKS / RECHTS ändert, ESC schließt",
+    on: "an",
+    off: "aus",
+    unlimited: "unbegrenzt",
+
+    paused: "Pause",
+    paused_time: "Zeit {}",
+    paused_hint: "P oder LEERTASTE zum Fortsetzen",
+    confirm_title: "Spiel verlassen?",
+    confirm_new_game: "Neues Spiel starten?",
+    confirm_restart: "Dieses Spiel neu starten?",
+    confirm_quit: "Beenden?",
+    confirm_loss: "Das laufende Spiel zählt als verloren.",
+    confirm_hint: "Y zum Verlassen, N zum Weiterspielen",
+
+    history_title: "Züge",
+    history_preview: "Zug {} von {}",
+    history_hint: "↑/↓ blättern, ESC zurück",
+    history_deal: "Austeilen",
+    history_piles: ["Stapel", "Ablage", "Reserve"],
+    history_column: "R{}",
+    history_foundation: "Z{}",
+    history_reveals: "{} deckt {} auf",
+    history_recycled: "Stapel umgedreht",
+
+    replay_status: " Wiederholung {} | Zug {}/{} | Tempo {}x | LEERTASTE Start/Pause, ←/→ Schritt, +/- Tempo, q Ende",
+    replay_playing: "läuft",
+    replay_finished: "beendet",
+    replay_paused: "pausiert",
+    server_status: " Wartet auf {} | {} verbunden",
+    race_title: "Gegner",
+    race_won: "Du hast das Rennen gewonnen!",
+    race_lost: "Der Gegner hat gewonnen",
+    race_opponent_left: "Der Gegner ist gegangen",
+    racing: "Rennen läuft",
+    race_moves: "Züge: {}",
+    achievement_unlocked: "Erfolg freigeschaltet",
+    save_failed: "Speichern fehlgeschlagen",
+    achievements_title: "Erfolge ({}/{})",
+    achievement_titles: [
+        "Erster Sieg",
+        "Eine Runde",
+        "Schnelllauf",
+        "Keine Reue",
+        "Geradeaus",
+        "Sparsam",
+        "Klare Sicht",
+        "Lauf",
+        "Dämonenbändiger",
+    ],
+    achievement_descriptions: [
+        "Gewinne ein Spiel",
+        "Gewinne, ohne den Stapel umzudrehen",
+        "Gewinne in unter 3 Minuten",
+        "Gewinne, ohne einen Zug zurückzunehmen",
+        "Gewinne, ohne eine Karte von einem Zielstapel zurückzunehmen",
+        "Gewinne in unter 100 Zügen",
+        "Decke alle Spaltenkarten auf, bevor der Stapel leer ist",
+        "Gewinne 10 Spiele in Folge",
+        "Gewinne eine Partie Canfield",
+    ],
+    theme: "Farbschema",
+    theme_error: "Farbschema nicht geladen",
+
+    reader_suits: ["Pik", "Herz", "Kreuz", "Karo"],
+    reader_suit_first: true,
+    reader_piles: ["Stapel", "Ablage", "Reserve"],
+    reader_welcome: "Tersoli, {}, {} pro Zug, Seed {}. Hilfe mit h.",
+    reader_help_piles: "Einen Stapel vorlesen: s Stapel, w Ablage,{} f1 bis f{} Zielstapel, 1 bis {} Spalten.",
+    reader_help_reserve: " r Reserve,",
+    reader_help_moves: "Zwei Stapel eingeben, um zwischen ihnen zu ziehen, etwa w 3, 5 f oder 2 7. f allein wählt den Zielstapel.",
+    reader_help_keys: "d zieht vom Stapel, l liest den ganzen Tisch, m nennt die möglichen Züge, u nimmt einen Zug zurück.",
+    reader_help_other: "n startet ein neues Spiel, h wiederholt diese Hilfe und q beendet.",
+    reader_card: "{} {}",
+    reader_column: "Spalte {}",
+    reader_foundation: "Zielstapel {}",
+    reader_one_card: "1 Karte",
+    reader_cards: "{} Karten",
+    reader_empty: "leer",
+    reader_hidden: "{} verdeckt",
+    reader_draw: "vom Stapel ziehen",
+    reader_run: " und {} darauf",
+    reader_move: "{}{} von {} nach {}",
+    reader_recycled: "Ablage zurück in den Stapel gedreht",
+    reader_drew: "{} gezogen",
+    reader_drew_nothing: "nichts gezogen",
+    reader_moved: "{}{} nach {} gezogen",
+    reader_moved_plain: "gezogen",
+    reader_revealed: ", {} aufgedeckt",
+    reader_won: ". Gewonnen in {} Zügen!",
+    reader_unknown: "'{}' ist unbekannt, h zeigt die Hilfe",
+    reader_draw_with_d: "Vom Stapel ziehen mit d",
+    reader_nothing_from: "Auf {} liegt nichts zum Ziehen",
+    reader_no_foundation: "Kein Zielstapel nimmt {}",
+    reader_cannot: "Von {} kann nichts nach {}",
+    reader_possible: "{} mögliche Züge",
+    reader_cant_move: "Nicht möglich: {}",
+    reader_undid_draw: "Ziehen zurückgenommen",
+    reader_undid: "Zurückgenommen: {}",
+    reader_nothing_to_undo: "Nichts zurückzunehmen",
+    reader_new_game: "Neues Spiel, Seed {}",
+    reader_bye: "Tschüss",
+};
+
+pub const CATALOGS: [&Catalog; 3] = [&ENGLISH, &FINNISH, &GERMAN];
+
+static CURRENT: OnceLock<&'static Catalog> = OnceLock::new();
+
+// Picked once at startup, everything drawn after that reads from it
+pub fn set_catalog(catalog: &'static Catalog) {
+    let _ = CURRENT.set(catalog);
+}
+
+// English until a catalog is picked, which keeps tests independent of the environment
+pub fn text() -> &'static Catalog {
+    CURRENT.get().copied().unwrap_or(&ENGLISH)
+}
+
+impl Catalog {
+    pub fn find(code: &str) -> Option<&'static Catalog> {
+        CATALOGS.into_iter().find(|catalog| catalog.code == code)
+    }
+
+    pub fn detect(language: &str) -> &'static Catalog {
+        Self::from_env(|name| env::var(name).ok(), language)
+    }
+
+    // The config's language wins, then the locale like fi_FI.UTF-8, falling back to English
+    pub fn from_env(var: impl Fn(&str) -> Option<String>, language: &str) -> &'static Catalog {
+        let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .into_iter()
+            .find_map(|name| var(name).filter(|value| !value.is_empty()))
+            .unwrap_or_default();
+        let code = if language.is_empty() {
+            locale.split(['_', '.', '-']).next().unwrap_or_default()
+        } else {
+            language
+        };
+        Self::find(&code.to_lowercase()).unwrap_or(&ENGLISH)
+    }
+
+    pub fn rank(&self, rank: u8) -> &'static str {
+        self.ranks
+            .get(rank as usize - 1)
+            .copied()
+            .unwrap_or("Error")
+    }
+
+    pub fn rank_label(&self, rank: u8) -> &'static str {
+        self.rank_labels
+            .get(rank as usize - 1)
+            .copied()
+            .unwrap_or("?")
+    }
+
+    pub fn suit(&self, suit: Suit) -> &'static str {
+        match suit {
+            Suit::Spades => self.suits[0],
+            Suit::Hearts => self.suits[1],
+            Suit::Clubs => self.suits[2],
+            Suit::Diamonds => self.suits[3],
+        }
+    }
+}
+
+// Puts the values into a template's {} in order
+pub fn fill(template: &str, values: &[&dyn Display]) -> String {
+    let mut parts = template.split("{}");
+    let mut text = parts.next().unwrap_or_default().to_string();
+    for (index, part) in parts.enumerate() {
+        if let Some(value) = values.get(index) {
+            text.push_str(&value.to_string());
+        }
+        text.push_str(part);
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mock_templates(catalog: &Catalog) -> Vec<&'static str> {
+        vec![
+            catalog.in_stock,
+            catalog.in_stock_pass,
+            catalog.reserve,
+            catalog.hidden,
+            catalog.too_small_need,
+            catalog.calendar_title,
+            catalog.calendar_moves,
+            catalog.leaderboard_title,
+            catalog.name_entry_score,
+            catalog.name_entry_name,
+            catalog.menu_rules,
+            catalog.paused_time,
+            catalog.history_preview,
+            catalog.history_column,
+            catalog.history_foundation,
+            catalog.history_reveals,
+            catalog.replay_status,
+            catalog.server_status,
+            catalog.race_moves,
+            catalog.stats_played,
+            catalog.stats_streaks,
+            catalog.stats_bests,
+            catalog.rules_columns,
+            catalog.rules_decks,
+            catalog.rules_foundation_rank,
+            catalog.rules_empty_rank,
+            catalog.rules_reserve,
+            catalog.rules_draw,
+            catalog.rules_passes,
+            catalog.achievements_title,
+            catalog.reader_welcome,
+            catalog.reader_help_piles,
+            catalog.reader_card,
+            catalog.reader_column,
+            catalog.reader_foundation,
+            catalog.reader_cards,
+            catalog.reader_hidden,
+            catalog.reader_run,
+            catalog.reader_move,
+            catalog.reader_drew,
+            catalog.reader_moved,
+            catalog.reader_revealed,
+            catalog.reader_won,
+            catalog.reader_unknown,
+            catalog.reader_nothing_from,
+            catalog.reader_no_foundation,
+            catalog.reader_cannot,
+            catalog.reader_possible,
+            catalog.reader_cant_move,
+            catalog.reader_undid,
+            catalog.reader_new_game,
+        ]
+    }
+
+    #[test]
+    fn test_detect() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                vars.iter()
+                    .find(|(var, _)| *var == name)
+                    .map(|(_, value)| value.to_string())
+            }
+        };
+
+        assert_eq!(
+            Catalog::from_env(env(&[("LANG", "fi_FI.UTF-8")]), "").code,
+            "fi"
+        );
+        assert_eq!(
+            Catalog::from_env(env(&[("LANG", "fi_FI.UTF-8"), ("LC_ALL", "de_DE")]), "").code,
+            "de"
+        );
+        assert_eq!(
+            Catalog::from_env(env(&[("LANG", "fi_FI.UTF-8")]), "de").code,
+            "de"
+        );
+        assert_eq!(Catalog::from_env(env(&[("LANG", "C")]), "").code, "en");
+        assert_eq!(Catalog::from_env(env(&[]), "sv").code, "en");
+    }
+
+    #[test]
+    fn test_fill() {
+        assert_eq!(
+            fill(ENGLISH.in_stock_pass, &[&24, &1, &3]),
+            "In stock: 24 (pass 1/3)"
+        );
+        assert_eq!(fill(GERMAN.hidden, &[&4]), "Verdeckt: 4");
+    }
+
+    #[test]
+    fn test_same_placeholders() {
+        for catalog in CATALOGS {
+            for (english, translated) in
+                mock_templates(&ENGLISH).iter().zip(mock_templates(catalog))
+            {
+                assert_eq!(
+                    english.matches("{}").count(),
+                    translated.matches("{}").count(),
+                    "{} in {}",
+                    translated,
+                    catalog.code
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_ranks() {
+        assert_eq!(GERMAN.rank(12), "Dame");
+        assert_eq!(GERMAN.rank_label(11), "B");
+        assert_eq!(FINNISH.rank(1), "Ässä");
+        assert_eq!(ENGLISH.rank_label(10), "10");
+    }
+}
@@ -0,0 +1,500 @@
+use ratatui::crossterm::event::KeyCode;
+use serde::{Deserialize, Serialize};
+
+use crate::{command::Command, i18n::Catalog};
+
+// Every screen's keys, handling input and the help overlay both read them from here
+pub struct Binding {
+    pub keys: &'static [KeyCode],
+    pub command: Command,
+    // Read from the catalog of the language that was picked
+    pub description: fn(&Catalog) -> &'static str,
+}
+
+const fn bind(
+    keys: &'static [KeyCode],
+    command: Command,
+    description: fn(&Catalog) -> &'static str,
+) -> Binding {
+    Binding {
+        keys,
+        command,
+        description,
+    }
+}
+
+pub const GAME: &[Binding] = &[
+    bind(
+        &[KeyCode::Left, KeyCode::Char('a')],
+        Command::MoveLeft,
+        |text| text.key_move_left,
+    ),
+    bind(
+        &[KeyCode::Right, KeyCode::Char('d')],
+        Command::MoveRight,
+        |text| text.key_move_right,
+    ),
+    bind(
+        &[KeyCode::Up, KeyCode::Char('w')],
+        Command::MoveUp,
+        |text| text.key_move_up,
+    ),
+    bind(
+        &[KeyCode::Down, KeyCode::Char('s')],
+        Command::MoveDown,
+        |text| text.key_move_down,
+    ),
+    bind(&[KeyCode::Enter], Command::Select, |text| text.key_select),
+    bind(&[KeyCode::Char(' ')], Command::AutoPlace, |text| {
+        text.key_auto_place
+    }),
+    bind(&[KeyCode::Char('u')], Command::Undo, |text| text.key_undo),
+    bind(&[KeyCode::Char('n')], Command::NewGame, |text| {
+        text.key_new_game
+    }),
+    bind(&[KeyCode::Char('r')], Command::RestartDeal, |text| {
+        text.key_restart
+    }),
+    bind(&[KeyCode::Char('p')], Command::TogglePause, |text| {
+        text.key_pause
+    }),
+    bind(&[KeyCode::Char('t')], Command::ToggleStats, |text| {
+        text.key_stats
+    }),
+    bind(&[KeyCode::Char('c')], Command::ToggleCalendar, |text| {
+        text.key_calendar
+    }),
+    bind(&[KeyCode::Char('l')], Command::ToggleLeaderboard, |text| {
+        text.key_leaderboard
+    }),
+    bind(&[KeyCode::Char('T')], Command::CycleTheme, |text| {
+        text.key_theme
+    }),
+    bind(&[KeyCode::Char('o')], Command::ToggleSettings, |text| {
+        text.key_settings
+    }),
+    bind(&[KeyCode::Char('v')], Command::ToggleHistory, |text| {
+        text.key_history
+    }),
+    bind(&[KeyCode::Char('[')], Command::HistoryBack, |text| {
+        text.key_history_back
+    }),
+    bind(
+        &[KeyCode::Esc, KeyCode::Char('m')],
+        Command::ToggleMenu,
+        |text| text.key_menu,
+    ),
+    bind(
+        &[KeyCode::Char('?'), KeyCode::F(1)],
+        Command::ToggleHelp,
+        |text| text.key_help,
+    ),
+    bind(&[KeyCode::Char('q')], Command::Quit, |text| text.key_quit),
+];
+
+// Moves with h j k l like vim, which leaves the high scores on L
+pub const GAME_VIM: &[Binding] = &[
+    bind(
+        &[KeyCode::Left, KeyCode::Char('h')],
+        Command::MoveLeft,
+        |text| text.key_move_left,
+    ),
+    bind(
+        &[KeyCode::Right, KeyCode::Char('l')],
+        Command::MoveRight,
+        |text| text.key_move_right,
+    ),
+    bind(
+        &[KeyCode::Up, KeyCode::Char('k')],
+        Command::MoveUp,
+        |text| text.key_move_up,
+    ),
+    bind(
+        &[KeyCode::Down, KeyCode::Char('j')],
+        Command::MoveDown,
+        |text| text.key_move_down,
+    ),
+    bind(&[KeyCode::Enter], Command::Select, |text| text.key_select),
+    bind(&[KeyCode::Char(' ')], Command::AutoPlace, |text| {
+        text.key_auto_place
+    }),
+    bind(&[KeyCode::Char('u')], Command::Undo, |text| text.key_undo),
+    bind(&[KeyCode::Char('n')], Command::NewGame, |text| {
+        text.key_new_game
+    }),
+    bind(&[KeyCode::Char('r')], Command::RestartDeal, |text| {
+        text.key_restart
+    }),
+    bind(&[KeyCode::Char('p')], Command::TogglePause, |text| {
+        text.key_pause
+    }),
+    bind(&[KeyCode::Char('t')], Command::ToggleStats, |text| {
+        text.key_stats
+    }),
+    bind(&[KeyCode::Char('c')], Command::ToggleCalendar, |text| {
+        text.key_calendar
+    }),
+    bind(&[KeyCode::Char('L')], Command::ToggleLeaderboard, |text| {
+        text.key_leaderboard
+    }),
+    bind(&[KeyCode::Char('T')], Command::CycleTheme, |text| {
+        text.key_theme
+    }),
+    bind(&[KeyCode::Char('o')], Command::ToggleSettings, |text| {
+        text.key_settings
+    }),
+    bind(&[KeyCode::Char('v')], Command::ToggleHistory, |text| {
+        text.key_history
+    }),
+    bind(&[KeyCode::Char('[')], Command::HistoryBack, |text| {
+        text.key_history_back
+    }),
+    bind(
+        &[KeyCode::Esc, KeyCode::Char('m')],
+        Command::ToggleMenu,
+        |text| text.key_menu,
+    ),
+    bind(
+        &[KeyCode::Char('?'), KeyCode::F(1)],
+        Command::ToggleHelp,
+        |text| text.key_help,
+    ),
+    bind(&[KeyCode::Char('q')], Command::Quit, |text| text.key_quit),
+];
+
+pub const PLAYBACK: &[Binding] = &[
+    bind(&[KeyCode::Char(' ')], Command::TogglePlayback, |text| {
+        text.key_play_pause
+    }),
+    bind(
+        &[KeyCode::Right, KeyCode::Char('d')],
+        Command::StepForward,
+        |text| text.key_next_move,
+    ),
+    bind(
+        &[KeyCode::Left, KeyCode::Char('a')],
+        Command::StepBack,
+        |text| text.key_previous_move,
+    ),
+    bind(
+        &[KeyCode::Char('+'), KeyCode::Char('=')],
+        Command::SpeedUp,
+        |text| text.key_faster,
+    ),
+    bind(&[KeyCode::Char('-')], Command::SpeedDown, |text| {
+        text.key_slower
+    }),
+    bind(
+        &[KeyCode::Esc, KeyCode::Char('m')],
+        Command::ToggleMenu,
+        |text| text.key_menu,
+    ),
+    bind(
+        &[KeyCode::Char('?'), KeyCode::F(1)],
+        Command::ToggleHelp,
+        |text| text.key_help,
+    ),
+    bind(&[KeyCode::Char('q')], Command::Quit, |text| text.key_quit),
+];
+
+pub const STATS: &[Binding] = &[
+    bind(
+        &[KeyCode::Char('t'), KeyCode::Esc],
+        Command::ToggleStats,
+        |text| text.key_close,
+    ),
+    bind(&[KeyCode::Char('q')], Command::Quit, |text| text.key_quit),
+];
+
+pub const LEADERBOARD: &[Binding] = &[
+    bind(
+        &[KeyCode::Char('l'), KeyCode::Esc],
+        Command::ToggleLeaderboard,
+        |text| text.key_close,
+    ),
+    bind(&[KeyCode::Char('s')], Command::CycleSort, |text| {
+        text.key_sort
+    }),
+    bind(&[KeyCode::Char('q')], Command::Quit, |text| text.key_quit),
+];
+
+pub const CALENDAR: &[Binding] = &[
+    bind(
+        &[KeyCode::Char('c'), KeyCode::Esc],
+        Command::ToggleCalendar,
+        |text| text.key_close,
+    ),
+    bind(
+        &[KeyCode::Left, KeyCode::Char('a')],
+        Command::PreviousMonth,
+        |text| text.key_previous_month,
+    ),
+    bind(
+        &[KeyCode::Right, KeyCode::Char('d')],
+        Command::NextMonth,
+        |text| text.key_next_month,
+    ),
+    bind(&[KeyCode::Char('q')], Command::Quit, |text| text.key_quit),
+];
+
+pub const MENU: &[Binding] = &[
+    bind(
+        &[KeyCode::Up, KeyCode::Char('w')],
+        Command::MenuUp,
+        |text| text.key_up,
+    ),
+    bind(
+        &[KeyCode::Down, KeyCode::Char('s')],
+        Command::MenuDown,
+        |text| text.key_down,
+    ),
+    bind(
+        &[KeyCode::Left, KeyCode::Char('a')],
+        Command::PreviousRules,
+        |text| text.key_previous_rules,
+    ),
+    bind(
+        &[KeyCode::Right, KeyCode::Char('d')],
+        Command::NextRules,
+        |text| text.key_next_rules,
+    ),
+    bind(&[KeyCode::Enter], Command::MenuSelect, |text| {
+        text.key_choose
+    }),
+    bind(
+        &[KeyCode::Esc, KeyCode::Char('m')],
+        Command::ToggleMenu,
+        |text| text.key_back,
+    ),
+    bind(&[KeyCode::Char('q')], Command::Quit, |text| text.key_quit),
+];
+
+pub const SETTINGS: &[Binding] = &[
+    bind(
+        &[KeyCode::Up, KeyCode::Char('w')],
+        Command::SettingUp,
+        |text| text.key_up,
+    ),
+    bind(
+        &[KeyCode::Down, KeyCode::Char('s')],
+        Command::SettingDown,
+        |text| text.key_down,
+    ),
+    bind(
+        &[KeyCode::Left, KeyCode::Char('a')],
+        Command::PreviousValue,
+        |text| text.key_previous_value,
+    ),
+    bind(
+        &[KeyCode::Right, KeyCode::Char('d'), KeyCode::Enter],
+        Command::NextValue,
+        |text| text.key_next_value,
+    ),
+    bind(
+        &[KeyCode::Esc, KeyCode::Char('o')],
+        Command::ToggleSettings,
+        |text| text.key_close,
+    ),
+    bind(&[KeyCode::Char('q')], Command::Quit, |text| text.key_quit),
+];
+
+pub const PAUSED: &[Binding] = &[
+    bind(
+        &[
+            KeyCode::Char('p'),
+            KeyCode::Char(' '),
+            KeyCode::Enter,
+            KeyCode::Esc,
+        ],
+        Command::TogglePause,
+        |text| text.key_resume,
+    ),
+    bind(
+        &[KeyCode::Char('?'), KeyCode::F(1)],
+        Command::ToggleHelp,
+        |text| text.key_help,
+    ),
+    bind(&[KeyCode::Char('q')], Command::Quit, |text| text.key_quit),
+];
+
+// Earlier positions are only shown, the game goes on from the latest one
+pub const HISTORY: &[Binding] = &[
+    bind(
+        &[
+            KeyCode::Up,
+            KeyCode::Char('w'),
+            KeyCode::Char('k'),
+            KeyCode::Char('['),
+        ],
+        Command::HistoryBack,
+        |text| text.key_earlier_move,
+    ),
+    bind(
+        &[
+            KeyCode::Down,
+            KeyCode::Char('s'),
+            KeyCode::Char('j'),
+            KeyCode::Char(']'),
+        ],
+        Command::HistoryForward,
+        |text| text.key_later_move,
+    ),
+    bind(
+        &[KeyCode::Esc, KeyCode::Enter],
+        Command::HistoryLive,
+        |text| text.key_back,
+    ),
+    bind(&[KeyCode::Char('v')], Command::ToggleHistory, |text| {
+        text.key_hide_history
+    }),
+    bind(
+        &[KeyCode::Char('?'), KeyCode::F(1)],
+        Command::ToggleHelp,
+        |text| text.key_help,
+    ),
+    bind(&[KeyCode::Char('q')], Command::Quit, |text| text.key_quit),
+];
+
+// Asked before leaving a game in progress
+pub const CONFIRM: &[Binding] = &[
+    bind(
+        &[KeyCode::Char('y'), KeyCode::Enter],
+        Command::Confirm,
+        |text| text.key_leave,
+    ),
+    bind(
+        &[KeyCode::Char('n'), KeyCode::Esc],
+        Command::Cancel,
+        |text| text.key_keep_playing,
+    ),
+];
+
+pub const HELP: &[Binding] = &[
+    bind(
+        &[KeyCode::Char('?'), KeyCode::F(1), KeyCode::Esc],
+        Command::ToggleHelp,
+        |text| text.key_close,
+    ),
+    bind(&[KeyCode::Char('q')], Command::Quit, |text| text.key_quit),
+];
+
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Eq, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyPreset {
+    #[default]
+    Standard,
+    Vim,
+}
+
+impl KeyPreset {
+    pub fn name(&self) -> &'static str {
+        match self {
+            KeyPreset::Standard => "standard",
+            KeyPreset::Vim => "vim",
+        }
+    }
+
+    pub fn game(&self) -> &'static [Binding] {
+        match self {
+            KeyPreset::Standard => GAME,
+            KeyPreset::Vim => GAME_VIM,
+        }
+    }
+}
+
+pub fn command_for(bindings: &[Binding], code: KeyCode) -> Option<Command> {
+    bindings
+        .iter()
+        .find(|binding| binding.keys.contains(&code))
+        .map(|binding| binding.command)
+}
+
+// Arrow keys read better as arrows, the rest use crossterm's names like "Enter" or "F1"
+pub fn key_name(code: KeyCode) -> String {
+    match code {
+        KeyCode::Left => "←".to_string(),
+        KeyCode::Right => "→".to_string(),
+        KeyCode::Up => "↑".to_string(),
+        KeyCode::Down => "↓".to_string(),
+        code => code.to_string(),
+    }
+}
+
+pub fn keys_text(binding: &Binding) -> String {
+    binding
+        .keys
+        .iter()
+        .map(|code| key_name(*code))
+        .collect::<Vec<_>>()
+        .join(" / ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i18n::{CATALOGS, ENGLISH, GERMAN};
+
+    #[test]
+    fn test_command_for() {
+        assert_eq!(
+            command_for(GAME, KeyCode::Char('a')),
+            Some(Command::MoveLeft)
+        );
+        assert_eq!(command_for(GAME, KeyCode::F(1)), Some(Command::ToggleHelp));
+        assert_eq!(command_for(GAME, KeyCode::Char('x')), None);
+        assert_eq!(
+            command_for(PLAYBACK, KeyCode::Char(' ')),
+            Some(Command::TogglePlayback)
+        );
+        assert_eq!(
+            command_for(KeyPreset::Vim.game(), KeyCode::Char('l')),
+            Some(Command::MoveRight)
+        );
+    }
+
+    #[test]
+    fn test_no_key_used_twice() {
+        for bindings in [
+            GAME,
+            GAME_VIM,
+            PLAYBACK,
+            STATS,
+            LEADERBOARD,
+            CALENDAR,
+            MENU,
+            SETTINGS,
+            PAUSED,
+            HISTORY,
+            CONFIRM,
+            HELP,
+        ] {
+            let keys: Vec<KeyCode> = bindings
+                .iter()
+                .flat_map(|binding| binding.keys.iter().copied())
+                .collect();
+            for (index, key) in keys.iter().enumerate() {
+                assert!(!keys[index + 1..].contains(key), "{} is bound twice", key);
+            }
+        }
+    }
+
+    #[test]
+    fn test_descriptions_follow_language() {
+        assert_eq!((GAME[0].description)(&ENGLISH), "Move left");
+        assert_eq!((GAME[0].description)(&GERMAN), "Nach links");
+        for catalog in CATALOGS {
+            assert!(
+                HISTORY
+                    .iter()
+                    .all(|binding| !(binding.description)(catalog).is_empty())
+            );
+        }
+    }
+
+    #[test]
+    fn test_keys_text() {
+        assert_eq!(keys_text(&GAME[0]), "← / a");
+        assert_eq!(keys_text(&GAME[5]), "Space");
+        assert_eq!(keys_text(&HELP[0]), "? / F1 / Esc");
+    }
+}
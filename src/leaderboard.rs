@@ -0,0 +1,297 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    scoring::Scoring,
+    storage::{data_path, read_json, write_json},
+    utils::format_duration,
+};
+
+const EXPORT_HEADER: &str =
+    "# tersoli leaderboard v1: rules, scoring, name, score, secs, moves, date";
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Entry {
+    pub name: String,
+    pub score: i32,
+    pub secs: u64,
+    pub moves: u32,
+    pub date: String,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum SortBy {
+    #[default]
+    Score,
+    Time,
+    Moves,
+}
+
+impl SortBy {
+    pub fn next(&self) -> Self {
+        match self {
+            SortBy::Score => SortBy::Time,
+            SortBy::Time => SortBy::Moves,
+            SortBy::Moves => SortBy::Score,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SortBy::Score => "score",
+            SortBy::Time => "time",
+            SortBy::Moves => "moves",
+        }
+    }
+}
+
+// Boards are keyed by the rules spec and then the scoring mode
+#[derive(Serialize, Deserialize, Default)]
+pub struct Leaderboard {
+    pub boards: BTreeMap<String, BTreeMap<String, Vec<Entry>>>,
+    #[serde(default)]
+    pub last_name: String,
+}
+
+impl Leaderboard {
+    pub fn path() -> PathBuf {
+        data_path("leaderboard.json")
+    }
+
+    pub fn load() -> Self {
+        read_json(&Self::path())
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        write_json(&Self::path(), self)
+    }
+
+    // Returns false when the same result is already on the board
+    pub fn add(&mut self, rules: &str, scoring: Scoring, entry: Entry) -> bool {
+        let board = self
+            .boards
+            .entry(rules.to_string())
+            .or_default()
+            .entry(scoring.to_string())
+            .or_default();
+
+        if board.contains(&entry) {
+            return false;
+        }
+        board.push(entry);
+        true
+    }
+
+    pub fn entries(&self, rules: &str, scoring: Scoring, sort_by: SortBy) -> Vec<Entry> {
+        let mut entries = self
+            .boards
+            .get(rules)
+            .and_then(|boards| boards.get(&scoring.to_string()))
+            .cloned()
+            .unwrap_or_default();
+
+        entries.sort_by(|a, b| match sort_by {
+            SortBy::Score => b.score.cmp(&a.score).then(a.secs.cmp(&b.secs)),
+            SortBy::Time => a.secs.cmp(&b.secs).then(b.score.cmp(&a.score)),
+            SortBy::Moves => a.moves.cmp(&b.moves).then(b.score.cmp(&a.score)),
+        });
+        entries
+    }
+
+    pub fn to_text(&self) -> String {
+        if self.boards.is_empty() {
+            return "No high scores yet.\n".to_string();
+        }
+
+        let mut text = String::new();
+        for (rules, boards) in self.boards.iter() {
+            for scoring in boards.keys() {
+                text.push_str(&format!("{} ({} scoring)\n", rules, scoring));
+                let scoring = scoring.parse().unwrap_or_default();
+                for (rank, entry) in self
+                    .entries(rules, scoring, SortBy::Score)
+                    .iter()
+                    .enumerate()
+                {
+                    text.push_str(&format!(
+                        "  {:>2}. {:<16} {:>6}  {:>8}  {:>4} moves  {}\n",
+                        rank + 1,
+                        entry.name,
+                        entry.score,
+                        format_duration(entry.secs),
+                        entry.moves,
+                        entry.date
+                    ));
+                }
+            }
+        }
+        text
+    }
+
+    // One tab separated entry per line, so boards can be concatenated and merged by hand
+    pub fn export(&self) -> String {
+        let mut text = format!("{}\n", EXPORT_HEADER);
+        for (rules, boards) in self.boards.iter() {
+            for (scoring, entries) in boards.iter() {
+                for entry in entries {
+                    text.push_str(&format!(
+                        "{}\t{}\t{}\t{}\t{}\t{}\t{}\n",
+                        rules,
+                        scoring,
+                        entry.name,
+                        entry.score,
+                        entry.secs,
+                        entry.moves,
+                        entry.date
+                    ));
+                }
+            }
+        }
+        text
+    }
+
+    pub fn import(&mut self, text: &str) -> Result<usize, String> {
+        let mut added = 0;
+
+        for (index, line) in text.lines().enumerate() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let invalid = |field: &str| format!("Line {}: invalid {}", index + 1, field);
+            let fields: Vec<&str> = line.split('\t').collect();
+            let [rules, scoring, name, score, secs, moves, date] = fields[..] else {
+                return Err(format!(
+                    "Line {}: expected 7 tab separated fields, got {}",
+                    index + 1,
+                    fields.len()
+                ));
+            };
+
+            let entry = Entry {
+                name: name.to_string(),
+                score: score.parse().map_err(|_| invalid("score"))?,
+                secs: secs.parse().map_err(|_| invalid("time"))?,
+                moves: moves.parse().map_err(|_| invalid("moves"))?,
+                date: date.to_string(),
+            };
+            let scoring = scoring
+                .parse()
+                .map_err(|err| format!("Line {}: {}", index + 1, err))?;
+
+            if self.add(rules, scoring, entry) {
+                added += 1;
+            }
+        }
+
+        Ok(added)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mock_entry(name: &str, score: i32, secs: u64, moves: u32) -> Entry {
+        Entry {
+            name: name.to_string(),
+            score,
+            secs,
+            moves,
+            date: "2026-10-18".to_string(),
+        }
+    }
+
+    fn mock_leaderboard() -> Leaderboard {
+        let mut leaderboard = Leaderboard::default();
+        leaderboard.add(
+            "klondike",
+            Scoring::Standard,
+            mock_entry("ada", 600, 300, 140),
+        );
+        leaderboard.add(
+            "klondike",
+            Scoring::Standard,
+            mock_entry("bob", 550, 200, 120),
+        );
+        leaderboard.add(
+            "klondike",
+            Scoring::Standard,
+            mock_entry("cy", 700, 400, 160),
+        );
+        leaderboard.add("klondike", Scoring::Vegas, mock_entry("ada", 208, 300, 140));
+        leaderboard
+    }
+
+    #[test]
+    fn test_sorting() {
+        let leaderboard = mock_leaderboard();
+        let names = |sort_by| -> Vec<String> {
+            leaderboard
+                .entries("klondike", Scoring::Standard, sort_by)
+                .into_iter()
+                .map(|entry| entry.name)
+                .collect()
+        };
+
+        assert_eq!(names(SortBy::Score), ["cy", "ada", "bob"]);
+        assert_eq!(names(SortBy::Time), ["bob", "ada", "cy"]);
+        assert_eq!(names(SortBy::Moves), ["bob", "ada", "cy"]);
+        assert_eq!(SortBy::Moves.next(), SortBy::Score);
+        assert!(
+            leaderboard
+                .entries("canfield", Scoring::Standard, SortBy::Score)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_to_text() {
+        assert_eq!(Leaderboard::default().to_text(), "No high scores yet.\n");
+
+        let text = mock_leaderboard().to_text();
+        assert!(text.starts_with("klondike (standard scoring)\n   1. cy"));
+        assert!(text.contains("klondike (vegas scoring)\n"));
+    }
+
+    #[test]
+    fn test_export_and_import() {
+        let leaderboard = mock_leaderboard();
+        let text = leaderboard.export();
+        assert!(text.starts_with(EXPORT_HEADER));
+        assert!(text.contains("klondike\tvegas\tada\t208\t300\t140\t2026-10-18\n"));
+
+        let mut merged = Leaderboard::default();
+        merged.add(
+            "klondike",
+            Scoring::Standard,
+            mock_entry("dee", 100, 900, 200),
+        );
+        assert_eq!(merged.import(&text), Ok(4));
+        assert_eq!(merged.import(&text), Ok(0));
+        assert_eq!(
+            merged
+                .entries("klondike", Scoring::Standard, SortBy::Score)
+                .len(),
+            4
+        );
+    }
+
+    #[test]
+    fn test_import_errors() {
+        let mut leaderboard = Leaderboard::default();
+
+        assert!(leaderboard.import("klondike\tstandard\tada").is_err());
+        assert!(
+            leaderboard
+                .import("klondike\tgolf\tada\t1\t2\t3\t2026-10-18")
+                .is_err()
+        );
+        assert!(
+            leaderboard
+                .import("klondike\tstandard\tada\tmany\t2\t3\t2026-10-18")
+                .is_err()
+        );
+    }
+}
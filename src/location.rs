@@ -1,7 +1,8 @@
-#[derive(Copy, Clone, PartialEq, Eq)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub enum Location {
     Stock,
     Waste,
+    Reserve,
     Tableau(usize),
     Foundation(usize),
 }
@@ -1,13 +1,41 @@
 use ratatui::{
-    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    crossterm::{
+        event::{self, DisableFocusChange, EnableFocusChange, Event, KeyCode, KeyEventKind},
+        execute,
+    },
     layout::{Constraint, Layout},
     *,
 };
-use std::time::{Duration, Instant};
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
 
+mod accessible;
+mod achievements;
+mod analyze;
+mod animation;
+mod capabilities;
+use achievements::{Achievements, Tracker};
+use animation::Animator;
+use capabilities::Capabilities;
+mod cli;
+mod config;
+use config::Config;
+mod headless;
+mod history;
+mod i18n;
+use i18n::Catalog;
+mod keymap;
+mod menu;
+mod settings;
+use keymap::Binding;
+use menu::{Leave, Menu, MenuItem};
+use settings::{Setting, Settings};
+mod theme;
+use theme::{Theme, Themes};
 mod renderer;
 mod utils;
-use utils::*;
 
 mod location;
 use location::Location;
@@ -16,27 +44,204 @@ use command::Command;
 mod suit;
 
 mod card;
-use card::Card;
+mod card_view;
 
 mod stock;
-use stock::Stock;
 
 mod tableau;
-use tableau::Tableau;
 
 mod waste;
-use waste::Waste;
 
 mod foundation;
-use foundation::Foundation;
 
-use crate::renderer::render;
+mod reserve;
+
+mod rules;
+use rules::Rules;
+
+mod events;
+use events::GameEvent;
+mod game;
+use game::Game;
+mod moves;
+use moves::Move;
+mod notation;
+mod protocol;
+use protocol::{Request, Response};
+mod server;
+use server::Server;
+mod race;
+use race::Race;
+mod daily;
+mod scoring;
+use scoring::Scoring;
+mod leaderboard;
+use daily::{DailyHistory, Date};
+use leaderboard::{Entry, Leaderboard, SortBy};
+mod replay;
+use replay::{Playback, Replay};
+
+mod solver;
+mod stats;
+use stats::{GameResult, Stats};
+mod storage;
+
+use crate::renderer::{
+    min_board_size, render, render_animation, render_calendar, render_cascade, render_confirm,
+    render_help, render_history, render_leaderboard, render_menu, render_name_entry, render_paused,
+    render_playback_status, render_race, render_server_status, render_settings, render_stats,
+    render_toasts, render_too_small,
+};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let terminal = ratatui::init();
-    let app_result = App::new().run(terminal);
-    ratatui::restore();
-    app_result
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let options = cli::parse_args(&args)?;
+    let capabilities = Capabilities::detect(options.unicode, options.color);
+    i18n::set_catalog(Catalog::detect(&Config::load().language));
+
+    match options.mode {
+        cli::Mode::Stats { json } => {
+            let stats = Stats::load();
+            if json {
+                println!("{}", stats.to_json());
+            } else {
+                print!("{}", stats.to_text());
+            }
+            Ok(())
+        }
+        cli::Mode::Replay { path } => {
+            let replay = Replay::load(&path)?;
+            let mut app = App::replay(replay).listen(options.listen.as_deref())?;
+            app.set_capabilities(capabilities);
+            let terminal = ratatui::init();
+            let app_result = app.run(terminal);
+            ratatui::restore();
+            app_result
+        }
+        cli::Mode::Headless { omniscient } => {
+            let mut replay = Replay::new(
+                options.seed.unwrap_or_else(rand::random),
+                &options.rules.unwrap_or_default(),
+            );
+            if let Some(position) = options.position {
+                Game::from_position(options.rules.unwrap_or_default(), &position)?;
+                replay.position = Some(position);
+            }
+
+            let mut session = protocol::Session::new(replay, omniscient);
+            headless::run(
+                &mut session,
+                std::io::stdin().lock(),
+                std::io::stdout().lock(),
+            )
+        }
+        cli::Mode::Accessible => {
+            let mut replay = Replay::new(
+                options.seed.unwrap_or_else(rand::random),
+                &options.rules.unwrap_or_default(),
+            );
+            if let Some(position) = options.position {
+                Game::from_position(options.rules.unwrap_or_default(), &position)?;
+                replay.position = Some(position);
+            }
+
+            let mut session = protocol::Session::new(replay, false);
+            accessible::run(
+                &mut session,
+                std::io::stdin().lock(),
+                std::io::stdout().lock(),
+            )
+        }
+        cli::Mode::Race { host, address } => {
+            let race = if host {
+                let listener = Race::listen(&address)?;
+                println!("Waiting for an opponent on {}", listener.local_addr()?);
+                Race::accept(
+                    &listener,
+                    options.seed.unwrap_or_else(rand::random),
+                    options.rules.unwrap_or_default(),
+                )?
+            } else {
+                Race::join(&address)?
+            };
+
+            let mut app = App::new(Game::new(race.rules, race.seed));
+            app.race = Some(race);
+            app.set_scoring(options.scoring);
+            app.set_capabilities(capabilities);
+            let terminal = ratatui::init();
+            let app_result = app.run(terminal);
+            ratatui::restore();
+            app_result
+        }
+        cli::Mode::Analyze {
+            seeds,
+            time_limit,
+            csv,
+        } => analyze::run(options.rules.unwrap_or_default(), seeds, time_limit, csv),
+        cli::Mode::Achievements => {
+            print!("{}", Achievements::load().to_text());
+            Ok(())
+        }
+        cli::Mode::Leaderboard(action) => {
+            let mut leaderboard = Leaderboard::load();
+            match action {
+                cli::LeaderboardAction::Show => print!("{}", leaderboard.to_text()),
+                cli::LeaderboardAction::Export => print!("{}", leaderboard.export()),
+                cli::LeaderboardAction::Import(path) => {
+                    let added = leaderboard.import(&std::fs::read_to_string(path)?)?;
+                    leaderboard.save()?;
+                    println!("Imported {} new entries", added);
+                }
+            }
+            Ok(())
+        }
+        cli::Mode::Daily => {
+            let today = Date::today();
+            let mut app = App::new(Game::new(Date::rules(), today.seed()))
+                .listen(options.listen.as_deref())?;
+            app.daily = Some(today);
+            app.set_scoring(options.scoring);
+            app.set_capabilities(capabilities);
+            let terminal = ratatui::init();
+            let app_result = app.run(terminal);
+            ratatui::restore();
+            app_result
+        }
+        cli::Mode::Play => {
+            let seed = options.seed.unwrap_or_else(rand::random);
+            let rules = options
+                .rules
+                .unwrap_or_else(|| Config::load().apply_rules(Rules::default()));
+            let mut app = match options.position {
+                Some(position) => App::from_position(Game::from_position(rules, &position)?),
+                None => App::new(Game::new(rules, seed)),
+            }
+            .listen(options.listen.as_deref())?;
+            app.set_scoring(options.scoring);
+            app.set_capabilities(capabilities);
+            let terminal = ratatui::init();
+            let app_result = app.run(terminal);
+            ratatui::restore();
+            app_result
+        }
+    }
+}
+
+// Focus changes are reported while this lives, an error or a panic turns them off again too
+struct FocusReporting;
+
+impl FocusReporting {
+    fn enable() -> std::io::Result<Self> {
+        execute!(std::io::stdout(), EnableFocusChange)?;
+        Ok(Self)
+    }
+}
+
+impl Drop for FocusReporting {
+    fn drop(&mut self) {
+        let _ = execute!(std::io::stdout(), DisableFocusChange);
+    }
 }
 
 struct App {
@@ -44,260 +249,1013 @@ struct App {
     tick_count: u64,
     selected: Location,
     active: Option<Location>,
-    stock: Stock,
-    waste: Waste,
-    tableau: Tableau,
-    foundation: Foundation,
+    game: Game,
     won: bool,
+    started: Instant,
+    // When the game was paused, the timer skips the time until it is resumed
+    paused: Option<Instant>,
+    last_input: Instant,
+    idle_pause: Option<Duration>,
+    recorded: bool,
+    replay: Replay,
+    playback: Option<Playback>,
+    stats: Stats,
+    show_stats: bool,
+    show_help: bool,
+    show_history: bool,
+    // A position from the history being looked at, the game itself goes on from the latest one
+    history_preview: Option<usize>,
+    history_cache: history::Cache,
+    menu: Option<Menu>,
+    settings: Option<Settings>,
+    config: Config,
+    config_changed: bool,
+    // Settings that would change the deal in progress wait for the next one
+    next_rules: Rules,
+    next_scoring: Scoring,
+    // After an undo, auto-move waits for the player's next move or it would redo it right away
+    auto_move_held: Option<usize>,
+    // Leaving a game in progress waits for the player to confirm it
+    confirm: Option<Leave>,
+    // Started from the run loop, where recording the abandoned game can fail
+    next_game: Option<Replay>,
+    server: Option<Server>,
+    last_change: String,
+    race: Option<Race>,
+    daily: Option<Date>,
+    daily_history: DailyHistory,
+    calendar: Option<Date>,
+    scoring: Scoring,
+    leaderboard: Leaderboard,
+    leaderboard_changed: bool,
+    show_leaderboard: bool,
+    sort_by: SortBy,
+    // A won game's high score, while the player types their name
+    name_entry: Option<Entry>,
+    achievements: Achievements,
+    tracker: Tracker,
+    // Heading and text of each notification in the corner
+    toasts: Vec<(&'static str, String, Instant)>,
+    animator: Animator,
+    themes: Themes,
+    capabilities: Capabilities,
+    // Keys pressed while cards are moving, handled one per tick once they stop
+    pending_keys: VecDeque<event::KeyEvent>,
 }
 
 impl App {
-    fn new() -> Self {
+    fn new(game: Game) -> Self {
+        let config = Config::load();
+        let themes = Themes::load(&config.theme);
+        let toasts = themes
+            .errors
+            .iter()
+            .map(|error| (i18n::text().theme_error, error.clone(), Instant::now()))
+            .collect();
         Self {
             exit: false,
             tick_count: 0,
             selected: Location::Stock,
             active: None,
-            stock: Stock::new(),
-            waste: Waste::new(),
-            tableau: Tableau::new(),
-            foundation: Foundation::new(),
+            replay: Replay::new(game.seed, &game.rules),
+            next_rules: game.rules,
+            game,
             won: false,
+            started: Instant::now(),
+            paused: None,
+            last_input: Instant::now(),
+            idle_pause: (config.idle_pause_secs > 0)
+                .then(|| Duration::from_secs(config.idle_pause_secs)),
+            recorded: false,
+            playback: None,
+            stats: Stats::load(),
+            show_stats: false,
+            show_help: false,
+            show_history: false,
+            history_preview: None,
+            history_cache: history::Cache::default(),
+            menu: None,
+            settings: None,
+            next_scoring: config.scoring,
+            auto_move_held: None,
+            confirm: None,
+            next_game: None,
+            server: None,
+            last_change: String::new(),
+            race: None,
+            daily: None,
+            daily_history: DailyHistory::load(),
+            calendar: None,
+            scoring: config.scoring,
+            leaderboard: Leaderboard::load(),
+            leaderboard_changed: false,
+            show_leaderboard: false,
+            sort_by: SortBy::default(),
+            name_entry: None,
+            achievements: Achievements::load(),
+            tracker: Tracker::default(),
+            toasts,
+            animator: Animator::new(&config),
+            themes,
+            capabilities: Capabilities::default(),
+            pending_keys: VecDeque::new(),
+            config,
+            config_changed: false,
         }
+        .with_card_style()
+    }
+
+    fn with_card_style(mut self) -> Self {
+        self.themes.set_card_style(self.config.card_style);
+        self
+    }
+
+    // A scoring picked on the command line is kept for every deal of the session
+    fn set_scoring(&mut self, scoring: Option<Scoring>) {
+        if let Some(scoring) = scoring {
+            self.scoring = scoring;
+            self.next_scoring = scoring;
+        }
+    }
+
+    fn listen(mut self, address: Option<&str>) -> std::io::Result<Self> {
+        if let Some(address) = address {
+            self.server = Some(Server::bind(address)?);
+        }
+        Ok(self)
+    }
+
+    // Colors are stripped from every frame anyway, the board keeps its meaning with a colorless theme
+    fn set_capabilities(&mut self, capabilities: Capabilities) {
+        self.capabilities = capabilities;
+        if !capabilities.color {
+            self.themes = Themes::from_themes(vec![Theme::no_color()], "no-color");
+        }
+    }
+
+    fn from_position(game: Game) -> Self {
+        let mut app = Self::new(game);
+        app.replay.position = Some(app.game.position());
+        app
+    }
+
+    fn replay(replay: Replay) -> Self {
+        let mut app = Self::new(replay.game_at(0));
+        app.recorded = true;
+        app.playback = Some(Playback::new(replay));
+        app
     }
 
     pub fn run(mut self, mut terminal: DefaultTerminal) -> Result<(), Box<dyn std::error::Error>> {
         let tick_rate = Duration::from_millis(16);
         let mut last_tick = Instant::now();
-
-        self.tableau.initialize(&mut self.stock);
+        let _focus_reporting = FocusReporting::enable()?;
 
         while !self.exit {
-            terminal.draw(|frame| self.draw(frame))?;
+            self.history_cache.update(
+                &self.replay,
+                self.show_history && self.playback.is_none(),
+                self.history_preview,
+            );
+            terminal.draw(|frame| {
+                self.draw(frame);
+                self.capabilities.apply(frame.buffer_mut());
+            })?;
             let timeout = tick_rate.saturating_sub(last_tick.elapsed());
 
-            if event::poll(timeout)?
-                && let Event::Key(key) = event::read()?
-            {
-                self.handle_key_press(key)
+            if event::poll(timeout)? {
+                match event::read()? {
+                    Event::Key(key) => {
+                        self.last_input = Instant::now();
+                        if self.animator.is_busy() {
+                            self.pending_keys.push_back(key);
+                        } else {
+                            self.handle_key_press(key);
+                        }
+                    }
+                    Event::FocusLost => self.pause(),
+                    _ => {}
+                }
             }
 
+            self.serve();
+
             if last_tick.elapsed() >= tick_rate {
                 self.on_tick();
-                self.won = check_win(&self.foundation);
+                if self
+                    .idle_pause
+                    .is_some_and(|idle| self.last_input.elapsed() >= idle)
+                {
+                    self.pause();
+                }
+                self.won = self.game.is_won();
+                if self.won
+                    && !self.recorded
+                    && let Err(err) = self.record_game()
+                {
+                    self.show_error(err);
+                }
+                let events = self.game.events.drain();
+                self.animate(&events);
+                if let Err(err) = self.check_achievements(&events) {
+                    self.show_error(err);
+                }
+                if !self.animator.is_busy()
+                    && let Some(key) = self.pending_keys.pop_front()
+                {
+                    self.handle_key_press(key);
+                }
+                if let Some(replay) = self.next_game.take() {
+                    self.start(replay);
+                }
+                if self.config_changed {
+                    self.config_changed = false;
+                    if let Err(err) = self.config.save() {
+                        self.show_error(err);
+                    }
+                }
+                if self.leaderboard_changed {
+                    self.leaderboard_changed = false;
+                    if let Err(err) = self.leaderboard.save() {
+                        self.show_error(err);
+                    }
+                }
                 last_tick = Instant::now();
             }
         }
+
+        if !self.recorded && self.game.moves > 0 {
+            self.record_game()?;
+        }
         Ok(())
     }
 
-    fn reset_selection(&mut self) {
-        self.active = None
+    // Only a game that is still being played has a timer to stop
+    fn pause(&mut self) {
+        if self.paused.is_none() && self.playback.is_none() && !self.recorded {
+            self.paused = Some(Instant::now());
+            self.menu = None;
+        }
     }
 
-    fn deal_from_stock(&mut self) {
-        if self.stock.cards.is_empty() && self.waste.cards.is_empty() {
-            return;
+    fn resume(&mut self) {
+        if let Some(paused) = self.paused.take() {
+            self.started += paused.elapsed();
         }
+        self.last_input = Instant::now();
+    }
+
+    fn elapsed(&self) -> Duration {
+        let paused = self
+            .paused
+            .map_or(Duration::ZERO, |paused| paused.elapsed());
+        self.started.elapsed().saturating_sub(paused)
+    }
+
+    fn in_progress(&self) -> bool {
+        self.playback.is_none() && !self.recorded && self.game.moves > 0
+    }
 
-        if self.stock.cards.is_empty() {
-            self.stock.reset(&self.waste);
-            self.waste.reset();
+    fn request_leave(&mut self, leave: Leave) {
+        // A race is played on one deal until it is over
+        if self.race.is_some() && leave != Leave::Quit {
+            return;
         }
 
-        self.waste.add(self.stock.deal());
+        if self.in_progress() {
+            self.confirm = Some(leave);
+        } else {
+            self.leave(leave);
+        }
     }
 
-    fn take_from_waste(&mut self) {
-        let card_to_place = match self.waste.get_top_card() {
-            Some(card) => card,
-            _ => return,
-        };
+    fn leave(&mut self, leave: Leave) {
+        self.confirm = None;
+        self.menu = None;
+        match leave {
+            Leave::NewGame(rules) => {
+                self.daily = None;
+                self.next_game = Some(Replay::new(rand::random(), &rules));
+            }
+            Leave::Restart => {
+                let mut replay = Replay::new(self.replay.seed, &self.game.rules);
+                replay.position = self.replay.position.clone();
+                self.next_game = Some(replay);
+            }
+            Leave::Quit => self.exit = true,
+        }
+    }
 
-        if self.tableau.add_card(self.selected, card_to_place) {
-            self.waste.cards.pop();
+    // An abandoned game counts as a loss, just like quitting in the middle of one
+    fn start(&mut self, replay: Replay) {
+        if self.in_progress()
+            && let Err(err) = self.record_game()
+        {
+            self.show_error(err);
         }
 
+        self.game = replay.game_at(0);
+        self.replay = replay;
+        self.history_cache.invalidate();
+        self.won = false;
+        self.recorded = false;
+        self.started = Instant::now();
+        self.paused = None;
+        self.scoring = self.next_scoring;
+        self.auto_move_held = None;
+        self.playback = None;
+        self.history_preview = None;
+        self.name_entry = None;
+        self.tracker = Tracker::default();
+        self.animator.queue.clear();
+        self.animator.cascade = None;
+        self.pending_keys.clear();
+        self.selected = Location::Stock;
         self.reset_selection();
     }
 
-    fn place_in_foundation(&mut self) {
-        let card: Card = match self.active {
-            Some(active) => match active {
-                Location::Tableau(_) => match self.tableau.get_top_card(active) {
-                    Some(tableau_card) => tableau_card,
-                    _ => return,
-                },
-                Location::Waste => match self.waste.get_top_card() {
-                    Some(waste_card) => waste_card,
-                    _ => return,
-                },
-                _ => return,
-            },
-            _ => return,
+    // A file that can't be written shouldn't end the game being played
+    fn show_error(&mut self, err: Box<dyn std::error::Error>) {
+        self.toasts
+            .push((i18n::text().save_failed, err.to_string(), Instant::now()));
+    }
+
+    fn record_game(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        self.recorded = true;
+        // Games from a set up position aren't comparable deals, so only keep their replay
+        if self.replay.position.is_some() {
+            self.replay.save()?;
+            return Ok(());
+        }
+
+        let result = GameResult {
+            won: self.won,
+            secs: self.elapsed().as_secs(),
+            moves: self.game.moves,
         };
+        if let Some(date) = self.daily {
+            self.daily_history.record(date, result);
+            self.daily_history.save()?;
+        }
 
-        if let Location::Foundation(index) = self.selected
-            && self
-                .foundation
-                .add_card(card, get_suit_by_card_suit_index(index))
-        {
-            match self.active {
-                Some(Location::Waste) => self.waste.remove(),
-                Some(Location::Tableau(index)) => {
-                    self.tableau.update_cutoffs(index);
-                    self.tableau.cards[index].pop();
-                }
-                _ => return,
-            }
+        self.stats.record(&self.game.rules, result);
+        self.stats.save()?;
+
+        if self.won {
+            let name = match self.leaderboard.last_name.as_str() {
+                "" => std::env::var("USER").unwrap_or_default(),
+                name => name.to_string(),
+            };
+            self.name_entry = Some(Entry {
+                name,
+                score: scoring::score(self.scoring, &self.replay),
+                secs: result.secs,
+                moves: result.moves,
+                date: Date::today().to_string(),
+            });
         }
+        self.replay.save()?;
+        Ok(())
+    }
 
-        self.reset_selection();
+    fn animate(&mut self, events: &[GameEvent]) {
+        // Playback moves at its own pace
+        if self.playback.is_some() {
+            return;
+        }
+
+        for event in events {
+            self.animator.on_event(*event, &self.game);
+        }
     }
 
-    fn take_from_foundation(&mut self) {
-        let active_location: Location;
-        let foundation_card = match self.active {
-            Some(position) => {
-                active_location = position;
-                match self.foundation.get_top_card(position) {
-                    Some(card) => card,
-                    _ => return,
+    // Runs after the win is recorded, so the streak already counts this game
+    fn check_achievements(
+        &mut self,
+        events: &[GameEvent],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.toasts
+            .retain(|(_, _, shown)| shown.elapsed() < Duration::from_secs(4));
+
+        // Replays and set up positions don't earn anything
+        if self.playback.is_some() || self.replay.position.is_some() {
+            return Ok(());
+        }
+
+        let context = achievements::Context {
+            variant: self.game.rules.variant,
+            secs: self.elapsed().as_secs(),
+            moves: self.game.moves,
+            streak: self
+                .stats
+                .records
+                .get(&self.game.rules.spec())
+                .map_or(0, |record| record.current_streak),
+        };
+        let today = Date::today().to_string();
+        let mut unlocked = false;
+        for event in events {
+            for id in self.tracker.observe(*event, &context) {
+                if self.achievements.unlock(id, &today)
+                    && let Some(achievement) = achievements::find(id)
+                {
+                    self.toasts.push((
+                        i18n::text().achievement_unlocked,
+                        achievement.title().to_string(),
+                        Instant::now(),
+                    ));
+                    unlocked = true;
                 }
             }
-            _ => return,
-        };
+        }
 
-        if self.tableau.add_card(self.selected, foundation_card) {
-            self.foundation.remove_card(active_location);
+        if unlocked {
+            self.achievements.save()?;
+        }
+        Ok(())
+    }
+
+    fn undo(&mut self) {
+        if self.playback.is_some() || self.won || self.replay.moves.pop().is_none() {
+            return;
         }
 
+        // Replaying the moves emits their events again, which were already counted
+        self.game = self.replay.game_at(self.replay.moves.len());
+        self.game.events.drain();
+        self.game.events.emit(GameEvent::Undone);
+        self.history_cache.invalidate();
         self.reset_selection();
+        self.auto_move_held = Some(self.replay.moves.len());
     }
 
-    fn move_between_tableau(&mut self) {
-        let active_location = match self.active {
-            Some(active) => active,
-            _ => return,
+    fn play(&mut self, mv: Move) -> bool {
+        if !self.game.apply(mv) {
+            return false;
+        }
+
+        let millis = self.elapsed().as_millis() as u64;
+        self.replay.push(millis, mv);
+        self.history_cache.invalidate();
+        true
+    }
+
+    // Answers remote clients and tells all of them when anything on screen changed
+    fn serve(&mut self) {
+        let Some(server) = self.server.take() else {
+            return;
         };
 
-        self.tableau
-            .try_to_move_between_tableau(active_location, self.selected);
+        for (id, line) in server.poll() {
+            let response = protocol::respond(&line, |request| self.handle_request(request));
+            server.send(id, &response);
+        }
 
-        self.reset_selection();
+        // The pause screen hides the board, so clients don't get to see it either
+        if server.client_count() == 0 {
+            self.last_change.clear();
+        } else if self.paused.is_none() {
+            let change =
+                protocol::changed(&self.game, self.replay.seed, self.selected, self.active);
+            let change = serde_json::to_string(&change).unwrap_or_default();
+            if change != self.last_change {
+                server.broadcast(&change);
+                self.last_change = change;
+            }
+        }
+
+        self.server = Some(server);
     }
 
-    fn try_to_place_in_foundation(&mut self) {
-        let card_to_place = match self.selected {
-            Location::Tableau(_) => match self.tableau.get_top_card(self.selected) {
-                Some(tableau_card) => tableau_card,
-                _ => return,
-            },
-            Location::Waste => match self.waste.get_top_card() {
-                Some(card) => card,
-                _ => return,
-            },
-            _ => return,
+    // While paused, clients can only resume the game, they don't get to see or change the board
+    fn handle_request(&mut self, request: Request) -> Response {
+        let resume = Request::Command {
+            command: Command::TogglePause,
         };
+        if self.paused.is_some() && request != resume {
+            return protocol::error("The game is paused".to_string());
+        }
 
-        if self.foundation.add_card(card_to_place, card_to_place.suit) {
-            match self.selected {
-                Location::Tableau(index) => {
-                    self.tableau.update_cutoffs(index);
-                    self.tableau.cards[index].pop();
+        match request {
+            Request::State => {
+                Response::State(protocol::state_view(&self.game, self.replay.seed, false))
+            }
+            Request::Moves => Response::Moves {
+                moves: self.game.legal_moves(),
+            },
+            Request::Move { mv } => {
+                if self.playback.is_some() || !self.play(mv) {
+                    return protocol::error(format!("Illegal move '{}'", mv));
                 }
-                Location::Waste => {
-                    self.waste.cards.pop();
+                self.reset_selection();
+                Response::Moved {
+                    mv,
+                    won: self.game.is_won(),
                 }
-                _ => unreachable!("Can't add other than waste or tableau card"),
+            }
+            Request::Seed => protocol::error(protocol::SEED_HIDDEN.to_string()),
+            Request::Command { command } => {
+                self.apply_command(command);
+                protocol::changed(&self.game, self.replay.seed, self.selected, self.active)
+            }
+            Request::Undo | Request::New { .. } => {
+                protocol::error("Only available in headless mode".to_string())
             }
         }
+    }
 
-        self.reset_selection();
+    fn reset_selection(&mut self) {
+        self.active = None
+    }
+
+    fn try_to_place_in_foundation(&mut self) {
+        if let Some(mv) = self.game.auto_place_move(self.selected) {
+            self.play(mv);
+            self.reset_selection();
+        }
     }
 
     fn on_tick(&mut self) {
         self.tick_count += 1;
+        self.animator.on_tick();
+
+        if let Some(playback) = &mut self.playback {
+            playback.on_tick(&mut self.game);
+        }
+
+        if let Some(race) = &mut self.race {
+            race.update(&self.game);
+        }
+
+        if self.config.auto_move
+            && self.auto_move_held != Some(self.replay.moves.len())
+            && self.playback.is_none()
+            && self.history_preview.is_none()
+            && self.paused.is_none()
+            && self.active.is_none()
+            && !self.animator.is_busy()
+        {
+            self.auto_move();
+        }
+    }
+
+    // One card per tick, so each one can be seen going up
+    fn auto_move(&mut self) {
+        let mut piles = vec![Location::Waste, Location::Reserve];
+        piles.extend((0..self.game.rules.tableau_columns).map(Location::Tableau));
+        if let Some(mv) = piles
+            .into_iter()
+            .find_map(|pile| self.game.auto_place_move(pile))
+        {
+            self.play(mv);
+            self.reset_selection();
+        }
     }
 
     fn draw(&self, frame: &mut Frame) {
-        let horizontal_constraints: [Constraint; 7] = [Constraint::Percentage(14); 7];
-        let horizontal = Layout::horizontal(horizontal_constraints);
-
-        let vertical_constraints: [Constraint; 2] = [Constraint::Percentage(50); 2];
-        let vertical = Layout::vertical(vertical_constraints);
-
-        render(
-            frame,
-            horizontal,
-            vertical,
-            &self.tableau,
-            &self.stock,
-            &self.waste,
-            &self.foundation,
-            self.selected,
-            self.active,
-            self.won,
+        self.draw_screen(frame);
+
+        // Overlays go on top of whichever screen is showing
+        if let Some(menu) = &self.menu {
+            render_menu(frame, menu);
+        }
+        if let Some(settings) = &self.settings {
+            render_settings(frame, settings);
+        }
+        if self.show_help {
+            render_help(frame, self.bindings(), &self.game.rules);
+        }
+        if let Some(leave) = self.confirm {
+            render_confirm(frame, leave);
+        }
+        if !self.toasts.is_empty() {
+            let toasts: Vec<(&str, &str)> = self
+                .toasts
+                .iter()
+                .map(|(heading, text, _)| (*heading, text.as_str()))
+                .collect();
+            render_toasts(frame, &toasts);
+        }
+    }
+
+    fn draw_screen(&self, frame: &mut Frame) {
+        // The board stays hidden so it can't be studied while the timer is stopped
+        if self.paused.is_some() {
+            render_paused(frame, self.elapsed());
+            return;
+        }
+
+        if self.show_stats {
+            render_stats(frame, &self.stats);
+            return;
+        }
+
+        if self.show_leaderboard {
+            let rules = self.game.rules.spec();
+            let entries = self.leaderboard.entries(&rules, self.scoring, self.sort_by);
+            render_leaderboard(frame, &entries, &rules, self.scoring, self.sort_by);
+            return;
+        }
+
+        if let Some(month) = self.calendar {
+            let today = self.daily.unwrap_or_else(Date::today);
+            render_calendar(frame, &self.daily_history, month, today);
+            return;
+        }
+
+        let [board, playback_status, server_status] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(self.playback.is_some() as u16),
+            Constraint::Length(self.server.is_some() as u16),
+        ])
+        .areas(frame.area());
+
+        let board = match &self.race {
+            Some(race) => {
+                let [board, opponent] =
+                    Layout::horizontal([Constraint::Fill(1), Constraint::Length(24)]).areas(board);
+                render_race(frame, opponent, race);
+                board
+            }
+            None => board,
+        };
+
+        let current = self.history_preview.unwrap_or(self.replay.moves.len());
+        let board = if self.show_history && self.playback.is_none() {
+            let [board, panel] =
+                Layout::horizontal([Constraint::Fill(1), Constraint::Length(28)]).areas(board);
+            let entries = self.history_cache.entries.as_deref().unwrap_or_default();
+            let preview = self.history_preview.is_some();
+            render_history(
+                frame,
+                panel,
+                entries,
+                current,
+                preview,
+                self.themes.current(),
+            );
+            board
+        } else {
+            board
+        };
+
+        let theme = self.themes.current();
+        let needed = min_board_size(&self.game);
+        if board.width < needed.0 || board.height < needed.1 {
+            render_too_small(frame, board, needed);
+        } else if let Some((_, shown)) = &self.history_cache.preview {
+            render(frame, board, shown, self.selected, None, theme);
+        } else {
+            if self.animator.is_busy() {
+                let mut shown = self.game.clone();
+                self.animator.hide(&mut shown);
+                render(frame, board, &shown, self.selected, self.active, theme);
+            } else {
+                render(frame, board, &self.game, self.selected, self.active, theme);
+            }
+            if let Some(animation) = self.animator.current() {
+                render_animation(frame, board, &self.game, animation, theme);
+            }
+            if let Some(cascade) = &self.animator.cascade {
+                render_cascade(frame, board, &self.game, cascade, theme);
+            }
+        }
+        if let Some(playback) = &self.playback {
+            render_playback_status(frame, playback_status, playback);
+        }
+        if let Some(server) = &self.server {
+            render_server_status(frame, server_status, &server.address, server.client_count());
+        }
+        if let Some(entry) = &self.name_entry {
+            render_name_entry(frame, entry, self.scoring);
+        }
+    }
+
+    fn top_row(&self) -> Vec<Option<Location>> {
+        let mut row = vec![Some(Location::Stock), Some(Location::Waste)];
+        row.push((self.game.rules.reserve_size > 0).then_some(Location::Reserve));
+        row.extend(
+            (0..self.game.foundation.cards.len()).map(|index| Some(Location::Foundation(index))),
         );
+        row
+    }
+
+    fn top_row_slot(&self, location: Location) -> usize {
+        self.top_row()
+            .iter()
+            .position(|slot| *slot == Some(location))
+            .unwrap_or_default()
+    }
+
+    fn move_in_top_row(&mut self, step: isize) {
+        let row = self.top_row();
+        let mut slot = self.top_row_slot(self.selected) as isize + step;
+
+        while slot >= 0 && (slot as usize) < row.len() {
+            if let Some(location) = row[slot as usize] {
+                self.selected = location;
+                return;
+            }
+            slot += step;
+        }
+    }
+
+    fn column_below(&self, slot: usize) -> usize {
+        let slots = self.top_row().len();
+        let columns = self.game.rules.tableau_columns;
+        ((2 * slot + 1) * columns) / (2 * slots)
+    }
+
+    fn slot_above(&self, column: usize) -> Location {
+        let row = self.top_row();
+        let columns = self.game.rules.tableau_columns;
+        let slot = ((2 * column + 1) * row.len()) / (2 * columns);
+
+        row[..=slot]
+            .iter()
+            .rev()
+            .find_map(|location| *location)
+            .unwrap_or(Location::Stock)
     }
 
     fn handle_key_press(&mut self, key: event::KeyEvent) {
-        if key.kind == KeyEventKind::Press {
+        if key.kind != KeyEventKind::Press {
+            return;
+        }
+
+        if self.name_entry.is_some() {
             match key.code {
-                KeyCode::Char('q') => self.apply_command(Command::Quit),
-                KeyCode::Left | KeyCode::Char('a') => {
-                    self.apply_command(Command::MoveLeft);
-                }
-                KeyCode::Right | KeyCode::Char('d') => {
-                    self.apply_command(Command::MoveRight);
-                }
-                KeyCode::Up | KeyCode::Char('w') => {
-                    self.apply_command(Command::MoveUp);
-                }
-                KeyCode::Down | KeyCode::Char('s') => {
-                    self.apply_command(Command::MoveDown);
-                }
-                KeyCode::Enter => self.apply_command(Command::Select),
-                KeyCode::Char(' ') => self.apply_command(Command::AutoPlace),
+                KeyCode::Enter => self.apply_command(Command::SubmitName),
+                KeyCode::Esc => self.apply_command(Command::CancelName),
+                KeyCode::Backspace => self.apply_command(Command::DeleteChar),
+                KeyCode::Char(c) => self.apply_command(Command::TypeChar(c)),
                 _ => {}
             }
+            return;
+        }
+
+        let bindings = if self.confirm.is_some() {
+            keymap::CONFIRM
+        } else if self.show_help {
+            keymap::HELP
+        } else {
+            self.bindings()
+        };
+        if let Some(command) = keymap::command_for(bindings, key.code) {
+            self.apply_command(command);
+        }
+    }
+
+    // Keys of the screen being shown, under the help overlay if it is open
+    fn bindings(&self) -> &'static [Binding] {
+        if self.paused.is_some() {
+            keymap::PAUSED
+        } else if self.settings.is_some() {
+            keymap::SETTINGS
+        } else if self.menu.is_some() {
+            keymap::MENU
+        } else if self.show_leaderboard {
+            keymap::LEADERBOARD
+        } else if self.show_stats {
+            keymap::STATS
+        } else if self.calendar.is_some() {
+            keymap::CALENDAR
+        } else if self.history_preview.is_some() {
+            keymap::HISTORY
+        } else if self.playback.is_some() {
+            keymap::PLAYBACK
+        } else {
+            self.config.key_preset.game()
+        }
+    }
+
+    fn apply_setting(&mut self, setting: Setting) {
+        match setting {
+            Setting::DrawCount | Setting::PassLimit => {
+                self.next_rules = self.config.apply_rules(self.next_rules)
+            }
+            Setting::Scoring => self.next_scoring = self.config.scoring,
+            Setting::Animations => self.animator.enabled = self.config.animations,
+            Setting::Theme => self.themes.select(&self.config.theme),
+            Setting::CardStyle => self.themes.set_card_style(self.config.card_style),
+            // Read straight from the config whenever they are needed
+            Setting::AutoMove | Setting::KeyPreset => {}
         }
+        self.config_changed = true;
     }
 
     fn apply_command(&mut self, cmd: Command) {
         match cmd {
             Command::AutoPlace => self.try_to_place_in_foundation(),
-            Command::Quit => self.exit = true,
+            Command::Undo => self.undo(),
+            Command::Quit => self.request_leave(Leave::Quit),
+            Command::NewGame => self.request_leave(Leave::NewGame(self.next_rules)),
+            Command::RestartDeal => self.request_leave(Leave::Restart),
+            Command::Confirm => {
+                if let Some(leave) = self.confirm.take() {
+                    self.leave(leave);
+                }
+            }
+            Command::Cancel => self.confirm = None,
+            Command::TogglePause => {
+                if self.paused.is_some() {
+                    self.resume();
+                } else {
+                    self.pause();
+                }
+            }
+            Command::ToggleMenu => {
+                self.menu = match self.menu {
+                    Some(_) => None,
+                    None => Some(Menu::new(self.next_rules)),
+                }
+            }
+            Command::MenuUp => {
+                if let Some(menu) = &mut self.menu {
+                    menu.up();
+                }
+            }
+            Command::MenuDown => {
+                if let Some(menu) = &mut self.menu {
+                    menu.down();
+                }
+            }
+            Command::PreviousRules | Command::NextRules => {
+                let step = if cmd == Command::NextRules { 1 } else { -1 };
+                if let Some(menu) = &mut self.menu
+                    && menu.item() == MenuItem::Rules
+                {
+                    menu.cycle_rules(step);
+                    self.next_rules = menu.rules;
+                }
+            }
+            Command::MenuSelect => {
+                let Some(menu) = &mut self.menu else {
+                    return;
+                };
+                match menu.item() {
+                    MenuItem::Continue => self.menu = None,
+                    MenuItem::NewGame => {
+                        let rules = menu.rules;
+                        self.request_leave(Leave::NewGame(rules));
+                    }
+                    MenuItem::Restart => self.request_leave(Leave::Restart),
+                    MenuItem::Rules => {
+                        menu.cycle_rules(1);
+                        self.next_rules = menu.rules;
+                    }
+                    MenuItem::Stats => {
+                        self.menu = None;
+                        self.show_stats = true;
+                    }
+                    MenuItem::Settings => {
+                        self.menu = None;
+                        self.apply_command(Command::ToggleSettings);
+                    }
+                    MenuItem::Quit => self.request_leave(Leave::Quit),
+                }
+            }
+            Command::ToggleSettings => {
+                self.settings = match self.settings {
+                    Some(_) => None,
+                    None => {
+                        // Every theme, even when colors are off and only the colorless one is used
+                        let themes = Themes::load(&self.config.theme).themes;
+                        let names = themes.into_iter().map(|theme| theme.name).collect();
+                        Some(Settings::new(self.config.clone(), names))
+                    }
+                }
+            }
+            Command::SettingUp => {
+                if let Some(settings) = &mut self.settings {
+                    settings.up();
+                }
+            }
+            Command::SettingDown => {
+                if let Some(settings) = &mut self.settings {
+                    settings.down();
+                }
+            }
+            Command::PreviousValue | Command::NextValue => {
+                let step = if cmd == Command::NextValue { 1 } else { -1 };
+                if let Some(settings) = &mut self.settings {
+                    settings.change(step);
+                    self.config = settings.config.clone();
+                    let setting = settings.setting();
+                    self.apply_setting(setting);
+                }
+            }
+            Command::ToggleHistory => {
+                self.show_history = !self.show_history;
+                self.history_preview = None;
+            }
+            Command::HistoryBack => {
+                self.show_history = true;
+                let position = self.history_preview.unwrap_or(self.replay.moves.len());
+                self.history_preview = Some(position.saturating_sub(1));
+            }
+            Command::HistoryForward => {
+                self.history_preview = self
+                    .history_preview
+                    .map(|position| position + 1)
+                    .filter(|position| *position < self.replay.moves.len());
+            }
+            Command::HistoryLive => self.history_preview = None,
+            Command::ToggleStats => self.show_stats = !self.show_stats,
+            Command::ToggleHelp => self.show_help = !self.show_help,
+            Command::ToggleCalendar => {
+                self.calendar = match self.calendar {
+                    Some(_) => None,
+                    None => Some(self.daily.unwrap_or_else(Date::today)),
+                }
+            }
+            Command::ToggleLeaderboard => self.show_leaderboard = !self.show_leaderboard,
+            Command::CycleSort => self.sort_by = self.sort_by.next(),
+            Command::CycleTheme => {
+                let name = self.themes.next().name.clone();
+                self.toasts.push((i18n::text().theme, name, Instant::now()));
+            }
+            Command::TypeChar(c) => {
+                // Tabs and newlines would break the export format
+                if let Some(entry) = &mut self.name_entry
+                    && !c.is_control()
+                    && entry.name.chars().count() < 16
+                {
+                    entry.name.push(c);
+                }
+            }
+            Command::DeleteChar => {
+                if let Some(entry) = &mut self.name_entry {
+                    entry.name.pop();
+                }
+            }
+            Command::SubmitName => {
+                if let Some(entry) = self
+                    .name_entry
+                    .take_if(|entry| !entry.name.trim().is_empty())
+                {
+                    self.leaderboard.last_name = entry.name.clone();
+                    self.leaderboard
+                        .add(&self.game.rules.spec(), self.scoring, entry);
+                    self.leaderboard_changed = true;
+                    self.show_leaderboard = true;
+                }
+            }
+            Command::CancelName => self.name_entry = None,
+            Command::PreviousMonth => {
+                self.calendar = self.calendar.map(|month| month.add_months(-1));
+            }
+            Command::NextMonth => {
+                self.calendar = self.calendar.map(|month| month.add_months(1));
+            }
+            Command::TogglePlayback => {
+                if let Some(playback) = &mut self.playback {
+                    playback.toggle();
+                }
+            }
+            Command::StepForward => {
+                if let Some(playback) = &mut self.playback {
+                    playback.step_forward(&mut self.game);
+                }
+            }
+            Command::StepBack => {
+                if let Some(playback) = &mut self.playback {
+                    playback.step_back(&mut self.game);
+                }
+            }
+            Command::SpeedUp => {
+                if let Some(playback) = &mut self.playback {
+                    playback.faster();
+                }
+            }
+            Command::SpeedDown => {
+                if let Some(playback) = &mut self.playback {
+                    playback.slower();
+                }
+            }
             Command::Select => match self.active {
                 Some(active) => {
                     if active == self.selected {
                         self.reset_selection();
+                    } else if self.selected == Location::Stock {
+                        self.play(Move::Draw);
                     } else {
-                        match (self.selected, active) {
-                            (Location::Stock, _) => {
-                                self.deal_from_stock();
-                            }
-                            (Location::Tableau(_), Location::Waste) => {
-                                self.take_from_waste();
-                            }
-                            (Location::Tableau(_), Location::Foundation(_)) => {
-                                self.take_from_foundation();
-                            }
-                            (Location::Foundation(_), _) => {
-                                self.place_in_foundation();
-                            }
-                            (Location::Tableau(_), Location::Tableau(_)) => {
-                                self.move_between_tableau();
+                        match self.game.move_between(active, self.selected) {
+                            Some(mv) => {
+                                self.play(mv);
+                                self.reset_selection();
                             }
-                            _ => {
-                                self.active = Some(self.selected);
+                            None => {
+                                if !matches!(self.selected, Location::Foundation(_)) {
+                                    self.active = Some(self.selected);
+                                }
                             }
                         }
                     }
                 }
                 _ => {
                     self.active = if self.selected == Location::Stock {
-                        self.deal_from_stock();
+                        self.play(Move::Draw);
                         Some(Location::Waste)
                     } else {
                         Some(self.selected)
@@ -305,55 +1263,74 @@ impl App {
                 }
             },
             Command::MoveDown => match self.selected {
-                Location::Stock => self.selected = Location::Tableau(0),
-                Location::Waste => self.selected = Location::Tableau(1),
-                Location::Foundation(index) => self.selected = Location::Tableau(3 + index),
                 Location::Tableau(_) => (),
+                location => {
+                    let slot = self.top_row_slot(location);
+                    self.selected = Location::Tableau(self.column_below(slot));
+                }
             },
             Command::MoveLeft => match self.selected {
-                Location::Stock => (),
-                Location::Waste => self.selected = Location::Stock,
-                Location::Foundation(index) => {
-                    if index == 0 {
-                        self.selected = Location::Waste
-                    } else {
-                        self.selected = Location::Foundation(index - 1)
-                    }
-                }
                 Location::Tableau(index) => {
                     if index != 0 {
                         self.selected = Location::Tableau(index - 1)
                     }
                 }
+                _ => self.move_in_top_row(-1),
             },
             Command::MoveRight => match self.selected {
-                Location::Stock => self.selected = Location::Waste,
-                Location::Waste => self.selected = Location::Foundation(0),
-                Location::Foundation(index) => {
-                    if index != 3 {
-                        self.selected = Location::Foundation(index + 1)
-                    }
-                }
                 Location::Tableau(index) => {
-                    if index != 6 {
+                    if index + 1 < self.game.rules.tableau_columns {
                         self.selected = Location::Tableau(index + 1)
                     }
                 }
+                _ => self.move_in_top_row(1),
             },
-            Command::MoveUp => match self.selected {
-                Location::Stock => (),
-                Location::Waste => (),
-                Location::Foundation(_) => (),
-                Location::Tableau(index) => {
-                    if index == 0 {
-                        self.selected = Location::Stock
-                    } else if index == 1 || index == 2 {
-                        self.selected = Location::Waste
-                    } else {
-                        self.selected = Location::Foundation(index - 3)
-                    }
+            Command::MoveUp => {
+                if let Location::Tableau(index) = self.selected {
+                    self.selected = self.slot_above(index)
                 }
-            },
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mock_app() -> App {
+        // Keeps the player's own settings and data out of the tests
+        let dir = std::env::temp_dir().join(format!("tersoli-app-{}", std::process::id()));
+        unsafe {
+            std::env::set_var("TERSOLI_DATA_DIR", &dir);
+            std::env::set_var("TERSOLI_CONFIG_DIR", &dir);
         }
+        App::new(Game::new(Rules::klondike(), 1))
+    }
+
+    #[test]
+    fn test_paused_requests_change_nothing() {
+        let mut app = mock_app();
+        app.pause();
+        let position = app.game.position();
+
+        for command in [Command::Select, Command::AutoPlace, Command::Undo] {
+            let response = app.handle_request(Request::Command { command });
+            assert!(matches!(response, Response::Error { .. }));
+        }
+        for request in [Request::Move { mv: Move::Draw }, Request::State] {
+            assert!(matches!(
+                app.handle_request(request),
+                Response::Error { .. }
+            ));
+        }
+        assert_eq!(app.game.position(), position);
+        assert_eq!(app.game.moves, 0);
+        assert!(app.paused.is_some());
+
+        app.handle_request(Request::Command {
+            command: Command::TogglePause,
+        });
+        assert!(app.paused.is_none());
     }
 }
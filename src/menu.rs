@@ -0,0 +1,125 @@
+use crate::{
+    i18n::{self, fill},
+    rules::{PRESETS, Rules},
+};
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum MenuItem {
+    Continue,
+    NewGame,
+    Restart,
+    Rules,
+    Stats,
+    Settings,
+    Quit,
+}
+
+pub const ITEMS: [MenuItem; 7] = [
+    MenuItem::Continue,
+    MenuItem::NewGame,
+    MenuItem::Restart,
+    MenuItem::Rules,
+    MenuItem::Stats,
+    MenuItem::Settings,
+    MenuItem::Quit,
+];
+
+// What the player is about to walk away from a game in progress for
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Leave {
+    NewGame(Rules),
+    Restart,
+    Quit,
+}
+
+pub struct Menu {
+    pub selected: usize,
+    // Rules for the next new game, picked from the presets
+    pub rules: Rules,
+}
+
+impl Menu {
+    pub fn new(rules: Rules) -> Self {
+        Self { selected: 0, rules }
+    }
+
+    pub fn item(&self) -> MenuItem {
+        ITEMS[self.selected]
+    }
+
+    pub fn up(&mut self) {
+        self.selected = (self.selected + ITEMS.len() - 1) % ITEMS.len();
+    }
+
+    pub fn down(&mut self) {
+        self.selected = (self.selected + 1) % ITEMS.len();
+    }
+
+    // Custom rules from the command line come before the presets
+    pub fn cycle_rules(&mut self, step: isize) {
+        let presets: Vec<Rules> = PRESETS
+            .iter()
+            .filter_map(|name| Rules::preset(name))
+            .collect();
+        let index = match presets.iter().position(|rules| *rules == self.rules) {
+            Some(index) => (index as isize + step).rem_euclid(presets.len() as isize),
+            None if step < 0 => presets.len() as isize - 1,
+            None => 0,
+        };
+        self.rules = presets[index as usize];
+    }
+
+    pub fn rules_name(&self) -> String {
+        PRESETS
+            .iter()
+            .find(|name| Rules::preset(name) == Some(self.rules))
+            .map_or_else(|| self.rules.spec(), |name| name.to_string())
+    }
+
+    pub fn label(&self, item: MenuItem) -> String {
+        let text = i18n::text();
+        match item {
+            MenuItem::Continue => text.menu_continue.to_string(),
+            MenuItem::NewGame => text.menu_new_game.to_string(),
+            MenuItem::Restart => text.menu_restart.to_string(),
+            MenuItem::Rules => fill(text.menu_rules, &[&self.rules_name()]),
+            MenuItem::Stats => text.menu_stats.to_string(),
+            MenuItem::Settings => text.menu_settings.to_string(),
+            MenuItem::Quit => text.menu_quit.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_navigate() {
+        let mut menu = Menu::new(Rules::klondike());
+
+        menu.up();
+        assert_eq!(menu.item(), MenuItem::Quit);
+        menu.down();
+        menu.down();
+        assert_eq!(menu.item(), MenuItem::NewGame);
+    }
+
+    #[test]
+    fn test_cycle_rules() {
+        let mut menu = Menu::new(Rules::klondike());
+
+        menu.cycle_rules(1);
+        assert_eq!(menu.rules_name(), "klondike3");
+        menu.cycle_rules(-2);
+        assert_eq!(menu.rules_name(), "double");
+
+        menu.rules = Rules::from_spec("klondike,passes=3").unwrap();
+        assert!(
+            menu.label(MenuItem::Rules)
+                .starts_with("Rules: ◂ klondike,draw=1,passes=3")
+        );
+        menu.cycle_rules(1);
+        assert_eq!(menu.rules, Rules::klondike());
+    }
+}
@@ -0,0 +1,101 @@
+use serde::{Deserialize, Serialize};
+
+use crate::location::Location;
+
+// Serialized in the text notation from notation.rs, e.g. "W>T3" or "T2:5>T6"
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub enum Move {
+    Draw,
+    WasteToTableau(usize),
+    WasteToFoundation(usize),
+    ReserveToTableau(usize),
+    ReserveToFoundation(usize),
+    TableauToFoundation(usize, usize),
+    TableauToTableau(usize, usize, usize),
+    FoundationToTableau(usize, usize),
+}
+
+impl Move {
+    pub fn between(from: Location, to: Location) -> Option<Self> {
+        match (from, to) {
+            (Location::Waste, Location::Tableau(column)) => Some(Move::WasteToTableau(column)),
+            (Location::Waste, Location::Foundation(slot)) => Some(Move::WasteToFoundation(slot)),
+            (Location::Reserve, Location::Tableau(column)) => Some(Move::ReserveToTableau(column)),
+            (Location::Reserve, Location::Foundation(slot)) => {
+                Some(Move::ReserveToFoundation(slot))
+            }
+            (Location::Tableau(column), Location::Foundation(slot)) => {
+                Some(Move::TableauToFoundation(column, slot))
+            }
+            (Location::Foundation(slot), Location::Tableau(column)) => {
+                Some(Move::FoundationToTableau(slot, column))
+            }
+            _ => None,
+        }
+    }
+
+    pub fn source(&self) -> Location {
+        match *self {
+            Move::Draw => Location::Stock,
+            Move::WasteToTableau(_) | Move::WasteToFoundation(_) => Location::Waste,
+            Move::ReserveToTableau(_) | Move::ReserveToFoundation(_) => Location::Reserve,
+            Move::TableauToFoundation(column, _) | Move::TableauToTableau(column, _, _) => {
+                Location::Tableau(column)
+            }
+            Move::FoundationToTableau(slot, _) => Location::Foundation(slot),
+        }
+    }
+
+    pub fn destination(&self) -> Location {
+        match *self {
+            Move::Draw => Location::Waste,
+            Move::WasteToTableau(column)
+            | Move::ReserveToTableau(column)
+            | Move::TableauToTableau(_, column, _)
+            | Move::FoundationToTableau(_, column) => Location::Tableau(column),
+            Move::WasteToFoundation(slot)
+            | Move::ReserveToFoundation(slot)
+            | Move::TableauToFoundation(_, slot) => Location::Foundation(slot),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_between() {
+        assert_eq!(
+            Move::between(Location::Waste, Location::Tableau(3)),
+            Some(Move::WasteToTableau(3))
+        );
+        assert_eq!(
+            Move::between(Location::Tableau(1), Location::Foundation(2)),
+            Some(Move::TableauToFoundation(1, 2))
+        );
+        assert_eq!(
+            Move::between(Location::Tableau(1), Location::Tableau(2)),
+            None
+        );
+        assert_eq!(Move::between(Location::Stock, Location::Waste), None);
+        assert_eq!(
+            Move::between(Location::Foundation(0), Location::Foundation(1)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_source_and_destination() {
+        let tableau_move = Move::TableauToTableau(2, 5, 3);
+        assert_eq!(tableau_move.source(), Location::Tableau(2));
+        assert_eq!(tableau_move.destination(), Location::Tableau(5));
+
+        assert_eq!(Move::Draw.source(), Location::Stock);
+        assert_eq!(
+            Move::ReserveToFoundation(1).destination(),
+            Location::Foundation(1)
+        );
+    }
+}
@@ -0,0 +1,462 @@
+use std::{fmt, str::FromStr};
+
+use crate::{
+    card::Card, foundation::Foundation, game::Game, location::Location, moves::Move,
+    reserve::Reserve, rules::Rules, stock::Stock, suit::Suit, tableau::Tableau, waste::Waste,
+};
+
+// Positions are seven space separated fields, similar to FEN in chess:
+// <tableau> <stock> <waste> <foundations> <reserve> <base rank> <recycles>
+// Tableau columns are separated by '/', with face-down cards before '|' and face-up after it.
+// Piles list cards from bottom to top, foundations only list their top card, '-' is empty.
+const RANKS: &str = "A23456789TJQK";
+const SUITS: &str = "shcd";
+
+pub fn format_rank(rank: u8) -> char {
+    RANKS.chars().nth(rank as usize - 1).unwrap_or('?')
+}
+
+pub fn format_card(card: Card) -> String {
+    let suit = match card.suit {
+        Suit::Spades => 's',
+        Suit::Hearts => 'h',
+        Suit::Clubs => 'c',
+        Suit::Diamonds => 'd',
+    };
+
+    format!("{}{}", format_rank(card.rank), suit)
+}
+
+fn parse_rank(rank: char) -> Result<u8, String> {
+    match RANKS.find(rank) {
+        Some(index) => Ok(index as u8 + 1),
+        None => Err(format!("Invalid rank '{}'", rank)),
+    }
+}
+
+pub fn parse_card(text: &str) -> Result<Card, String> {
+    let mut chars = text.chars();
+
+    let (rank, suit) = match (chars.next(), chars.next(), chars.next()) {
+        (Some(rank), Some(suit), None) => (rank, suit),
+        _ => return Err(format!("Invalid card '{}'", text)),
+    };
+
+    let suit = match SUITS.find(suit) {
+        Some(0) => Suit::Spades,
+        Some(1) => Suit::Hearts,
+        Some(2) => Suit::Clubs,
+        Some(3) => Suit::Diamonds,
+        _ => return Err(format!("Invalid suit in card '{}'", text)),
+    };
+
+    Ok(Card::new(suit, parse_rank(rank)?))
+}
+
+fn format_cards(cards: &[Card]) -> String {
+    cards.iter().map(|card| format_card(*card)).collect()
+}
+
+fn format_pile(cards: &[Card]) -> String {
+    if cards.is_empty() {
+        return "-".to_string();
+    }
+
+    format_cards(cards)
+}
+
+fn parse_cards(text: &str) -> Result<Vec<Card>, String> {
+    if text == "-" {
+        return Ok(vec![]);
+    }
+
+    let chars: Vec<char> = text.chars().collect();
+    if !chars.len().is_multiple_of(2) {
+        return Err(format!("Invalid card list '{}'", text));
+    }
+
+    chars
+        .chunks(2)
+        .map(|pair| parse_card(&pair.iter().collect::<String>()))
+        .collect()
+}
+
+pub fn format_location(location: Location) -> String {
+    match location {
+        Location::Stock => "S".to_string(),
+        Location::Waste => "W".to_string(),
+        Location::Reserve => "R".to_string(),
+        Location::Tableau(column) => format!("T{}", column + 1),
+        Location::Foundation(slot) => format!("F{}", slot + 1),
+    }
+}
+
+pub fn parse_location(text: &str) -> Result<Location, String> {
+    let index = || match text[1..].parse::<usize>() {
+        Ok(number) if number > 0 => Ok(number - 1),
+        _ => Err(format!("Invalid pile '{}'", text)),
+    };
+
+    match text.chars().next() {
+        Some('S') if text.len() == 1 => Ok(Location::Stock),
+        Some('W') if text.len() == 1 => Ok(Location::Waste),
+        Some('R') if text.len() == 1 => Ok(Location::Reserve),
+        Some('T') => Ok(Location::Tableau(index()?)),
+        Some('F') => Ok(Location::Foundation(index()?)),
+        _ => Err(format!("Invalid pile '{}'", text)),
+    }
+}
+
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let source = format_location(self.source());
+        let destination = format_location(self.destination());
+
+        match *self {
+            Move::TableauToTableau(_, _, count) if count > 1 => {
+                write!(f, "{}:{}>{}", source, count, destination)
+            }
+            _ => write!(f, "{}>{}", source, destination),
+        }
+    }
+}
+
+impl FromStr for Move {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let text = text.trim();
+        if text == "S" {
+            return Ok(Move::Draw);
+        }
+
+        let (source, destination) = text
+            .split_once('>')
+            .ok_or_else(|| format!("Invalid move '{}', expected e.g. W>T3", text))?;
+
+        let (source, count) = match source.split_once(':') {
+            Some((source, count)) => match count.parse::<usize>() {
+                Ok(count) if count > 0 => (source, count),
+                _ => return Err(format!("Invalid card count in move '{}'", text)),
+            },
+            None => (source, 1),
+        };
+
+        let from = parse_location(source)?;
+        let to = parse_location(destination)?;
+
+        match (from, to) {
+            (Location::Stock, Location::Waste) => Ok(Move::Draw),
+            (Location::Tableau(column), Location::Tableau(target)) if column != target => {
+                Ok(Move::TableauToTableau(column, target, count))
+            }
+            _ if count > 1 => Err(format!(
+                "Only tableau moves can have a count, got '{}'",
+                text
+            )),
+            _ => Move::between(from, to).ok_or_else(|| format!("Impossible move '{}'", text)),
+        }
+    }
+}
+
+impl From<Move> for String {
+    fn from(mv: Move) -> Self {
+        mv.to_string()
+    }
+}
+
+impl TryFrom<String> for Move {
+    type Error = String;
+
+    fn try_from(text: String) -> Result<Self, Self::Error> {
+        text.parse()
+    }
+}
+
+pub fn format_position(game: &Game) -> String {
+    let tableau = (0..game.tableau.cards.len())
+        .map(|column| {
+            let cards = &game.tableau.cards[column];
+            if cards.is_empty() {
+                return "-".to_string();
+            }
+
+            let cutoff = (game.tableau.cutoffs[column] as usize).min(cards.len());
+            format!(
+                "{}|{}",
+                format_cards(&cards[..cutoff]),
+                format_cards(&cards[cutoff..])
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("/");
+
+    let foundations = (0..game.foundation.cards.len())
+        .map(
+            |slot| match game.foundation.get_top_card(Location::Foundation(slot)) {
+                Some(card) => format_card(card),
+                None => "-".to_string(),
+            },
+        )
+        .collect::<Vec<_>>()
+        .join("/");
+
+    format!(
+        "{} {} {} {} {} {} {}",
+        tableau,
+        format_pile(&game.stock.cards),
+        format_pile(&game.waste.cards),
+        foundations,
+        format_pile(&game.reserve.cards),
+        format_rank(game.foundation.base_rank),
+        game.stock.recycles
+    )
+}
+
+pub fn parse_position(rules: Rules, text: &str) -> Result<Game, String> {
+    let fields: Vec<&str> = text.split_whitespace().collect();
+    let [tableau, stock, waste, foundations, reserve, base, recycles] = fields[..] else {
+        return Err(format!(
+            "Position needs 7 fields, got {}: <tableau> <stock> <waste> <foundations> <reserve> <base> <recycles>",
+            fields.len()
+        ));
+    };
+
+    let mut game_tableau = Tableau::new(rules);
+    game_tableau.cards.clear();
+    game_tableau.cutoffs.clear();
+    for column in tableau.split('/') {
+        let (down, up) = match column {
+            "-" => ("", ""),
+            _ => column
+                .split_once('|')
+                .ok_or_else(|| format!("Tableau column '{}' is missing '|'", column))?,
+        };
+        let mut cards = parse_cards(down)?;
+        game_tableau.cutoffs.push(cards.len() as u8);
+        cards.extend(parse_cards(up)?);
+        game_tableau.cards.push(cards);
+    }
+
+    if game_tableau.cards.len() != rules.tableau_columns {
+        return Err(format!(
+            "Position has {} tableau columns, rules need {}",
+            game_tableau.cards.len(),
+            rules.tableau_columns
+        ));
+    }
+
+    let base_rank = match base.chars().collect::<Vec<_>>()[..] {
+        [rank] => parse_rank(rank)?,
+        _ => return Err(format!("Invalid base rank '{}'", base)),
+    };
+
+    let mut foundation = Foundation::new(rules);
+    foundation.base_rank = base_rank;
+    let slots: Vec<&str> = foundations.split('/').collect();
+    if slots.len() != foundation.cards.len() {
+        return Err(format!(
+            "Position has {} foundations, rules need {}",
+            slots.len(),
+            foundation.cards.len()
+        ));
+    }
+
+    for (slot, top) in slots.iter().enumerate() {
+        if *top == "-" {
+            continue;
+        }
+
+        let top_card = parse_card(top)?;
+        let mut rank = base_rank;
+        for _ in 0..13 {
+            foundation.add_card(Card::new(top_card.suit, rank), slot);
+            if rank == top_card.rank {
+                break;
+            }
+            rank = rules.next_rank(rank).unwrap_or(rank);
+        }
+
+        if foundation
+            .get_top_card(Location::Foundation(slot))
+            .map(|card| card.rank)
+            != Some(top_card.rank)
+        {
+            return Err(format!(
+                "Foundation '{}' can't be built from base rank",
+                top
+            ));
+        }
+    }
+
+    let game = Game {
+        rules,
+        seed: 0,
+        stock: Stock {
+            cards: parse_cards(stock)?,
+            recycles: recycles
+                .parse()
+                .map_err(|_| format!("Invalid recycle count '{}'", recycles))?,
+        },
+        waste: Waste {
+            cards: parse_cards(waste)?,
+        },
+        reserve: Reserve {
+            cards: parse_cards(reserve)?,
+        },
+        tableau: game_tableau,
+        foundation,
+        moves: 0,
+        events: Default::default(),
+    };
+
+    check_card_counts(&game)?;
+    Ok(game)
+}
+
+fn check_card_counts(game: &Game) -> Result<(), String> {
+    let mut counts = [[0usize; 13]; 4];
+
+    let foundation_cards = game.foundation.cards.iter().flatten().flatten();
+    let all_cards = game
+        .tableau
+        .cards
+        .iter()
+        .flatten()
+        .chain(&game.stock.cards)
+        .chain(&game.waste.cards)
+        .chain(&game.reserve.cards)
+        .chain(foundation_cards);
+
+    for card in all_cards {
+        let suit = match card.suit {
+            Suit::Spades => 0,
+            Suit::Hearts => 1,
+            Suit::Clubs => 2,
+            Suit::Diamonds => 3,
+        };
+        counts[suit][card.rank as usize - 1] += 1;
+    }
+
+    for (suit, ranks) in counts.iter().enumerate() {
+        for (rank, count) in ranks.iter().enumerate() {
+            if *count != game.rules.decks {
+                let card = Card::new(
+                    crate::utils::get_suit_by_card_suit_index(suit),
+                    rank as u8 + 1,
+                );
+                return Err(format!(
+                    "Card {} appears {} times, expected {}",
+                    format_card(card),
+                    count,
+                    game.rules.decks
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_card_round_trip() {
+        assert_eq!(format_card(Card::new(Suit::Hearts, 10)), "Th");
+        assert_eq!(format_card(Card::new(Suit::Spades, 1)), "As");
+
+        let card = parse_card("Qd").unwrap();
+        assert_eq!(card.suit, Suit::Diamonds);
+        assert_eq!(card.rank, 12);
+
+        assert!(parse_card("Zd").is_err());
+        assert!(parse_card("Qx").is_err());
+        assert!(parse_card("Qdd").is_err());
+    }
+
+    #[test]
+    fn test_move_round_trip() {
+        let moves = [
+            Move::Draw,
+            Move::WasteToTableau(2),
+            Move::WasteToFoundation(0),
+            Move::ReserveToTableau(3),
+            Move::ReserveToFoundation(7),
+            Move::TableauToFoundation(1, 2),
+            Move::TableauToTableau(1, 5, 1),
+            Move::TableauToTableau(1, 5, 5),
+            Move::FoundationToTableau(3, 6),
+        ];
+
+        for mv in moves {
+            assert_eq!(mv.to_string().parse::<Move>(), Ok(mv));
+        }
+
+        assert_eq!(Move::WasteToTableau(2).to_string(), "W>T3");
+        assert_eq!(Move::TableauToTableau(1, 5, 5).to_string(), "T2:5>T6");
+        assert_eq!("S".parse::<Move>(), Ok(Move::Draw));
+    }
+
+    #[test]
+    fn test_parse_invalid_moves() {
+        assert!("W".parse::<Move>().is_err());
+        assert!("W>T0".parse::<Move>().is_err());
+        assert!("W:3>T1".parse::<Move>().is_err());
+        assert!("T1>T1".parse::<Move>().is_err());
+        assert!("F1>F2".parse::<Move>().is_err());
+        assert!("X>T1".parse::<Move>().is_err());
+    }
+
+    #[test]
+    fn test_position_round_trip() {
+        for rules in [
+            Rules::klondike(),
+            Rules::canfield(),
+            Rules::double_klondike(),
+        ] {
+            let mut game = Game::new(rules, 99);
+            game.apply(Move::Draw);
+
+            let position = format_position(&game);
+            let parsed = parse_position(rules, &position).unwrap();
+
+            assert_eq!(format_position(&parsed), position);
+            assert_eq!(parsed.tableau.cutoffs, game.tableau.cutoffs);
+            assert_eq!(parsed.foundation.base_rank, game.foundation.base_rank);
+        }
+    }
+
+    #[test]
+    fn test_parse_position() {
+        let position = "|Ks/As|2h/-/-/-/-/- \
+            3c4c5c6c7c8c9cTcJcQcKc2d3d4d5d6d7d8d9dTdJdQdKd - \
+            3s/-/2c/Ad - 2 0";
+        let game = parse_position(Rules::klondike(), position);
+        assert!(game.is_err());
+
+        let position = "|Ks/Ah|2h/-/-/-/-/- \
+            3c4c5c6c7c8c9cTcJcQcKc2d3d4d5d6d7d8d9dTdJdQdKd3h4h5h6h7h8h9hThJhQhKh - \
+            Qs/-/2c/Ad - A 0";
+        let game = parse_position(Rules::klondike(), position).unwrap();
+
+        assert_eq!(game.tableau.cards[1].len(), 2);
+        assert_eq!(game.tableau.cutoffs[1], 1);
+        assert_eq!(game.foundation.cards[0].len(), 13);
+        assert_eq!(game.foundation.cards[2].len(), 3);
+        assert_eq!(game.stock.cards.len(), 34);
+        assert_eq!(format_position(&game), position);
+    }
+
+    #[test]
+    fn test_parse_position_errors() {
+        assert!(parse_position(Rules::klondike(), "").is_err());
+
+        let game = Game::new(Rules::klondike(), 3);
+        let position = format_position(&game);
+
+        assert!(parse_position(Rules::canfield(), &position).is_err());
+        assert!(parse_position(Rules::klondike(), &position.replacen("|", "", 1)).is_err());
+    }
+}
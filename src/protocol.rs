@@ -0,0 +1,357 @@
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    command::Command,
+    game::Game,
+    location::Location,
+    moves::Move,
+    notation::{format_card, format_location},
+    replay::Replay,
+    rules::Rules,
+};
+
+#[derive(Deserialize, PartialEq, Debug)]
+#[serde(tag = "cmd", rename_all = "lowercase")]
+pub enum Request {
+    State,
+    Moves,
+    Move {
+        #[serde(rename = "move")]
+        mv: Move,
+    },
+    Undo,
+    New {
+        seed: Option<u64>,
+        rules: Option<String>,
+    },
+    Seed,
+    Command {
+        command: Command,
+    },
+}
+
+#[derive(Serialize, Debug)]
+#[serde(tag = "event", rename_all = "lowercase")]
+pub enum Response {
+    State(StateView),
+    Moves {
+        moves: Vec<Move>,
+    },
+    Moved {
+        #[serde(rename = "move")]
+        mv: Move,
+        won: bool,
+    },
+    Undone {
+        #[serde(rename = "move")]
+        mv: Move,
+    },
+    Seed {
+        seed: u64,
+    },
+    Changed {
+        state: StateView,
+        selected: String,
+        active: Option<String>,
+    },
+    Error {
+        message: String,
+    },
+}
+
+#[derive(Serialize, Debug)]
+pub struct ColumnView {
+    pub hidden: usize,
+    pub cards: Vec<String>,
+}
+
+// What a player can see, face-down cards are only counted unless the session is omniscient
+#[derive(Serialize, Debug)]
+pub struct StateView {
+    pub rules: String,
+    // The seed gives away the whole deal, so only omniscient sessions see it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    pub moves: u32,
+    pub won: bool,
+    pub stock: usize,
+    pub recycles: u32,
+    pub waste: Vec<String>,
+    pub reserve: Option<String>,
+    pub reserve_size: usize,
+    pub foundations: Vec<Option<String>>,
+    pub tableau: Vec<ColumnView>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub position: Option<String>,
+}
+
+pub struct Session {
+    pub game: Game,
+    pub replay: Replay,
+    pub omniscient: bool,
+    started: Instant,
+}
+
+impl Session {
+    pub fn new(replay: Replay, omniscient: bool) -> Self {
+        Self {
+            game: replay.game_at(replay.moves.len()),
+            replay,
+            omniscient,
+            started: Instant::now(),
+        }
+    }
+
+    pub fn state(&self) -> StateView {
+        state_view(&self.game, self.replay.seed, self.omniscient)
+    }
+
+    pub fn handle(&mut self, request: Request) -> Response {
+        match request {
+            Request::State => Response::State(self.state()),
+            Request::Moves => Response::Moves {
+                moves: self.game.legal_moves(),
+            },
+            Request::Move { mv } => {
+                if !self.game.apply(mv) {
+                    return error(format!("Illegal move '{}'", mv));
+                }
+                // Nothing listens for events here, so don't let them pile up
+                self.game.events.drain();
+
+                let millis = self.started.elapsed().as_millis() as u64;
+                self.replay.push(millis, mv);
+                Response::Moved {
+                    mv,
+                    won: self.game.is_won(),
+                }
+            }
+            Request::Undo => match self.replay.moves.pop() {
+                Some(timed) => {
+                    self.game = self.replay.game_at(self.replay.moves.len());
+                    Response::Undone { mv: timed.mv }
+                }
+                None => error("Nothing to undo".to_string()),
+            },
+            // Knowing the seed means knowing every card, so only omniscient sessions pick it
+            Request::New { seed: Some(_), .. } if !self.omniscient => {
+                error("Picking the seed needs --omniscient".to_string())
+            }
+            Request::New { seed, rules } => {
+                let rules = match rules {
+                    Some(spec) => match Rules::from_spec(&spec) {
+                        Ok(rules) => rules,
+                        Err(message) => return error(message),
+                    },
+                    None => self.game.rules,
+                };
+                let seed = seed.unwrap_or_else(rand::random);
+
+                *self = Self::new(Replay::new(seed, &rules), self.omniscient);
+                Response::State(self.state())
+            }
+            Request::Seed if !self.omniscient => error(SEED_HIDDEN.to_string()),
+            Request::Seed => Response::Seed {
+                seed: self.replay.seed,
+            },
+            Request::Command { .. } => {
+                error("Commands need a game shown in the terminal".to_string())
+            }
+        }
+    }
+
+    pub fn handle_line(&mut self, line: &str) -> String {
+        respond(line, |request| self.handle(request))
+    }
+}
+
+pub const SEED_HIDDEN: &str = "The seed is only shown with --omniscient";
+
+pub fn state_view(game: &Game, seed: u64, omniscient: bool) -> StateView {
+    let cards = |cards: &[_]| cards.iter().map(|card| format_card(*card)).collect();
+
+    let tableau = game
+        .tableau
+        .cards
+        .iter()
+        .zip(game.tableau.cutoffs.iter())
+        .map(|(column, cutoff)| {
+            let hidden = (*cutoff as usize).min(column.len());
+            ColumnView {
+                hidden,
+                cards: cards(&column[hidden..]),
+            }
+        })
+        .collect();
+
+    let foundations = (0..game.foundation.cards.len())
+        .map(|slot| {
+            game.foundation
+                .get_top_card(Location::Foundation(slot))
+                .map(format_card)
+        })
+        .collect();
+
+    StateView {
+        rules: game.rules.spec(),
+        seed: omniscient.then_some(seed),
+        moves: game.moves,
+        won: game.is_won(),
+        stock: game.stock.cards.len(),
+        recycles: game.stock.recycles,
+        waste: cards(&game.waste.cards),
+        reserve: game.reserve.get_top_card().map(format_card),
+        reserve_size: game.reserve.cards.len(),
+        foundations,
+        tableau,
+        position: omniscient.then(|| game.position()),
+    }
+}
+
+pub fn respond(line: &str, handle: impl FnOnce(Request) -> Response) -> String {
+    let response = match serde_json::from_str::<Request>(line) {
+        Ok(request) => handle(request),
+        Err(err) => error(format!("Invalid request: {}", err)),
+    };
+
+    serde_json::to_string(&response).unwrap_or_default()
+}
+
+pub fn changed(game: &Game, seed: u64, selected: Location, active: Option<Location>) -> Response {
+    Response::Changed {
+        state: state_view(game, seed, false),
+        selected: format_location(selected),
+        active: active.map(format_location),
+    }
+}
+
+pub fn error(message: String) -> Response {
+    Response::Error { message }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mock_session(omniscient: bool) -> Session {
+        Session::new(Replay::new(8, &Rules::klondike()), omniscient)
+    }
+
+    #[test]
+    fn test_state_hides_face_down_cards() {
+        let session = mock_session(false);
+        let state = session.state();
+
+        assert_eq!(state.tableau[6].hidden, 6);
+        assert_eq!(state.tableau[6].cards.len(), 1);
+        assert_eq!(state.stock, 24);
+        assert!(state.position.is_none());
+
+        let line = serde_json::to_string(&Response::State(state)).unwrap();
+        for card in session.game.tableau.cards[6][..6].iter() {
+            assert!(!line.contains(&format!("\"{}\"", format_card(*card))));
+        }
+    }
+
+    #[test]
+    fn test_omniscient_state_has_position() {
+        let session = mock_session(true);
+
+        assert_eq!(session.state().position, Some(session.game.position()));
+    }
+
+    #[test]
+    fn test_move_and_undo() {
+        let mut session = mock_session(false);
+
+        let response = session.handle_line(r#"{"cmd":"move","move":"S>W"}"#);
+        assert_eq!(response, r#"{"event":"moved","move":"S>W","won":false}"#);
+        assert_eq!(session.game.waste.cards.len(), 1);
+
+        let response = session.handle_line(r#"{"cmd":"undo"}"#);
+        assert_eq!(response, r#"{"event":"undone","move":"S>W"}"#);
+        assert_eq!(session.game.waste.cards.len(), 0);
+
+        let response = session.handle_line(r#"{"cmd":"undo"}"#);
+        assert!(response.contains("error"));
+    }
+
+    #[test]
+    fn test_invalid_requests() {
+        let mut session = mock_session(false);
+
+        assert!(session.handle_line("state").contains("error"));
+        assert!(session.handle_line(r#"{"cmd":"fly"}"#).contains("error"));
+        assert!(
+            session
+                .handle_line(r#"{"cmd":"move","move":"X>T1"}"#)
+                .contains("Invalid pile")
+        );
+        assert!(
+            session
+                .handle_line(r#"{"cmd":"move","move":"F1>T1"}"#)
+                .contains("Illegal move")
+        );
+    }
+
+    #[test]
+    fn test_commands_need_terminal() {
+        let line = r#"{"cmd":"command","command":"move_left"}"#;
+        assert_eq!(
+            serde_json::from_str::<Request>(line).unwrap(),
+            Request::Command {
+                command: Command::MoveLeft
+            }
+        );
+
+        assert!(mock_session(false).handle_line(line).contains("error"));
+    }
+
+    #[test]
+    fn test_new_game_and_seed() {
+        let mut session = mock_session(true);
+
+        session.handle_line(r#"{"cmd":"new","seed":5,"rules":"canfield"}"#);
+        assert_eq!(session.game.rules, Rules::canfield());
+        assert_eq!(
+            session.handle_line(r#"{"cmd":"seed"}"#),
+            r#"{"event":"seed","seed":5}"#
+        );
+
+        assert!(
+            session
+                .handle_line(r#"{"cmd":"new","rules":"spider"}"#)
+                .contains("error")
+        );
+    }
+
+    #[test]
+    fn test_seed_is_hidden() {
+        let mut session = mock_session(false);
+
+        assert!(session.state().seed.is_none());
+        assert!(!session.handle_line(r#"{"cmd":"state"}"#).contains("seed"));
+        assert!(session.handle_line(r#"{"cmd":"seed"}"#).contains("error"));
+        assert_eq!(mock_session(true).state().seed, Some(8));
+
+        let position = session.game.position();
+        assert!(
+            session
+                .handle_line(r#"{"cmd":"new","seed":5}"#)
+                .contains("error")
+        );
+        assert_eq!(session.game.position(), position);
+        session.handle_line(r#"{"cmd":"new","rules":"canfield"}"#);
+        assert_eq!(session.game.rules, Rules::canfield());
+    }
+
+    #[test]
+    fn test_legal_moves_response() {
+        let mut session = mock_session(false);
+
+        let response = session.handle_line(r#"{"cmd":"moves"}"#);
+        assert!(response.starts_with(r#"{"event":"moves","moves":["S>W""#));
+    }
+}
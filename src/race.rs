@@ -0,0 +1,328 @@
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::{Shutdown, TcpListener, TcpStream},
+    sync::mpsc::{self, Receiver, TryRecvError},
+    thread,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{game::Game, location::Location, notation::format_card, rules::Rules};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum Message {
+    Hello {
+        seed: u64,
+        rules: String,
+    },
+    Progress {
+        foundations: Vec<Option<String>>,
+        cards: usize,
+        moves: u32,
+    },
+    Won,
+    Result {
+        host_won: bool,
+    },
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Outcome {
+    Won,
+    Lost,
+    OpponentLeft,
+}
+
+#[derive(Default, Clone, PartialEq, Debug)]
+pub struct Progress {
+    pub foundations: Vec<Option<String>>,
+    pub cards: usize,
+    pub moves: u32,
+}
+
+// Both players deal the same seed, the host decides who finished first so a close race has one winner
+pub struct Race {
+    pub seed: u64,
+    pub rules: Rules,
+    pub host: bool,
+    pub opponent: Progress,
+    pub outcome: Option<Outcome>,
+    stream: TcpStream,
+    incoming: Receiver<Message>,
+    sent: Option<(usize, u32)>,
+    finished: bool,
+}
+
+impl Race {
+    // A bare port listens on every interface, so players on the same network can join
+    pub fn listen(address: &str) -> std::io::Result<TcpListener> {
+        match address.parse::<u16>() {
+            Ok(port) => TcpListener::bind(("0.0.0.0", port)),
+            Err(_) => TcpListener::bind(address),
+        }
+    }
+
+    pub fn accept(
+        listener: &TcpListener,
+        seed: u64,
+        rules: Rules,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let (stream, _) = listener.accept()?;
+        let reader = BufReader::new(stream.try_clone()?);
+
+        let mut race = Self::new(stream, reader, seed, rules, true);
+        race.send(&Message::Hello {
+            seed,
+            rules: rules.spec(),
+        });
+        Ok(race)
+    }
+
+    pub fn join(address: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let address = match address.parse::<u16>() {
+            Ok(port) => format!("127.0.0.1:{}", port),
+            Err(_) => address.to_string(),
+        };
+        let stream = TcpStream::connect(address)?;
+        let mut reader = BufReader::new(stream.try_clone()?);
+
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        match serde_json::from_str(&line)? {
+            Message::Hello { seed, rules } => {
+                let rules = Rules::from_spec(&rules)?;
+                Ok(Self::new(stream, reader, seed, rules, false))
+            }
+            _ => Err("Expected a hello from the host".into()),
+        }
+    }
+
+    fn new(
+        stream: TcpStream,
+        reader: BufReader<TcpStream>,
+        seed: u64,
+        rules: Rules,
+        host: bool,
+    ) -> Self {
+        let (sender, incoming) = mpsc::channel();
+        thread::spawn(move || {
+            for line in reader.lines().map_while(Result::ok) {
+                let Ok(message) = serde_json::from_str(&line) else {
+                    continue;
+                };
+                if sender.send(message).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Self {
+            seed,
+            rules,
+            host,
+            opponent: Progress::default(),
+            outcome: None,
+            stream,
+            incoming,
+            sent: None,
+            finished: false,
+        }
+    }
+
+    fn send(&mut self, message: &Message) {
+        if let Ok(line) = serde_json::to_string(message) {
+            let _ = writeln!(self.stream, "{}", line);
+        }
+    }
+
+    fn finish(&mut self, host_won: bool) {
+        self.outcome = Some(if host_won == self.host {
+            Outcome::Won
+        } else {
+            Outcome::Lost
+        });
+        self.send(&Message::Result { host_won });
+    }
+
+    pub fn update(&mut self, game: &Game) {
+        loop {
+            match self.incoming.try_recv() {
+                Ok(Message::Progress {
+                    foundations,
+                    cards,
+                    moves,
+                }) => {
+                    // Sent by the other player, who could claim more cards than the deck has
+                    self.opponent = Progress {
+                        foundations,
+                        cards: cards.min(52 * self.rules.decks),
+                        moves,
+                    }
+                }
+                Ok(Message::Won) if self.host && self.outcome.is_none() => self.finish(false),
+                Ok(Message::Result { host_won }) if !self.host => {
+                    self.outcome = Some(if host_won {
+                        Outcome::Lost
+                    } else {
+                        Outcome::Won
+                    })
+                }
+                Ok(_) => {}
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    if self.outcome.is_none() {
+                        self.outcome = Some(Outcome::OpponentLeft);
+                    }
+                    break;
+                }
+            }
+        }
+
+        let progress = progress(game);
+        if self.sent != Some((progress.cards, progress.moves)) {
+            self.sent = Some((progress.cards, progress.moves));
+            self.send(&Message::Progress {
+                foundations: progress.foundations,
+                cards: progress.cards,
+                moves: progress.moves,
+            });
+        }
+
+        if game.is_won() && !self.finished {
+            self.finished = true;
+            if !self.host {
+                self.send(&Message::Won);
+            } else if self.outcome.is_none() {
+                self.finish(true);
+            }
+        }
+    }
+}
+
+// The reader thread holds a clone of the stream, so close it explicitly for the opponent to notice
+impl Drop for Race {
+    fn drop(&mut self) {
+        let _ = self.stream.shutdown(Shutdown::Both);
+    }
+}
+
+pub fn progress(game: &Game) -> Progress {
+    let foundations = (0..game.foundation.cards.len())
+        .map(|slot| {
+            game.foundation
+                .get_top_card(Location::Foundation(slot))
+                .map(format_card)
+        })
+        .collect();
+    let cards = game.foundation.cards.iter().flatten().flatten().count();
+
+    Progress {
+        foundations,
+        cards,
+        moves: game.moves,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    fn wait_for<T>(mut check: impl FnMut() -> Option<T>) -> T {
+        let started = Instant::now();
+        loop {
+            if let Some(value) = check() {
+                return value;
+            }
+            assert!(started.elapsed() < Duration::from_secs(5), "timed out");
+            thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    fn mock_races() -> (Race, Race) {
+        let listener = Race::listen("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+
+        let guest = thread::spawn(move || Race::join(&address).unwrap());
+        let host = Race::accept(&listener, 9, Rules::canfield()).unwrap();
+        (host, guest.join().unwrap())
+    }
+
+    #[test]
+    fn test_join_gets_deal() {
+        let (host, guest) = mock_races();
+
+        assert!(host.host);
+        assert!(!guest.host);
+        assert_eq!(guest.seed, 9);
+        assert_eq!(guest.rules, Rules::canfield());
+    }
+
+    #[test]
+    fn test_first_to_win() {
+        let (mut host, mut guest) = mock_races();
+        let rules = Rules::klondike();
+        let won = Game::from_position(rules, "-/-/-/-/-/-/- - - Ks/Kh/Kc/Kd - A 0").unwrap();
+        let playing = Game::new(rules, 9);
+
+        guest.update(&won);
+        assert_eq!(guest.outcome, None);
+
+        assert_eq!(
+            wait_for(|| {
+                host.update(&playing);
+                host.outcome
+            }),
+            Outcome::Lost
+        );
+        assert_eq!(host.opponent.cards, 52);
+        assert_eq!(host.opponent.foundations[0], Some("Ks".to_string()));
+
+        assert_eq!(
+            wait_for(|| {
+                guest.update(&won);
+                guest.outcome
+            }),
+            Outcome::Won
+        );
+
+        // A late win from the host doesn't change the result
+        host.update(&won);
+        assert_eq!(host.outcome, Some(Outcome::Lost));
+    }
+
+    #[test]
+    fn test_progress_is_clamped() {
+        let (mut host, mut guest) = mock_races();
+        guest.send(&Message::Progress {
+            foundations: vec![],
+            cards: usize::MAX,
+            moves: 1,
+        });
+
+        let game = Game::new(Rules::canfield(), 9);
+        assert_eq!(
+            wait_for(|| {
+                host.update(&game);
+                (host.opponent.moves > 0).then_some(host.opponent.cards)
+            }),
+            52
+        );
+    }
+
+    #[test]
+    fn test_opponent_left() {
+        let (mut host, guest) = mock_races();
+        drop(guest);
+
+        let game = Game::new(Rules::canfield(), 9);
+        assert_eq!(
+            wait_for(|| {
+                host.update(&game);
+                host.outcome
+            }),
+            Outcome::OpponentLeft
+        );
+    }
+}
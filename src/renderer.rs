@@ -1,188 +1,925 @@
+use std::fmt::Display;
+
 use ratatui::{
     Frame,
-    layout::Layout,
-    style::{Color, Style},
-    text::Span,
-    widgets::{Block, Widget, canvas::Canvas},
+    layout::{Alignment, Constraint, Layout, Margin, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span, Text},
+    widgets::{Block, Cell, Clear, Paragraph, Row, Table, Widget, Wrap, canvas::Canvas},
 };
 
 use crate::{
-    card::Card, foundation::Foundation, location::Location, stock::Stock, suit::Suit,
-    tableau::Tableau, utils::get_suit_by_card_suit_index, waste::Waste,
+    animation::{Animation, Cascade, Effect, cascade_path},
+    card::Card,
+    card_view::{CardSize, CardWidget, Face, Fan, fan_down, fan_right},
+    daily::{DailyHistory, Date},
+    game::Game,
+    history,
+    i18n::{self, fill},
+    keymap::{Binding, keys_text},
+    leaderboard::{Entry, SortBy},
+    location::Location,
+    menu::{ITEMS, Leave, Menu},
+    race::{Outcome, Race},
+    replay::Playback,
+    rules::Rules,
+    scoring::Scoring,
+    settings::{SETTINGS, Settings},
+    stats::{Stats, optional_duration},
+    suit::Suit,
+    theme::Theme,
+    utils::format_duration,
 };
 
 pub fn render(
     frame: &mut Frame,
-    horizontal: Layout,
-    vertical: Layout,
-    tableau: &Tableau,
-    stock: &Stock,
-    waste: &Waste,
-    foundation: &Foundation,
+    area: Rect,
+    game: &Game,
     selected: Location,
     active: Option<Location>,
-    won: bool,
+    theme: &Theme,
 ) {
-    let [top, bottom] = vertical.areas(frame.area());
-    let [
-        stock_rect,
-        waste_rect,
-        second_empty,
-        spades,
-        hearts,
-        clubs,
-        diamonds,
-    ] = horizontal.areas(top);
-
-    let [first, second, third, fourth, fifth, sixth, seventh] = horizontal.areas(bottom);
+    let won = game.is_won();
+    let (slots, columns) = pile_areas(area, game);
+    let targets = active.map_or_else(Vec::new, |active| game.destinations(active));
+
+    let mut piles = vec![Location::Stock, Location::Waste];
+    if game.rules.reserve_size > 0 && !won {
+        piles.push(Location::Reserve);
+    } else {
+        frame.render_widget(empty_canvas(won), slots[2]);
+    }
+    piles.extend((0..slots.len() - 3).map(Location::Foundation));
+    piles.extend((0..columns.len()).map(Location::Tableau));
+
+    for location in piles {
+        let block = Block::bordered()
+            .title(pile_title(game, location))
+            .border_style(canvas_style(location, selected, active, &targets, theme));
+        let area = pile_area(area, game, location);
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let fan = pile_rects(inner, game, location, 0);
+        for (face, rect) in pile_faces(game, location).into_iter().zip(fan.rects) {
+            frame.render_widget(card_widget(face, fan.size, theme), rect);
+        }
+        if fan.scrolled > 0 {
+            let marker = match format!("↑ {} more", fan.scrolled) {
+                marker if marker.chars().count() <= inner.width as usize => marker,
+                _ => format!("↑{}", fan.scrolled),
+            };
+            frame.render_widget(
+                Paragraph::new(marker).style(Style::default().fg(theme.muted)),
+                Rect { height: 1, ..inner },
+            );
+        }
+    }
+}
+
+// Klondike needs about 40x10 before piles get too narrow to tell cards apart
+pub fn min_board_size(game: &Game) -> (u16, u16) {
+    let piles = (3 + game.foundation.cards.len()).max(game.rules.tableau_columns) as u16;
+    (piles * 5, 10)
+}
+
+pub fn render_too_small(frame: &mut Frame, area: Rect, needed: (u16, u16)) {
+    let text = i18n::text();
+    let lines = vec![
+        Line::from(text.too_small),
+        Line::from(""),
+        Line::from(fill(
+            text.too_small_need,
+            &[&needed.0, &needed.1, &area.width, &area.height],
+        )),
+        Line::from(text.too_small_hint),
+    ];
 
     frame.render_widget(
-        stock_canvas(Location::Stock, stock, selected, active),
-        stock_rect,
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: true })
+            .alignment(Alignment::Center)
+            .style(Style::default().fg(Color::Yellow)),
+        area,
     );
+}
+
+// Top row slots are stock, waste, reserve and then the foundations, the bottom row is the tableau
+pub fn pile_areas(area: Rect, game: &Game) -> (Vec<Rect>, Vec<Rect>) {
+    let slots = 3 + game.foundation.cards.len() as u32;
+    let horizontal = Layout::horizontal((0..slots).map(|_| Constraint::Ratio(1, slots)));
+
+    let columns = game.rules.tableau_columns as u32;
+    let tableau_horizontal =
+        Layout::horizontal((0..columns).map(|_| Constraint::Ratio(1, columns)));
+
+    // The top row only ever holds one card per pile, the tableau gets the rest of the height
+    let slot_width = (area.width / slots as u16).saturating_sub(2);
+    let card = CardSize::fit(Rect::new(0, 0, slot_width, u16::MAX));
+    let top_height = (card.height() + 2).min(area.height / 2);
+    let vertical = Layout::vertical([Constraint::Length(top_height), Constraint::Fill(1)]);
+
+    let [top, bottom] = vertical.areas(area);
+    (
+        horizontal.split(top).to_vec(),
+        tableau_horizontal.split(bottom).to_vec(),
+    )
+}
+
+fn pile_area(area: Rect, game: &Game, location: Location) -> Rect {
+    let (slots, columns) = pile_areas(area, game);
+    match location {
+        Location::Stock => slots[0],
+        Location::Waste => slots[1],
+        Location::Reserve => slots[2],
+        Location::Foundation(index) => slots[3 + index],
+        Location::Tableau(index) => columns[index],
+    }
+}
+
+// Where each card of a pile goes inside its border, with room for extra face-up cards on top
+fn pile_rects(inner: Rect, game: &Game, location: Location, extra: usize) -> Fan {
+    match location {
+        Location::Tableau(column) => {
+            let hidden = game.tableau.cutoffs[column] as usize;
+            let visible = game.tableau.cards[column].len() - hidden + extra;
+            fan_down(inner, hidden, visible.max(1))
+        }
+        Location::Waste => {
+            let count = (game.waste.get_last_cards().len() + extra).clamp(1, 3);
+            fan_right(inner, count)
+        }
+        _ => {
+            let size = CardSize::fit(inner);
+            let width = size.width_in(inner);
+            let x = inner.x + (inner.width - width) / 2;
+            Fan {
+                size,
+                rects: vec![Rect::new(x, inner.y, width, size.height())],
+                scrolled: 0,
+            }
+        }
+    }
+}
+
+fn pile_faces(game: &Game, location: Location) -> Vec<Face> {
+    let faces: Vec<Face> = match location {
+        Location::Tableau(column) => {
+            let hidden = game.tableau.cutoffs[column] as usize;
+            let visible = game.tableau.get_visible_cards(column);
+            vec![Face::Down; hidden]
+                .into_iter()
+                .chain(visible.into_iter().map(Face::Up))
+                .collect()
+        }
+        Location::Waste => game
+            .waste
+            .get_last_cards()
+            .into_iter()
+            .rev()
+            .map(Face::Up)
+            .collect(),
+        Location::Stock if game.stock.cards.is_empty() => vec![],
+        Location::Stock => vec![Face::Down],
+        _ => game
+            .get_top_card(location)
+            .map(Face::Up)
+            .into_iter()
+            .collect(),
+    };
+
+    if faces.is_empty() {
+        return vec![Face::Empty];
+    }
+    faces
+}
+
+fn pile_title(game: &Game, location: Location) -> String {
+    let text = i18n::text();
+    match location {
+        Location::Stock => match game.rules.pass_limit {
+            Some(limit) => fill(
+                text.in_stock_pass,
+                &[
+                    &game.stock.cards.len() as &dyn Display,
+                    &(game.stock.recycles + 1),
+                    &limit,
+                ],
+            ),
+            None => fill(text.in_stock, &[&game.stock.cards.len()]),
+        },
+        Location::Waste => text.waste.to_string(),
+        Location::Reserve => fill(text.reserve, &[&game.reserve.cards.len()]),
+        Location::Tableau(column) => fill(text.hidden, &[&game.tableau.cutoffs[column]]),
+        Location::Foundation(_) => match game.get_top_card(location) {
+            Some(card) => text.suit(card.suit),
+            _ => text.foundation,
+        }
+        .to_string(),
+    }
+}
+
+fn card_widget(face: Face, size: CardSize, theme: &Theme) -> CardWidget {
+    let card = match face {
+        Face::Up(card) => Some(card),
+        _ => None,
+    };
+
+    CardWidget {
+        face,
+        size,
+        style: card_text_style(card, theme),
+        border_style: match face {
+            Face::Empty => Style::default().fg(theme.muted),
+            _ => Style::default().fg(theme.card_border),
+        },
+        back_style: Style::default().fg(theme.back),
+        card_style: theme.card_style,
+    }
+}
+
+fn pile_inner(area: Rect, game: &Game, location: Location) -> Rect {
+    pile_area(area, game, location).inner(Margin::new(1, 1))
+}
+
+pub fn render_animation(
+    frame: &mut Frame,
+    area: Rect,
+    game: &Game,
+    animation: &Animation,
+    theme: &Theme,
+) {
+    let progress = animation.progress();
+
+    match animation.effect {
+        Effect::Slide {
+            card,
+            from,
+            to,
+            count,
+            ..
+        } => {
+            let start = pile_rects(pile_inner(area, game, from), game, from, count).rects;
+            let Fan {
+                size, rects: end, ..
+            } = pile_rects(pile_inner(area, game, to), game, to, 0);
+            let lead = end.len().saturating_sub(count);
+            let (start, landed) = (start[start.len() - count], end[lead]);
+
+            // Ease in and out so the card settles into place
+            let t = progress * progress * (3.0 - 2.0 * progress);
+            let lerp = |a: u16, b: u16| (a as f64 + (b as f64 - a as f64) * t).round() as u16;
+            let (x, y) = (lerp(start.x, landed.x), lerp(start.y, landed.y));
+
+            // A run of tableau cards keeps its fan on the way
+            let cards = match to {
+                Location::Tableau(column) => game.tableau.cards[column][lead..].to_vec(),
+                _ => vec![card],
+            };
+            for (card, rect) in cards.into_iter().zip(end[lead..].iter()) {
+                let rect = Rect::new(x, y + rect.y - landed.y, rect.width, rect.height);
+                frame.render_widget(card_widget(Face::Up(card), size, theme), rect);
+            }
+        }
+        Effect::Flip { card, column } => {
+            let location = Location::Tableau(column);
+            let Fan { size, rects, .. } =
+                pile_rects(pile_inner(area, game, location), game, location, 0);
+            let Some(rect) = rects.get(game.tableau.cutoffs[column] as usize) else {
+                return;
+            };
+
+            // The card narrows to an edge showing its back, then widens again face up
+            let scale = (progress * std::f64::consts::PI).cos().abs();
+            let width = ((rect.width as f64 * scale).round() as u16).max(1);
+            let rect = Rect::new(
+                rect.x + (rect.width - width) / 2,
+                rect.y,
+                width,
+                rect.height,
+            );
+            let face = if progress < 0.5 {
+                Face::Down
+            } else {
+                Face::Up(card)
+            };
+            let size = match size {
+                CardSize::Compact => CardSize::Compact,
+                CardSize::Normal { height, .. } => CardSize::Normal { width, height },
+            };
+            frame.render_widget(card_widget(face, size, theme), rect);
+        }
+    }
+}
+
+pub fn render_cascade(
+    frame: &mut Frame,
+    area: Rect,
+    game: &Game,
+    cascade: &Cascade,
+    theme: &Theme,
+) {
+    for (index, (card, slot)) in cascade.cards.iter().enumerate() {
+        let Some(time) = cascade.flight_time(index) else {
+            break;
+        };
+
+        let location = Location::Foundation(*slot);
+        let fan = pile_rects(pile_inner(area, game, location), game, location, 0);
+        let (size, start) = (fan.size, fan.rects[0]);
+        let bounds = (
+            area.width.saturating_sub(start.width) as f64 + 1.0,
+            area.height.saturating_sub(start.height) as f64 + 1.0,
+        );
+        let from = ((start.x - area.x) as f64, (start.y - area.y) as f64);
+
+        // Every step leaves a copy of the card behind, like the classic
+        for (x, y) in cascade_path(from, Cascade::velocity(index), bounds, time) {
+            let rect = Rect::new(
+                area.x + x as u16,
+                area.y + y as u16,
+                start.width,
+                start.height,
+            );
+            frame.render_widget(card_widget(Face::Up(*card), size, theme), rect);
+        }
+    }
+}
+
+pub fn render_stats(frame: &mut Frame, stats: &Stats) {
+    let text = i18n::text();
+    let block = Block::bordered().title(text.stats_title);
+    let summaries = stats.summaries();
+
+    if summaries.is_empty() {
+        frame.render_widget(Paragraph::new(text.no_games).block(block), frame.area());
+        return;
+    }
+
+    let header = Row::new(text.stats_headers).style(Style::default().add_modifier(Modifier::BOLD));
+
+    let rows = summaries.into_iter().map(|summary| {
+        Row::new([
+            Cell::from(summary.variant),
+            Cell::from(summary.rules),
+            Cell::from(summary.games_played.to_string()),
+            Cell::from(summary.wins.to_string()),
+            Cell::from(format!("{:.1}", summary.win_rate)),
+            Cell::from(summary.current_streak.to_string()),
+            Cell::from(summary.best_streak.to_string()),
+            Cell::from(optional_duration(summary.fastest_win_secs)),
+            Cell::from(match summary.fewest_moves {
+                Some(moves) => moves.to_string(),
+                None => "-".to_string(),
+            }),
+            Cell::from(optional_duration(summary.average_time_secs)),
+        ])
+    });
+
+    let widths = [
+        Constraint::Length(9),
+        Constraint::Fill(1),
+        Constraint::Length(7),
+        Constraint::Length(5),
+        Constraint::Length(6),
+        Constraint::Length(7),
+        Constraint::Length(5),
+        Constraint::Length(8),
+        Constraint::Length(7),
+        Constraint::Length(8),
+    ];
+
     frame.render_widget(
-        waste_canvas(Location::Waste, waste, selected, active),
-        waste_rect,
+        Table::new(rows, widths).header(header).block(block),
+        frame.area(),
     );
-    frame.render_widget(empty_canvas(won), second_empty);
-    frame.render_widget(
-        foundation_canvas(Location::Foundation(0), foundation, selected, active),
-        spades,
+}
+
+pub fn render_calendar(frame: &mut Frame, history: &DailyHistory, month: Date, today: Date) {
+    let first = Date::new(month.year, month.month, 1);
+    let completed = (1..=first.days_in_month())
+        .filter_map(|day| history.get(Date::new(month.year, month.month, day)))
+        .filter(|record| record.completed())
+        .count();
+    let text = i18n::text();
+    let title = fill(
+        text.calendar_title,
+        &[
+            &text.months[month.month as usize - 1] as &dyn Display,
+            &month.year,
+            &completed,
+        ],
     );
+    let block = Block::bordered().title(title);
+
+    let header = Row::new(text.weekdays).style(Style::default().add_modifier(Modifier::BOLD));
+
+    let mut cells: Vec<Cell> = (0..first.weekday()).map(|_| Cell::from("")).collect();
+    for day in 1..=first.days_in_month() {
+        let date = Date::new(month.year, month.month, day);
+        let record = history.get(date);
+
+        let day_style = if date == today {
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+        let mut lines = vec![Line::styled(day.to_string(), day_style)];
+
+        match record.and_then(|record| record.best()) {
+            Some(best) => {
+                let style = Style::default().fg(Color::LightGreen);
+                lines.push(Line::styled(
+                    format!("✓ {}", format_duration(best.secs)),
+                    style,
+                ));
+                lines.push(Line::styled(
+                    fill(text.calendar_moves, &[&best.moves]),
+                    style,
+                ));
+            }
+            None if record.is_some() => lines.push(Line::styled(
+                text.calendar_tried,
+                Style::default().fg(Color::DarkGray),
+            )),
+            None => {}
+        }
+
+        cells.push(Cell::from(Text::from(lines)));
+    }
+
+    let rows: Vec<Row> = cells
+        .chunks(7)
+        .map(|week| Row::new(week.to_vec()).height(3))
+        .collect();
+
     frame.render_widget(
-        foundation_canvas(Location::Foundation(1), foundation, selected, active),
-        hearts,
+        Table::new(rows, [Constraint::Ratio(1, 7); 7])
+            .header(header)
+            .block(block),
+        frame.area(),
     );
-    frame.render_widget(
-        foundation_canvas(Location::Foundation(2), foundation, selected, active),
-        clubs,
+}
+
+pub fn render_leaderboard(
+    frame: &mut Frame,
+    entries: &[Entry],
+    rules: &str,
+    scoring: Scoring,
+    sort_by: SortBy,
+) {
+    let text = i18n::text();
+    let title = fill(
+        text.leaderboard_title,
+        &[&scoring as &dyn Display, &sort_by.name()],
     );
+    let block = Block::bordered()
+        .title(title)
+        .title_bottom(rules.to_string());
+
+    if entries.is_empty() {
+        frame.render_widget(
+            Paragraph::new(text.no_high_scores).block(block),
+            frame.area(),
+        );
+        return;
+    }
+
+    let header =
+        Row::new(text.leaderboard_headers).style(Style::default().add_modifier(Modifier::BOLD));
+    let rows = entries.iter().enumerate().map(|(rank, entry)| {
+        Row::new([
+            Cell::from((rank + 1).to_string()),
+            Cell::from(entry.name.clone()),
+            Cell::from(entry.score.to_string()),
+            Cell::from(format_duration(entry.secs)),
+            Cell::from(entry.moves.to_string()),
+            Cell::from(entry.date.clone()),
+        ])
+    });
+    let widths = [
+        Constraint::Length(4),
+        Constraint::Fill(1),
+        Constraint::Length(7),
+        Constraint::Length(9),
+        Constraint::Length(6),
+        Constraint::Length(11),
+    ];
+
     frame.render_widget(
-        foundation_canvas(Location::Foundation(3), foundation, selected, active),
-        diamonds,
+        Table::new(rows, widths).header(header).block(block),
+        frame.area(),
     );
+}
+
+pub fn render_name_entry(frame: &mut Frame, entry: &Entry, scoring: Scoring) {
+    let [_, area, _] = Layout::vertical([
+        Constraint::Fill(1),
+        Constraint::Length(7),
+        Constraint::Fill(1),
+    ])
+    .areas(frame.area());
+    let [_, area, _] = Layout::horizontal([
+        Constraint::Fill(1),
+        Constraint::Length(40),
+        Constraint::Fill(1),
+    ])
+    .areas(area);
+
+    let text = i18n::text();
+    let lines = vec![
+        Line::from(fill(
+            text.name_entry_score,
+            &[
+                &entry.score as &dyn Display,
+                &scoring,
+                &format_duration(entry.secs),
+                &entry.moves,
+            ],
+        )),
+        Line::from(""),
+        Line::from(fill(text.name_entry_name, &[&entry.name])),
+        Line::from(""),
+        Line::styled(text.name_entry_hint, Style::default().fg(Color::DarkGray)),
+    ];
 
+    frame.render_widget(Clear, area);
     frame.render_widget(
-        card_canvas(Location::Tableau(0), tableau, selected, active),
-        first,
+        Paragraph::new(lines).block(
+            Block::bordered()
+                .title(text.name_entry_title)
+                .style(Style::default().fg(Color::Magenta)),
+        ),
+        area,
     );
-    frame.render_widget(
-        card_canvas(Location::Tableau(1), tableau, selected, active),
-        second,
+}
+
+// Keys come from the same bindings that handle input, so the list can't go stale
+pub fn render_help(frame: &mut Frame, bindings: &[Binding], rules: &Rules) {
+    let text = i18n::text();
+    let heading = Style::default().fg(Color::Yellow);
+    let width = bindings
+        .iter()
+        .map(|binding| keys_text(binding).chars().count())
+        .max()
+        .unwrap_or(0);
+
+    let mut lines = vec![Line::styled(text.help_keys, heading)];
+    lines.extend(bindings.iter().map(|binding| {
+        Line::from(vec![
+            Span::styled(
+                format!("  {:<width$}  ", keys_text(binding), width = width),
+                Style::default().fg(Color::LightCyan),
+            ),
+            Span::raw((binding.description)(text)),
+        ])
+    }));
+
+    lines.push(Line::from(""));
+    lines.push(Line::styled(text.help_rules, heading));
+    lines.extend(
+        rules
+            .describe()
+            .into_iter()
+            .map(|line| Line::from(format!("  {}", line))),
     );
-    frame.render_widget(
-        card_canvas(Location::Tableau(2), tableau, selected, active),
-        third,
+
+    lines.push(Line::from(""));
+    lines.push(Line::styled(text.help_pile_titles, heading));
+    lines.push(Line::from(format!("  {}", text.help_in_stock)));
+    lines.push(Line::from(format!("  {}", text.help_hidden)));
+    if rules.reserve_size > 0 {
+        lines.push(Line::from(format!("  {}", text.help_reserve)));
+    }
+
+    let area = frame.area();
+    let popup = Rect::new(
+        area.x + area.width.saturating_sub(84) / 2,
+        area.y + area.height.saturating_sub(lines.len() as u16 + 2) / 2,
+        84.min(area.width),
+        (lines.len() as u16 + 2).min(area.height),
     );
+    frame.render_widget(Clear, popup);
     frame.render_widget(
-        card_canvas(Location::Tableau(3), tableau, selected, active),
-        fourth,
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(Block::bordered().title(text.help_title)),
+        popup,
     );
+}
+
+pub fn render_menu(frame: &mut Frame, menu: &Menu) {
+    let [_, area, _] = Layout::vertical([
+        Constraint::Fill(1),
+        Constraint::Length(ITEMS.len() as u16 + 4),
+        Constraint::Fill(1),
+    ])
+    .areas(frame.area());
+    let [_, area, _] = Layout::horizontal([
+        Constraint::Fill(1),
+        Constraint::Length(40),
+        Constraint::Fill(1),
+    ])
+    .areas(area);
+
+    let mut lines: Vec<Line> = ITEMS
+        .iter()
+        .enumerate()
+        .map(|(index, item)| {
+            if index == menu.selected {
+                Line::styled(
+                    format!("> {}", menu.label(*item)),
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                )
+            } else {
+                Line::from(format!("  {}", menu.label(*item)))
+            }
+        })
+        .collect();
+    lines.push(Line::from(""));
+    lines.push(Line::styled(
+        i18n::text().menu_hint,
+        Style::default().fg(Color::DarkGray),
+    ));
+
+    frame.render_widget(Clear, area);
     frame.render_widget(
-        card_canvas(Location::Tableau(4), tableau, selected, active),
-        fifth,
+        Paragraph::new(lines).block(Block::bordered().title(i18n::text().menu_title)),
+        area,
     );
+}
+
+pub fn render_settings(frame: &mut Frame, settings: &Settings) {
+    let text = i18n::text();
+    let [_, area, _] = Layout::vertical([
+        Constraint::Fill(1),
+        Constraint::Length(SETTINGS.len() as u16 + 5),
+        Constraint::Fill(1),
+    ])
+    .areas(frame.area());
+    let [_, area, _] = Layout::horizontal([
+        Constraint::Fill(1),
+        Constraint::Length(66),
+        Constraint::Fill(1),
+    ])
+    .areas(area);
+
+    let mut lines: Vec<Line> = SETTINGS
+        .iter()
+        .enumerate()
+        .map(|(index, setting)| {
+            let style = if index == settings.selected {
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            let marker = if index == settings.selected { ">" } else { " " };
+            let mut spans = vec![Span::styled(
+                format!(
+                    "{} {:<26}◂ {} ▸",
+                    marker,
+                    setting.label(),
+                    settings.value(*setting)
+                ),
+                style,
+            )];
+            if setting.next_deal() {
+                spans.push(Span::styled(
+                    format!("  {}", text.settings_next_deal),
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+            Line::from(spans)
+        })
+        .collect();
+    lines.push(Line::from(""));
+    lines.push(Line::styled(
+        text.settings_saved,
+        Style::default().fg(Color::DarkGray),
+    ));
+    lines.push(Line::styled(
+        text.settings_hint,
+        Style::default().fg(Color::DarkGray),
+    ));
+
+    frame.render_widget(Clear, area);
     frame.render_widget(
-        card_canvas(Location::Tableau(5), tableau, selected, active),
-        sixth,
+        Paragraph::new(lines).block(Block::bordered().title(text.settings_title)),
+        area,
     );
+}
+
+pub fn render_paused(frame: &mut Frame, elapsed: std::time::Duration) {
+    let text = i18n::text();
+    let [_, area, _] = Layout::vertical([
+        Constraint::Fill(1),
+        Constraint::Length(5),
+        Constraint::Fill(1),
+    ])
+    .areas(frame.area());
+
+    let lines = vec![
+        Line::styled(text.paused, Style::default().add_modifier(Modifier::BOLD)),
+        Line::from(fill(
+            text.paused_time,
+            &[&format_duration(elapsed.as_secs())],
+        )),
+        Line::from(""),
+        Line::styled(text.paused_hint, Style::default().fg(Color::DarkGray)),
+    ];
+    frame.render_widget(Paragraph::new(lines).alignment(Alignment::Center), area);
+}
+
+pub fn render_confirm(frame: &mut Frame, leave: Leave) {
+    let text = i18n::text();
+    let [_, area, _] = Layout::vertical([
+        Constraint::Fill(1),
+        Constraint::Length(6),
+        Constraint::Fill(1),
+    ])
+    .areas(frame.area());
+    let [_, area, _] = Layout::horizontal([
+        Constraint::Fill(1),
+        Constraint::Length(44),
+        Constraint::Fill(1),
+    ])
+    .areas(area);
+
+    let action = match leave {
+        Leave::NewGame(_) => text.confirm_new_game,
+        Leave::Restart => text.confirm_restart,
+        Leave::Quit => text.confirm_quit,
+    };
+    let lines = vec![
+        Line::from(action),
+        Line::from(text.confirm_loss),
+        Line::from(""),
+        Line::styled(text.confirm_hint, Style::default().fg(Color::DarkGray)),
+    ];
+
+    frame.render_widget(Clear, area);
     frame.render_widget(
-        card_canvas(Location::Tableau(6), tableau, selected, active),
-        seventh,
+        Paragraph::new(lines).block(
+            Block::bordered()
+                .title(text.confirm_title)
+                .style(Style::default().fg(Color::Magenta)),
+        ),
+        area,
     );
 }
 
-fn card_canvas(
-    pos: Location,
-    tableau: &Tableau,
-    selected: Location,
-    active: Option<Location>,
-) -> impl Widget {
-    let (visible_cards, card_text) = match pos {
-        Location::Tableau(index) => (
-            tableau.get_visible_cards(index),
-            format!("Hidden: {}", tableau.cutoffs[index]),
-        ),
-        _ => unreachable!("Can't draw tableau other than in tableau"),
+// Unlocked achievements stack in the top right corner, newest at the bottom
+pub fn render_toasts(frame: &mut Frame, toasts: &[(&str, &str)]) {
+    let area = frame.area();
+    let width = 32.min(area.width);
+
+    for (index, (heading, text)) in toasts.iter().enumerate() {
+        let y = area.y + 1 + 3 * index as u16;
+        if y + 3 > area.bottom() {
+            break;
+        }
+
+        let toast = Rect::new(area.right() - width, y, width, 3);
+        frame.render_widget(Clear, toast);
+        frame.render_widget(
+            Paragraph::new(*text).block(
+                Block::bordered()
+                    .title(*heading)
+                    .style(Style::default().fg(Color::Yellow)),
+            ),
+            toast,
+        );
+    }
+}
+
+pub fn render_playback_status(frame: &mut Frame, area: Rect, playback: &Playback) {
+    let text = i18n::text();
+    let state = if playback.playing {
+        text.replay_playing
+    } else if playback.is_finished() {
+        text.replay_finished
+    } else {
+        text.replay_paused
     };
 
-    Canvas::default()
-        .block(
-            Block::bordered()
-                .title(card_text)
-                .border_style(canvas_style(pos, selected, active)),
-        )
-        .x_bounds([0.0, 100.0])
-        .y_bounds([0.0, 100.0])
-        .paint(move |ctx| {
-            ctx.layer();
-            let amount_of_cards = visible_cards.len() as f64;
+    let status = fill(
+        text.replay_status,
+        &[
+            &state as &dyn Display,
+            &playback.position,
+            &playback.replay.moves.len(),
+            &playback.speed(),
+        ],
+    );
 
-            for (i, card) in visible_cards.iter().enumerate() {
-                let card_name = get_card(card.suit, card.rank);
+    frame.render_widget(
+        Paragraph::new(status).style(Style::default().fg(Color::Yellow)),
+        area,
+    );
+}
 
-                ctx.print(
-                    10.0,
-                    100.0 - (100.0 / (amount_of_cards) * i as f64),
-                    Span::styled(card_name.to_string(), card_text_style(Some(*card))),
-                );
-            }
-        })
+pub fn render_server_status(frame: &mut Frame, area: Rect, address: &str, clients: usize) {
+    let status = fill(
+        i18n::text().server_status,
+        &[&address as &dyn Display, &clients],
+    );
+
+    frame.render_widget(
+        Paragraph::new(status).style(Style::default().fg(Color::DarkGray)),
+        area,
+    );
 }
 
-fn stock_canvas(
-    pos: Location,
-    stock: &Stock,
-    selected: Location,
-    active: Option<Location>,
-) -> impl Widget {
-    let card_amount = stock.cards.len();
+pub fn render_race(frame: &mut Frame, area: Rect, race: &Race) {
+    let total = 52 * race.rules.decks;
+    let opponent = &race.opponent;
+    let filled = (opponent.cards.saturating_mul(16))
+        .checked_div(total)
+        .unwrap_or_default()
+        .min(16);
 
-    let card_text = format!("In stock: {}", card_amount);
+    let text = i18n::text();
+    let status = match race.outcome {
+        Some(Outcome::Won) => text.race_won,
+        Some(Outcome::Lost) => text.race_lost,
+        Some(Outcome::OpponentLeft) => text.race_opponent_left,
+        None => text.racing,
+    };
+    let foundations: Vec<String> = opponent
+        .foundations
+        .iter()
+        .map(|top| top.clone().unwrap_or("-".to_string()))
+        .collect();
 
-    Canvas::default()
-        .block(
-            Block::bordered()
-                .title(card_text)
-                .border_style(canvas_style(pos, selected, active)),
-        )
-        .x_bounds([0.0, 100.0])
-        .y_bounds([0.0, 100.0])
-        .paint(|_ctx| {})
+    let lines = vec![
+        Line::from(status),
+        Line::from(""),
+        Line::from(foundations.join(" ")),
+        Line::from(format!(
+            "{}{} {}/{}",
+            "█".repeat(filled),
+            "░".repeat(16 - filled),
+            opponent.cards,
+            total
+        )),
+        Line::from(fill(text.race_moves, &[&opponent.moves])),
+    ];
+
+    frame.render_widget(
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: true })
+            .block(Block::bordered().title(text.race_title)),
+        area,
+    );
 }
 
-fn waste_canvas(
-    pos: Location,
-    waste: &Waste,
-    selected: Location,
-    active: Option<Location>,
-) -> impl Widget {
-    let cards = waste.get_last_cards();
+// Lines of the current position are marked, and the list scrolls to keep them in view
+pub fn render_history(
+    frame: &mut Frame,
+    area: Rect,
+    entries: &[history::Entry],
+    current: usize,
+    preview: bool,
+    theme: &Theme,
+) {
+    let text = i18n::text();
+    let total = entries.last().map_or(0, |entry| entry.position);
+    let mut block = Block::bordered().title(if preview {
+        fill(text.history_preview, &[&current, &total])
+    } else {
+        text.history_title.to_string()
+    });
+    if preview {
+        block = block.title_bottom(text.history_hint);
+    }
 
-    Canvas::default()
-        .block(
-            Block::bordered()
-                .title("Waste pile")
-                .border_style(canvas_style(pos, selected, active)),
-        )
-        .x_bounds([0.0, 100.0])
-        .y_bounds([0.0, 100.0])
-        .paint(move |ctx| {
-            ctx.layer();
-            if cards.is_empty() {
-                ctx.print(10.0, 50.0, Span::styled("Empty", card_text_style(None)));
+    let last = entries
+        .iter()
+        .rposition(|entry| entry.position == current)
+        .unwrap_or_default();
+    let skip = (last + 1).saturating_sub(block.inner(area).height as usize);
+    let lines: Vec<Line> = entries
+        .iter()
+        .skip(skip)
+        .map(|entry| {
+            if entry.position == current {
+                Line::styled(
+                    format!("▸ {}", entry.text),
+                    Style::default().fg(theme.selected),
+                )
             } else {
-                let amount_of_cards = cards.len() as f64;
-                for (i, card) in cards.iter().enumerate() {
-                    let card_name = get_card(card.suit, card.rank);
-                    ctx.print(
-                        10.0,
-                        100.0 - (100.0 / (amount_of_cards) * i as f64),
-                        Span::styled(card_name.to_string(), card_text_style(Some(*card))),
-                    );
-                }
+                Line::from(format!("  {}", entry.text))
             }
         })
+        .collect();
+
+    frame.render_widget(Paragraph::new(lines).block(block), area);
 }
 
 fn empty_canvas(won: bool) -> impl Widget {
@@ -195,133 +932,236 @@ fn empty_canvas(won: bool) -> impl Widget {
                 ctx.print(
                     10.0,
                     50.0,
-                    Span::styled("You win!", Style::default().fg(Color::Magenta)),
+                    Span::styled(i18n::text().you_win, Style::default().fg(Color::Magenta)),
                 );
             }
         })
 }
 
-fn foundation_canvas(
+fn canvas_style(
     pos: Location,
-    foundation: &Foundation,
     selected: Location,
     active: Option<Location>,
-) -> impl Widget {
-    let suit_index = match pos {
-        Location::Foundation(index) => index,
-        _ => unreachable!("Can't draw foundation other than in foundation"),
-    };
-
-    let card_name = get_card(
-        get_suit_by_card_suit_index(suit_index),
-        foundation.get_top_value(pos),
-    );
-
-    Canvas::default()
-        .block(
-            Block::bordered()
-                .title(match suit_index {
-                    0 => "Spades",
-                    1 => "Hearts",
-                    2 => "Clubs",
-                    3 => "Diamonds",
-                    _ => "Error",
-                })
-                .border_style(canvas_style(pos, selected, active)),
-        )
-        .x_bounds([0.0, 100.0])
-        .y_bounds([0.0, 100.0])
-        .paint(move |ctx| {
-            ctx.layer();
-            ctx.print(
-                10.0,
-                50.0,
-                Span::styled(
-                    card_name.to_string(),
-                    Style::default().fg(if (suit_index + 1) % 2 == 0 {
-                        Color::LightRed
-                    } else {
-                        Color::LightGreen
-                    }),
-                ),
-            )
-        })
-}
-
-fn get_card(suit: Suit, rank: u8) -> String {
-    let suit_str = match suit {
-        Suit::Spades => "♠".to_string(),
-        Suit::Hearts => "♥".to_string(),
-        Suit::Clubs => "♣".to_string(),
-        Suit::Diamonds => "♦".to_string(),
-    };
-
-    let rank_str = match rank {
-        0 => "".to_string(),
-        1 => "Ace".to_string(),
-        2..=10 => rank.to_string(),
-        11 => "Jack".to_string(),
-        12 => "Queen".to_string(),
-        13 => "King".to_string(),
-        _ => "Error".to_string(),
-    };
-
-    format!("{} {}", rank_str, suit_str)
-}
-
-fn canvas_style(pos: Location, selected: Location, active: Option<Location>) -> Style {
+    targets: &[Location],
+    theme: &Theme,
+) -> Style {
     let is_selected = pos == selected;
     let is_active = match active {
         Some(active) => pos == active,
         _ => false,
     };
+    let is_target = targets.contains(&pos);
 
-    Style::default().fg(if is_selected && is_active {
-        Color::Green
+    if theme.colorless {
+        let style = match (is_selected, is_active) {
+            (true, true) => Style::default().add_modifier(Modifier::REVERSED | Modifier::BOLD),
+            (true, false) => Style::default().add_modifier(Modifier::REVERSED),
+            (false, true) => Style::default().add_modifier(Modifier::BOLD),
+            (false, false) => Style::default(),
+        };
+        return if is_target {
+            style.add_modifier(Modifier::UNDERLINED)
+        } else {
+            style
+        };
+    }
+
+    let style = Style::default().fg(if is_selected && is_active {
+        theme.selected_active
     } else if is_selected {
-        Color::Blue
+        theme.selected
     } else if is_active {
-        Color::Red
+        theme.active
+    } else if is_target {
+        theme.target
     } else {
-        Color::White
-    })
+        theme.pile
+    });
+    if is_selected && theme.bold_selection {
+        style.add_modifier(Modifier::BOLD)
+    } else {
+        style
+    }
 }
 
-fn card_text_style(card: Option<Card>) -> Style {
+fn card_text_style(card: Option<Card>, theme: &Theme) -> Style {
+    if theme.colorless {
+        return match card {
+            Some(card) if matches!(card.suit, Suit::Hearts | Suit::Diamonds) => {
+                Style::default().add_modifier(Modifier::UNDERLINED)
+            }
+            _ => Style::default(),
+        };
+    }
+
     Style::default().fg(match card {
-        Some(c) => match c.suit {
-            Suit::Hearts | Suit::Diamonds => Color::LightRed,
-            Suit::Clubs | Suit::Spades => Color::LightGreen,
-        },
-        _ => Color::Yellow,
+        Some(c) => theme.suit_color(c.suit),
+        _ => theme.label,
     })
 }
 
 #[cfg(test)]
 mod tests {
+    use std::{env, fs, path::Path};
+
+    use ratatui::{Terminal, backend::TestBackend};
+
     use super::*;
 
+    // Compares the screen with src/snapshots/<name>.txt, run with UPDATE_SNAPSHOTS=1 to rewrite them
+    fn mock_snapshot(name: &str, width: u16, height: u16, game: &Game) {
+        let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+        terminal
+            .draw(|frame| {
+                let area = frame.area();
+                let needed = min_board_size(game);
+                if area.width < needed.0 || area.height < needed.1 {
+                    render_too_small(frame, area, needed);
+                } else {
+                    render(frame, area, game, Location::Stock, None, &Theme::classic());
+                }
+            })
+            .unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let screen: String = (0..height)
+            .map(|y| {
+                let line: String = (0..width).map(|x| buffer[(x, y)].symbol()).collect();
+                line.trim_end().to_string() + "\n"
+            })
+            .collect();
+
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("src/snapshots")
+            .join(format!("{}.txt", name));
+        if env::var_os("UPDATE_SNAPSHOTS").is_some() {
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, &screen).unwrap();
+        }
+        let expected = fs::read_to_string(&path).unwrap_or_default();
+        assert!(screen == expected, "{} differs:\n{}", name, screen);
+    }
+
     #[test]
-    fn test_get_card() {
-        assert_eq!(get_card(Suit::Hearts, 8), "8 ♥");
-        assert_eq!(get_card(Suit::Spades, 13), "King ♠");
+    fn test_render_help() {
+        let mut terminal = Terminal::new(TestBackend::new(90, 40)).unwrap();
+        terminal
+            .draw(|frame| render_help(frame, crate::keymap::GAME, &Rules::canfield()))
+            .unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let screen: String = buffer.content().iter().map(|cell| cell.symbol()).collect();
+        assert!(screen.contains("? / F1"));
+        assert!(screen.contains("Undo the last move"));
+        assert!(screen.contains("Any card can fill an empty column"));
+        assert!(screen.contains("Reserve: N"));
+    }
+
+    #[test]
+    fn test_render_menu() {
+        let mut terminal = Terminal::new(TestBackend::new(60, 20)).unwrap();
+        let mut menu = Menu::new(Rules::klondike());
+        menu.down();
+        terminal.draw(|frame| render_menu(frame, &menu)).unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let screen: String = buffer.content().iter().map(|cell| cell.symbol()).collect();
+        assert!(screen.contains("> New game"));
+        assert!(screen.contains("Rules: ◂ klondike ▸"));
+
+        terminal
+            .draw(|frame| render_confirm(frame, Leave::NewGame(menu.rules)))
+            .unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let screen: String = buffer.content().iter().map(|cell| cell.symbol()).collect();
+        assert!(screen.contains("Start a new game?"));
+    }
+
+    #[test]
+    fn test_render_history() {
+        let mut terminal = Terminal::new(TestBackend::new(24, 6)).unwrap();
+        let entries: Vec<history::Entry> = (0..6)
+            .map(|position| history::Entry {
+                position,
+                text: format!("Move {}", position),
+            })
+            .collect();
+        terminal
+            .draw(|frame| {
+                render_history(frame, frame.area(), &entries, 5, false, &Theme::classic())
+            })
+            .unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let screen: String = buffer.content().iter().map(|cell| cell.symbol()).collect();
+        assert!(screen.contains("▸ Move 5"));
+        assert!(!screen.contains("Move 1"));
+
+        terminal
+            .draw(|frame| render_history(frame, frame.area(), &entries, 1, true, &Theme::classic()))
+            .unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let screen: String = buffer.content().iter().map(|cell| cell.symbol()).collect();
+        assert!(screen.contains("Move 1 of 5"));
+        assert!(screen.contains("▸ Move 1"));
+    }
+
+    #[test]
+    fn test_render_paused() {
+        let mut terminal = Terminal::new(TestBackend::new(40, 10)).unwrap();
+        terminal
+            .draw(|frame| render_paused(frame, std::time::Duration::from_secs(75)))
+            .unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let screen: String = buffer.content().iter().map(|cell| cell.symbol()).collect();
+        assert!(screen.contains("Paused"));
+        assert!(screen.contains("Time 1:15"));
+    }
+
+    #[test]
+    fn test_snapshots() {
+        let game = Game::new(Rules::klondike(), 1);
+        mock_snapshot("tiny", 30, 8, &game);
+        mock_snapshot("small", 40, 14, &game);
+        mock_snapshot("standard", 80, 24, &game);
+        mock_snapshot("large", 160, 50, &game);
+
+        // A long run has to squeeze into a short terminal
+        let game = Game::from_position(
+            Rules::klondike(),
+            "Kd|KsQhJsTh9s8h7s6h5s4h3s2hAs/-/-/-/-/-/- KhJh9h7h5h3hAhQsTs8s6s4s2s - Kc/Qd/-/- - A 0",
+        )
+        .unwrap();
+        mock_snapshot("long_run", 60, 16, &game);
     }
 
     #[test]
     fn test_canvas_style() {
+        let theme = Theme::classic();
         assert_eq!(
-            canvas_style(Location::Stock, Location::Stock, Some(Location::Stock)),
+            canvas_style(
+                Location::Stock,
+                Location::Stock,
+                Some(Location::Stock),
+                &[],
+                &theme
+            ),
             Style::default().fg(Color::Green)
         );
         assert_eq!(
-            canvas_style(Location::Stock, Location::Stock, None),
+            canvas_style(Location::Stock, Location::Stock, None, &[], &theme),
             Style::default().fg(Color::Blue)
         );
         assert_eq!(
             canvas_style(
                 Location::Tableau(1),
                 Location::Stock,
-                Some(Location::Tableau(1))
+                Some(Location::Tableau(1)),
+                &[],
+                &theme
             ),
             Style::default().fg(Color::Red)
         );
@@ -329,24 +1169,84 @@ mod tests {
             canvas_style(
                 Location::Stock,
                 Location::Foundation(0),
-                Some(Location::Tableau(1))
+                Some(Location::Tableau(1)),
+                &[],
+                &theme
             ),
             Style::default().fg(Color::White)
         );
+        assert_eq!(
+            canvas_style(
+                Location::Foundation(0),
+                Location::Stock,
+                Some(Location::Tableau(1)),
+                &[Location::Foundation(0)],
+                &theme
+            ),
+            Style::default().fg(Color::LightMagenta)
+        );
     }
 
     #[test]
     fn test_card_text_style() {
         let card1 = Card::new(Suit::Clubs, 1);
         let card2 = Card::new(Suit::Hearts, 2);
+        let theme = Theme::classic();
 
         assert_eq!(
-            card_text_style(Some(card1)),
+            card_text_style(Some(card1), &theme),
             Style::default().fg(Color::LightGreen)
         );
         assert_eq!(
-            card_text_style(Some(card2)),
+            card_text_style(Some(card2), &theme),
             Style::default().fg(Color::LightRed)
         );
+
+        // Four-color decks tell diamonds from hearts, monochrome makes up for its grays with bold
+        let card3 = Card::new(Suit::Diamonds, 2);
+        assert_eq!(
+            card_text_style(Some(card3), &Theme::four_color()),
+            Style::default().fg(Color::LightBlue)
+        );
+        assert_eq!(
+            canvas_style(
+                Location::Stock,
+                Location::Stock,
+                None,
+                &[],
+                &Theme::monochrome()
+            ),
+            Style::default()
+                .fg(Color::Gray)
+                .add_modifier(Modifier::BOLD)
+        );
+
+        // Without colors the active pile is bold, and piles it could go to and red suits are underlined
+        let theme = Theme::no_color();
+        assert_eq!(
+            canvas_style(
+                Location::Waste,
+                Location::Stock,
+                Some(Location::Waste),
+                &[],
+                &theme
+            ),
+            Style::default().add_modifier(Modifier::BOLD)
+        );
+        assert_eq!(
+            canvas_style(
+                Location::Tableau(0),
+                Location::Stock,
+                Some(Location::Waste),
+                &[Location::Tableau(0)],
+                &theme
+            ),
+            Style::default().add_modifier(Modifier::UNDERLINED)
+        );
+        assert_eq!(
+            card_text_style(Some(card3), &theme),
+            Style::default().add_modifier(Modifier::UNDERLINED)
+        );
+        assert_eq!(card_text_style(Some(card1), &theme), Style::default());
     }
 }
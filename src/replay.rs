@@ -0,0 +1,307 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::{ErrorKind, Write},
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{game::Game, moves::Move, rules::Rules, storage::data_path};
+
+const MAX_DELAY_MS: u64 = 3000;
+const SPEEDS: [f64; 6] = [0.25, 0.5, 1.0, 2.0, 4.0, 8.0];
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub struct TimedMove {
+    #[serde(rename = "t")]
+    pub millis: u64,
+    #[serde(rename = "m")]
+    pub mv: Move,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Replay {
+    pub version: u32,
+    pub seed: u64,
+    pub rules: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub position: Option<String>,
+    pub moves: Vec<TimedMove>,
+}
+
+impl Replay {
+    pub fn new(seed: u64, rules: &Rules) -> Self {
+        Self {
+            version: 1,
+            seed,
+            rules: rules.spec(),
+            position: None,
+            moves: vec![],
+        }
+    }
+
+    pub fn push(&mut self, millis: u64, mv: Move) {
+        self.moves.push(TimedMove { millis, mv });
+    }
+
+    pub fn load(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let replay: Self = serde_json::from_str(&fs::read_to_string(path)?)?;
+        replay.start()?;
+        Ok(replay)
+    }
+
+    pub fn save(&self) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        let started = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        self.save_in(&data_path("replays"), started)
+    }
+
+    // Two games can end within the same second, so a taken name gets a number instead of being overwritten
+    fn save_in(&self, dir: &Path, started: u64) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        fs::create_dir_all(dir)?;
+        let json = serde_json::to_string(self)?;
+
+        for attempt in 1.. {
+            let name = match attempt {
+                1 => format!("{}-{}.json", started, self.seed),
+                _ => format!("{}-{}-{}.json", started, self.seed, attempt),
+            };
+            let path = dir.join(name);
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    file.write_all(json.as_bytes())?;
+                    return Ok(path);
+                }
+                Err(err) if err.kind() == ErrorKind::AlreadyExists => continue,
+                Err(err) => return Err(err.into()),
+            }
+        }
+        unreachable!()
+    }
+
+    pub fn game_rules(&self) -> Result<Rules, String> {
+        Rules::from_spec(&self.rules)
+    }
+
+    pub fn start(&self) -> Result<Game, String> {
+        let rules = self.game_rules()?;
+        match &self.position {
+            Some(position) => Game::from_position(rules, position),
+            None => Ok(Game::new(rules, self.seed)),
+        }
+    }
+
+    // Deals are deterministic, so any position is the start plus the first moves
+    pub fn game_at(&self, position: usize) -> Game {
+        let mut game = self
+            .start()
+            .unwrap_or_else(|_| Game::new(Rules::default(), self.seed));
+        for timed in self.moves.iter().take(position) {
+            game.apply(timed.mv);
+        }
+        game
+    }
+}
+
+pub struct Playback {
+    pub replay: Replay,
+    pub position: usize,
+    pub playing: bool,
+    speed_index: usize,
+    last_step: Instant,
+}
+
+impl Playback {
+    pub fn new(replay: Replay) -> Self {
+        Self {
+            replay,
+            position: 0,
+            playing: false,
+            speed_index: 2,
+            last_step: Instant::now(),
+        }
+    }
+
+    pub fn speed(&self) -> f64 {
+        SPEEDS[self.speed_index]
+    }
+
+    pub fn toggle(&mut self) {
+        self.playing = !self.playing;
+        self.last_step = Instant::now();
+    }
+
+    pub fn faster(&mut self) {
+        self.speed_index = (self.speed_index + 1).min(SPEEDS.len() - 1);
+    }
+
+    pub fn slower(&mut self) {
+        self.speed_index = self.speed_index.saturating_sub(1);
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.position >= self.replay.moves.len()
+    }
+
+    pub fn step_forward(&mut self, game: &mut Game) {
+        if let Some(timed) = self.replay.moves.get(self.position) {
+            game.apply(timed.mv);
+            self.position += 1;
+        }
+        self.last_step = Instant::now();
+    }
+
+    pub fn step_back(&mut self, game: &mut Game) {
+        if self.position > 0 {
+            self.position -= 1;
+            *game = self.replay.game_at(self.position);
+        }
+        self.last_step = Instant::now();
+    }
+
+    fn next_delay(&self) -> Duration {
+        let previous = match self.position {
+            0 => 0,
+            position => self.replay.moves[position - 1].millis,
+        };
+        let delay = match self.replay.moves.get(self.position) {
+            Some(next) => next.millis.saturating_sub(previous).min(MAX_DELAY_MS),
+            None => 0,
+        };
+
+        Duration::from_millis((delay as f64 / self.speed()) as u64)
+    }
+
+    pub fn on_tick(&mut self, game: &mut Game) {
+        if !self.playing {
+            return;
+        }
+
+        if self.is_finished() {
+            self.playing = false;
+            return;
+        }
+
+        if self.last_step.elapsed() >= self.next_delay() {
+            self.step_forward(game);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mock_replay() -> Replay {
+        let mut replay = Replay::new(11, &Rules::klondike_by_threes());
+        replay.push(100, Move::Draw);
+        replay.push(900, Move::Draw);
+        replay.push(4000, Move::Draw);
+        replay
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let replay = mock_replay();
+        let dir = std::env::temp_dir().join(format!("tersoli-replay-{}", std::process::id()));
+
+        let path = replay.save_in(&dir, 100).unwrap();
+        assert!(path.ends_with(format!("100-{}.json", replay.seed)));
+        let loaded = Replay::load(&path).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(loaded, replay);
+        assert_eq!(loaded.game_rules(), Ok(Rules::klondike_by_threes()));
+    }
+
+    #[test]
+    fn test_save_keeps_earlier_replay() {
+        let dir = std::env::temp_dir().join(format!("tersoli-replays-{}", std::process::id()));
+        let first = mock_replay();
+        let mut second = mock_replay();
+        second.push(50, Move::Draw);
+
+        let first_path = first.save_in(&dir, 100).unwrap();
+        let second_path = second.save_in(&dir, 100).unwrap();
+
+        assert_ne!(first_path, second_path);
+        assert_eq!(Replay::load(&first_path).unwrap(), first);
+        assert_eq!(Replay::load(&second_path).unwrap(), second);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_moves_saved_as_notation() {
+        let mut replay = Replay::new(1, &Rules::klondike());
+        replay.push(5, Move::TableauToTableau(1, 5, 3));
+
+        let json = serde_json::to_string(&replay.moves).unwrap();
+        assert_eq!(json, r#"[{"t":5,"m":"T2:3>T6"}]"#);
+    }
+
+    #[test]
+    fn test_game_at_from_position() {
+        let mut game = Game::new(Rules::klondike_by_threes(), 5);
+        game.apply(Move::Draw);
+
+        let mut replay = Replay::new(0, &Rules::klondike_by_threes());
+        replay.position = Some(game.position());
+        replay.push(100, Move::Draw);
+
+        assert_eq!(replay.game_at(0).position(), game.position());
+        assert_eq!(replay.game_at(1).waste.cards.len(), 6);
+    }
+
+    #[test]
+    fn test_game_at() {
+        let replay = mock_replay();
+
+        assert_eq!(replay.game_at(0).waste.cards.len(), 0);
+        assert_eq!(replay.game_at(2).waste.cards.len(), 6);
+    }
+
+    #[test]
+    fn test_playback_steps() {
+        let replay = mock_replay();
+        let mut game = replay.game_at(0);
+        let mut playback = Playback::new(replay);
+
+        playback.step_forward(&mut game);
+        playback.step_forward(&mut game);
+        assert_eq!(playback.position, 2);
+        assert_eq!(game.waste.cards.len(), 6);
+
+        playback.step_back(&mut game);
+        assert_eq!(playback.position, 1);
+        assert_eq!(game.waste.cards.len(), 3);
+
+        playback.step_back(&mut game);
+        playback.step_back(&mut game);
+        assert_eq!(playback.position, 0);
+    }
+
+    #[test]
+    fn test_playback_delay_and_speed() {
+        let mut playback = Playback::new(mock_replay());
+        assert_eq!(playback.next_delay(), Duration::from_millis(100));
+
+        playback.position = 2;
+        assert_eq!(playback.next_delay(), Duration::from_millis(MAX_DELAY_MS));
+
+        playback.faster();
+        assert_eq!(playback.speed(), 2.0);
+        assert_eq!(
+            playback.next_delay(),
+            Duration::from_millis(MAX_DELAY_MS / 2)
+        );
+
+        for _ in 0..10 {
+            playback.slower();
+        }
+        assert_eq!(playback.speed(), 0.25);
+    }
+}
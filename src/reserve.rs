@@ -0,0 +1,61 @@
+use crate::{card::Card, stock::Stock};
+
+#[derive(Clone)]
+pub struct Reserve {
+    pub cards: Vec<Card>,
+}
+
+impl Reserve {
+    pub fn new() -> Self {
+        Self { cards: vec![] }
+    }
+
+    pub fn initialize(&mut self, stock: &mut Stock, size: usize) {
+        self.cards.clear();
+        for _ in 0..size {
+            self.cards.push(stock.deal());
+        }
+    }
+
+    pub fn get_top_card(&self) -> Option<Card> {
+        self.cards.last().copied()
+    }
+
+    pub fn remove(&mut self) {
+        self.cards.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_initialize() {
+        let mut stock = Stock::new(1, rand::random());
+        let mut reserve = Reserve::new();
+
+        reserve.initialize(&mut stock, 13);
+
+        assert_eq!(reserve.cards.len(), 13);
+        assert_eq!(stock.cards.len(), 52 - 13);
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut stock = Stock::new(1, rand::random());
+        let mut reserve = Reserve::new();
+        reserve.initialize(&mut stock, 2);
+
+        let second = reserve.cards[0];
+        reserve.remove();
+
+        match reserve.get_top_card() {
+            Some(card) => {
+                assert_eq!(card.rank, second.rank);
+                assert_eq!(card.suit, second.suit);
+            }
+            _ => panic!("Couldn't get top card"),
+        }
+    }
+}
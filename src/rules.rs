@@ -0,0 +1,412 @@
+use std::fmt;
+
+use crate::i18n::{self, fill};
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Variant {
+    Klondike,
+    Canfield,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum EmptyColumn {
+    Rank(u8),
+    Any,
+    None,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum FoundationBase {
+    Rank(u8),
+    Random,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Rules {
+    pub variant: Variant,
+    pub draw_count: usize,
+    pub pass_limit: Option<u32>,
+    pub empty_column: EmptyColumn,
+    pub foundation_base: FoundationBase,
+    pub tableau_columns: usize,
+    pub reserve_size: usize,
+    pub wrap_around: bool,
+    pub decks: usize,
+}
+
+pub const PRESETS: [&str; 4] = ["klondike", "klondike3", "canfield", "double"];
+
+impl Rules {
+    pub fn klondike() -> Self {
+        Self {
+            variant: Variant::Klondike,
+            draw_count: 1,
+            pass_limit: None,
+            empty_column: EmptyColumn::Rank(13),
+            foundation_base: FoundationBase::Rank(1),
+            tableau_columns: 7,
+            reserve_size: 0,
+            wrap_around: false,
+            decks: 1,
+        }
+    }
+
+    pub fn klondike_by_threes() -> Self {
+        Self {
+            draw_count: 3,
+            ..Self::klondike()
+        }
+    }
+
+    pub fn canfield() -> Self {
+        Self {
+            variant: Variant::Canfield,
+            draw_count: 3,
+            pass_limit: None,
+            empty_column: EmptyColumn::Any,
+            foundation_base: FoundationBase::Random,
+            tableau_columns: 4,
+            reserve_size: 13,
+            wrap_around: true,
+            decks: 1,
+        }
+    }
+
+    pub fn double_klondike() -> Self {
+        Self {
+            tableau_columns: 9,
+            decks: 2,
+            ..Self::klondike()
+        }
+    }
+
+    pub fn preset(name: &str) -> Option<Self> {
+        match name {
+            "klondike" | "draw1" => Some(Self::klondike()),
+            "klondike3" | "draw3" => Some(Self::klondike_by_threes()),
+            "canfield" => Some(Self::canfield()),
+            "double" => Some(Self::double_klondike()),
+            _ => None,
+        }
+    }
+
+    // Spec is a comma separated list, e.g. "canfield,passes=3" or "draw3,empty=any"
+    pub fn from_spec(spec: &str) -> Result<Self, String> {
+        let mut parts = spec
+            .split(',')
+            .map(str::trim)
+            .filter(|part| !part.is_empty());
+
+        let mut rules = Self::klondike();
+        let mut pending = None;
+
+        if let Some(first) = parts.next() {
+            match Self::preset(first) {
+                Some(preset) => rules = preset,
+                None => pending = Some(first),
+            }
+        }
+
+        for part in pending.into_iter().chain(parts) {
+            rules.apply_option(part)?;
+        }
+
+        if rules.dealt_cards() > 52 * rules.decks {
+            return Err(format!(
+                "'{}' deals {} cards, but the deck only has {}",
+                spec,
+                rules.dealt_cards(),
+                52 * rules.decks
+            ));
+        }
+
+        Ok(rules)
+    }
+
+    // Cards laid out before play starts: the reserve, the tableau and a random foundation base
+    pub fn dealt_cards(&self) -> usize {
+        let columns = self.tableau_columns;
+        let tableau = match self.variant {
+            Variant::Klondike => columns * (columns + 1) / 2,
+            Variant::Canfield => columns,
+        };
+        let base = (self.foundation_base == FoundationBase::Random) as usize;
+        self.reserve_size + tableau + base
+    }
+
+    fn apply_option(&mut self, option: &str) -> Result<(), String> {
+        let (key, value) = option.split_once('=').unwrap_or((option, ""));
+
+        match (key, value) {
+            ("draw1", "") => self.draw_count = 1,
+            ("draw3", "") => self.draw_count = 3,
+            ("draw", count) => self.draw_count = parse_number(option, count, 1, 52)?,
+            ("passes", "unlimited") => self.pass_limit = None,
+            ("passes", count) => self.pass_limit = Some(parse_number(option, count, 1, 999)?),
+            ("empty", "kings") => self.empty_column = EmptyColumn::Rank(13),
+            ("empty", "any") => self.empty_column = EmptyColumn::Any,
+            ("empty", "none") => self.empty_column = EmptyColumn::None,
+            ("empty", rank) => self.empty_column = EmptyColumn::Rank(parse_rank(option, rank)?),
+            ("base", "random") => self.foundation_base = FoundationBase::Random,
+            ("base", rank) => {
+                self.foundation_base = FoundationBase::Rank(parse_rank(option, rank)?)
+            }
+            ("columns", count) => self.tableau_columns = parse_number(option, count, 1, 10)?,
+            ("reserve", count) => self.reserve_size = parse_number(option, count, 0, 20)?,
+            ("decks", count) => self.decks = parse_number(option, count, 1, 2)?,
+            ("wrap", "") => self.wrap_around = true,
+            ("nowrap", "") => self.wrap_around = false,
+            _ => {
+                return Err(format!(
+                    "Unknown rule option '{}' (presets: {})",
+                    option,
+                    PRESETS.join(", ")
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn spec(&self) -> String {
+        let mut parts = vec![match self.variant {
+            Variant::Klondike => "klondike".to_string(),
+            Variant::Canfield => "canfield".to_string(),
+        }];
+
+        parts.push(format!("draw={}", self.draw_count));
+        parts.push(match self.pass_limit {
+            Some(limit) => format!("passes={}", limit),
+            None => "passes=unlimited".to_string(),
+        });
+        parts.push(match self.empty_column {
+            EmptyColumn::Rank(13) => "empty=kings".to_string(),
+            EmptyColumn::Rank(rank) => format!("empty={}", rank_name(rank)),
+            EmptyColumn::Any => "empty=any".to_string(),
+            EmptyColumn::None => "empty=none".to_string(),
+        });
+        parts.push(match self.foundation_base {
+            FoundationBase::Rank(rank) => format!("base={}", rank_name(rank)),
+            FoundationBase::Random => "base=random".to_string(),
+        });
+        parts.push(format!("columns={}", self.tableau_columns));
+        parts.push(format!("reserve={}", self.reserve_size));
+        parts.push(format!("decks={}", self.decks));
+        parts.push(if self.wrap_around { "wrap" } else { "nowrap" }.to_string());
+
+        parts.join(",")
+    }
+
+    pub fn next_rank(&self, rank: u8) -> Option<u8> {
+        match rank {
+            13 if self.wrap_around => Some(1),
+            13 => None,
+            _ => Some(rank + 1),
+        }
+    }
+
+    pub fn previous_rank(&self, rank: u8) -> Option<u8> {
+        match rank {
+            1 if self.wrap_around => Some(13),
+            1 => None,
+            _ => Some(rank - 1),
+        }
+    }
+
+    pub fn can_fill_empty_column(&self, rank: u8) -> bool {
+        match self.empty_column {
+            EmptyColumn::Rank(allowed) => rank == allowed,
+            EmptyColumn::Any => true,
+            EmptyColumn::None => false,
+        }
+    }
+
+    // Plain sentences for the help overlay
+    pub fn describe(&self) -> Vec<String> {
+        let text = i18n::text();
+        let rank_name = |rank: u8| text.rank_names[rank as usize - 1];
+        let name = match self.variant {
+            Variant::Klondike => "Klondike",
+            Variant::Canfield => "Canfield",
+        };
+        let mut lines = vec![match self.decks {
+            1 => fill(text.rules_columns, &[&name, &self.tableau_columns]),
+            decks => fill(text.rules_decks, &[&name, &decks, &self.tableau_columns]),
+        }];
+
+        lines.push(match self.foundation_base {
+            FoundationBase::Rank(rank) => fill(text.rules_foundation_rank, &[&rank_name(rank)]),
+            FoundationBase::Random => text.rules_foundation_random.to_string(),
+        });
+        lines.push(text.rules_columns_down.to_string());
+        if self.wrap_around {
+            lines.push(text.rules_wrap_around.to_string());
+        }
+        lines.push(match self.empty_column {
+            EmptyColumn::Rank(rank) => fill(text.rules_empty_rank, &[&rank_name(rank)]),
+            EmptyColumn::Any => text.rules_empty_any.to_string(),
+            EmptyColumn::None => text.rules_empty_none.to_string(),
+        });
+        if self.reserve_size > 0 {
+            lines.push(fill(text.rules_reserve, &[&self.reserve_size]));
+        }
+        let draw = match self.draw_count {
+            1 => text.rules_draw_one.to_string(),
+            count => fill(text.rules_draw, &[&count]),
+        };
+        let passes = match self.pass_limit {
+            Some(limit) => fill(text.rules_passes, &[&limit]),
+            None => text.rules_passes_unlimited.to_string(),
+        };
+        lines.push(format!("{}, {}", draw, passes));
+        lines
+    }
+
+    pub fn can_recycle(&self, recycles: u32) -> bool {
+        match self.pass_limit {
+            Some(limit) => recycles + 1 < limit,
+            None => true,
+        }
+    }
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self::klondike()
+    }
+}
+
+impl fmt::Display for Rules {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.spec())
+    }
+}
+
+fn parse_number<T: std::str::FromStr + PartialOrd>(
+    option: &str,
+    value: &str,
+    min: T,
+    max: T,
+) -> Result<T, String> {
+    match value.parse::<T>() {
+        Ok(number) if number >= min && number <= max => Ok(number),
+        _ => Err(format!("Invalid value in rule option '{}'", option)),
+    }
+}
+
+fn parse_rank(option: &str, value: &str) -> Result<u8, String> {
+    match value {
+        "ace" | "a" | "A" => Ok(1),
+        "jack" | "j" | "J" => Ok(11),
+        "queen" | "q" | "Q" => Ok(12),
+        "king" | "k" | "K" => Ok(13),
+        _ => parse_number(option, value, 1, 13),
+    }
+}
+
+fn rank_name(rank: u8) -> String {
+    match rank {
+        1 => "ace".to_string(),
+        11 => "jack".to_string(),
+        12 => "queen".to_string(),
+        13 => "king".to_string(),
+        _ => rank.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_presets() {
+        assert_eq!(Rules::preset("klondike"), Some(Rules::klondike()));
+        assert_eq!(Rules::preset("klondike3").unwrap().draw_count, 3);
+        assert!(Rules::preset("spider").is_none());
+
+        let canfield = Rules::canfield();
+        assert_eq!(canfield.reserve_size, 13);
+        assert_eq!(canfield.foundation_base, FoundationBase::Random);
+        assert!(canfield.wrap_around);
+
+        let double = Rules::preset("double").unwrap();
+        assert_eq!(double.decks, 2);
+        assert_eq!(double.tableau_columns, 9);
+    }
+
+    #[test]
+    fn test_from_spec() {
+        let rules = Rules::from_spec("draw3,passes=3").unwrap();
+        assert_eq!(rules.variant, Variant::Klondike);
+        assert_eq!(rules.draw_count, 3);
+        assert_eq!(rules.pass_limit, Some(3));
+
+        let rules = Rules::from_spec("canfield,draw1,empty=kings").unwrap();
+        assert_eq!(rules.variant, Variant::Canfield);
+        assert_eq!(rules.draw_count, 1);
+        assert_eq!(rules.empty_column, EmptyColumn::Rank(13));
+
+        assert!(Rules::from_spec("draw=0").is_err());
+        assert!(Rules::from_spec("klondike,jokers").is_err());
+
+        // A single deck runs out of cards for ten Klondike columns, but two decks have enough
+        assert!(Rules::from_spec("columns=10").is_err());
+        assert!(Rules::from_spec("reserve=20,columns=9").is_err());
+        assert!(Rules::from_spec("columns=10,decks=2").is_ok());
+        assert_eq!(Rules::from_spec("columns=9").unwrap().dealt_cards(), 45);
+    }
+
+    #[test]
+    fn test_describe() {
+        let lines = Rules::klondike().describe();
+        assert_eq!(lines[0], "Klondike with 7 tableau columns");
+        assert_eq!(
+            lines[1],
+            "Build foundations up by suit, starting with the ace"
+        );
+        assert_eq!(lines[3], "Only a king can fill an empty column");
+
+        let lines = Rules::canfield().describe();
+        assert!(lines.contains(&"Any card can fill an empty column".to_string()));
+        assert!(
+            lines
+                .iter()
+                .any(|line| line.starts_with("Ranks wrap around"))
+        );
+        assert_eq!(
+            lines.last().unwrap(),
+            "Draw 3 cards at a time, as many passes through the stock as you like"
+        );
+    }
+
+    #[test]
+    fn test_spec_round_trip() {
+        for preset in PRESETS {
+            let rules = Rules::preset(preset).unwrap();
+            assert_eq!(Rules::from_spec(&rules.spec()), Ok(rules));
+        }
+    }
+
+    #[test]
+    fn test_next_and_previous_rank() {
+        let klondike = Rules::klondike();
+        assert_eq!(klondike.next_rank(12), Some(13));
+        assert_eq!(klondike.next_rank(13), None);
+        assert_eq!(klondike.previous_rank(1), None);
+
+        let canfield = Rules::canfield();
+        assert_eq!(canfield.next_rank(13), Some(1));
+        assert_eq!(canfield.previous_rank(1), Some(13));
+    }
+
+    #[test]
+    fn test_can_recycle() {
+        let mut rules = Rules::klondike();
+        assert!(rules.can_recycle(100));
+
+        rules.pass_limit = Some(3);
+        assert!(rules.can_recycle(0));
+        assert!(rules.can_recycle(1));
+        assert!(!rules.can_recycle(2));
+    }
+}
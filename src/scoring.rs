@@ -0,0 +1,128 @@
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{game::Game, moves::Move, replay::Replay};
+
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Eq, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Scoring {
+    #[default]
+    Standard,
+    Vegas,
+}
+
+impl fmt::Display for Scoring {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Scoring::Standard => write!(f, "standard"),
+            Scoring::Vegas => write!(f, "vegas"),
+        }
+    }
+}
+
+impl FromStr for Scoring {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text {
+            "standard" => Ok(Scoring::Standard),
+            "vegas" => Ok(Scoring::Vegas),
+            _ => Err(format!(
+                "Unknown scoring '{}', expected standard or vegas",
+                text
+            )),
+        }
+    }
+}
+
+fn foundation_cards(game: &Game) -> i32 {
+    game.foundation.cards.iter().flatten().flatten().count() as i32
+}
+
+// Standard follows the classic Windows points, Vegas pays 5 per foundation card after buying the deck
+pub fn score(scoring: Scoring, replay: &Replay) -> i32 {
+    let mut game = replay.game_at(0);
+
+    if scoring == Scoring::Vegas {
+        for timed in replay.moves.iter() {
+            game.apply(timed.mv);
+        }
+        return 5 * foundation_cards(&game) - 52 * game.rules.decks as i32;
+    }
+
+    let mut score: i32 = 0;
+    for timed in replay.moves.iter() {
+        let hidden = game.tableau.hidden_count();
+        let recycles = game.stock.recycles;
+        if !game.apply(timed.mv) {
+            continue;
+        }
+
+        score += match timed.mv {
+            Move::WasteToTableau(_) | Move::ReserveToTableau(_) => 5,
+            Move::WasteToFoundation(_)
+            | Move::ReserveToFoundation(_)
+            | Move::TableauToFoundation(_, _) => 10,
+            Move::FoundationToTableau(_, _) => -15,
+            Move::Draw | Move::TableauToTableau(_, _, _) => 0,
+        };
+        if game.tableau.hidden_count() < hidden {
+            score += 5;
+        }
+        if game.stock.recycles > recycles && game.rules.draw_count == 1 {
+            score -= 100;
+        }
+        score = score.max(0);
+    }
+
+    score
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::Rules;
+
+    #[test]
+    fn test_parse_scoring() {
+        assert_eq!("vegas".parse(), Ok(Scoring::Vegas));
+        assert_eq!(Scoring::Standard.to_string(), "standard");
+        assert!("golf".parse::<Scoring>().is_err());
+    }
+
+    #[test]
+    fn test_standard_score() {
+        let rules = Rules::klondike();
+        let mut replay = Replay::new(0, &rules);
+        replay.position = Some(
+            "|Ah/2s|2h/|3s/-/-/-/- \
+            3h4h5h6h7h8h9hThJhQhKhAc2c3c4c5c6c7c8c9cTcJcQcKcAd2d3d4d5d6d7d8d9dTdJdQdKd \
+            4s5s6s7s8s9sTsJsQsKsAs -/-/-/- - A 0"
+                .to_string(),
+        );
+
+        // Waste to foundation, tableau to foundation, then the 2s flips over
+        replay.push(0, Move::WasteToFoundation(0));
+        replay.push(0, Move::TableauToFoundation(0, 1));
+        replay.push(0, Move::TableauToFoundation(1, 1));
+        assert_eq!(score(Scoring::Standard, &replay), 10 + 10 + 10 + 5);
+
+        replay.push(0, Move::FoundationToTableau(1, 2));
+        assert_eq!(score(Scoring::Standard, &replay), 35 - 15);
+
+        // Illegal moves don't count
+        replay.push(0, Move::WasteToFoundation(3));
+        assert_eq!(score(Scoring::Standard, &replay), 20);
+    }
+
+    #[test]
+    fn test_vegas_score() {
+        let rules = Rules::klondike();
+        let mut replay = Replay::new(0, &rules);
+        replay.position = Some("-/-/-/-/-/-/- - - Ks/Kh/Kc/Kd - A 0".to_string());
+
+        assert_eq!(score(Scoring::Vegas, &replay), 5 * 52 - 52);
+        assert_eq!(score(Scoring::Vegas, &Replay::new(1, &rules)), -52);
+    }
+}
@@ -0,0 +1,267 @@
+use std::{
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{SocketAddr, TcpListener, ToSocketAddrs},
+    sync::{
+        Arc, Mutex,
+        mpsc::{self, Receiver, Sender, SyncSender},
+    },
+    thread,
+};
+
+#[cfg(unix)]
+use std::os::unix::{
+    fs::FileTypeExt,
+    net::{UnixListener, UnixStream},
+};
+
+// Lines waiting for a client before it counts as stuck and is dropped
+const QUEUE_LENGTH: usize = 256;
+
+// Each client has a thread writing its lines, so a slow client never holds up the game
+type Clients = Arc<Mutex<Vec<(usize, SyncSender<String>)>>>;
+
+// Accepts clients on background threads, the app polls their lines and answers from its own loop
+pub struct Server {
+    pub address: String,
+    incoming: Receiver<(usize, String)>,
+    clients: Clients,
+}
+
+impl Server {
+    // A path is a Unix domain socket, a bare number is a localhost port, anything else a TCP
+    // address on the loopback interface
+    pub fn bind(address: &str) -> io::Result<Self> {
+        let (sender, incoming) = mpsc::channel();
+        let clients: Clients = Arc::new(Mutex::new(vec![]));
+
+        let address = if address.contains('/') {
+            bind_unix(address, sender, clients.clone())?
+        } else {
+            let address = match address.parse::<u16>() {
+                Ok(port) => format!("127.0.0.1:{}", port),
+                Err(_) => address.to_string(),
+            };
+            bind_tcp(&address, sender, clients.clone())?
+        };
+
+        Ok(Self {
+            address,
+            incoming,
+            clients,
+        })
+    }
+
+    // Lines from clients that have been dropped in the meantime are left out
+    pub fn poll(&self) -> Vec<(usize, String)> {
+        let clients = self.clients.lock().unwrap();
+        self.incoming
+            .try_iter()
+            .filter(|(id, _)| clients.iter().any(|(client, _)| client == id))
+            .collect()
+    }
+
+    pub fn client_count(&self) -> usize {
+        self.clients.lock().unwrap().len()
+    }
+
+    pub fn send(&self, id: usize, line: &str) {
+        let mut clients = self.clients.lock().unwrap();
+        clients.retain(|(client, queue)| *client != id || queue.try_send(line.to_string()).is_ok());
+    }
+
+    pub fn broadcast(&self, line: &str) {
+        let mut clients = self.clients.lock().unwrap();
+        clients.retain(|(_, queue)| queue.try_send(line.to_string()).is_ok());
+    }
+}
+
+fn bind_tcp(
+    address: &str,
+    sender: Sender<(usize, String)>,
+    clients: Clients,
+) -> io::Result<String> {
+    let addresses: Vec<SocketAddr> = address.to_socket_addrs()?.collect();
+    // Clients can play moves, so they have to be on this machine
+    if addresses.iter().any(|address| !address.ip().is_loopback()) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "Only loopback addresses can be listened on, not {}",
+                address
+            ),
+        ));
+    }
+
+    let listener = TcpListener::bind(&addresses[..])?;
+    let address = listener.local_addr()?.to_string();
+
+    thread::spawn(move || {
+        for (id, stream) in listener.incoming().flatten().enumerate() {
+            let writer = match stream.try_clone() {
+                Ok(writer) => writer,
+                Err(_) => continue,
+            };
+            add_client(id, stream, Box::new(writer), &sender, &clients);
+        }
+    });
+
+    Ok(address)
+}
+
+#[cfg(unix)]
+fn bind_unix(path: &str, sender: Sender<(usize, String)>, clients: Clients) -> io::Result<String> {
+    let listener = match UnixListener::bind(path) {
+        Ok(listener) => listener,
+        // A socket nobody answers on is left over from a previous run, anything else at the
+        // path is left alone
+        Err(err)
+            if err.kind() == io::ErrorKind::AddrInUse
+                && std::fs::symlink_metadata(path)?.file_type().is_socket()
+                && UnixStream::connect(path).is_err() =>
+        {
+            std::fs::remove_file(path)?;
+            UnixListener::bind(path)?
+        }
+        Err(err) => return Err(err),
+    };
+
+    thread::spawn(move || {
+        for (id, stream) in listener.incoming().flatten().enumerate() {
+            let writer = match stream.try_clone() {
+                Ok(writer) => writer,
+                Err(_) => continue,
+            };
+            add_client(id, stream, Box::new(writer), &sender, &clients);
+        }
+    });
+
+    Ok(path.to_string())
+}
+
+#[cfg(not(unix))]
+fn bind_unix(_: &str, _: Sender<(usize, String)>, _: Clients) -> io::Result<String> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "Unix domain sockets aren't supported on this platform",
+    ))
+}
+
+fn add_client<S: Read + Send + 'static>(
+    id: usize,
+    reader: S,
+    mut writer: Box<dyn Write + Send>,
+    sender: &Sender<(usize, String)>,
+    clients: &Clients,
+) {
+    let (queue, lines) = mpsc::sync_channel::<String>(QUEUE_LENGTH);
+    clients.lock().unwrap().push((id, queue));
+
+    // Ends once the client is dropped and its queue is empty, or writing to it fails
+    thread::spawn(move || {
+        for line in lines {
+            if writeln!(writer, "{}", line).is_err() {
+                break;
+            }
+        }
+    });
+
+    let sender = sender.clone();
+    let clients = clients.clone();
+    thread::spawn(move || {
+        for line in BufReader::new(reader).lines().map_while(Result::ok) {
+            if !line.trim().is_empty() && sender.send((id, line)).is_err() {
+                break;
+            }
+        }
+        clients.lock().unwrap().retain(|(client, _)| *client != id);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        net::TcpStream,
+        time::{Duration, Instant},
+    };
+
+    fn wait_for<T>(mut check: impl FnMut() -> Option<T>) -> T {
+        let started = Instant::now();
+        loop {
+            if let Some(value) = check() {
+                return value;
+            }
+            assert!(started.elapsed() < Duration::from_secs(5), "timed out");
+            thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    #[test]
+    fn test_tcp_clients() {
+        let server = Server::bind("127.0.0.1:0").unwrap();
+        let mut client = TcpStream::connect(&server.address).unwrap();
+        let mut reader = BufReader::new(client.try_clone().unwrap());
+
+        writeln!(client, "{{\"cmd\":\"state\"}}").unwrap();
+        let (id, line) = wait_for(|| server.poll().pop());
+        assert_eq!(line, "{\"cmd\":\"state\"}");
+
+        server.send(id, "reply");
+        server.broadcast("changed");
+
+        let mut received = String::new();
+        reader.read_line(&mut received).unwrap();
+        reader.read_line(&mut received).unwrap();
+        assert_eq!(received, "reply\nchanged\n");
+
+        drop(reader);
+        drop(client);
+        wait_for(|| (server.client_count() == 0).then_some(()));
+    }
+
+    #[test]
+    fn test_loopback_only() {
+        assert!(Server::bind("0.0.0.0:0").is_err());
+        assert!(Server::bind("localhost:0").is_ok());
+    }
+
+    #[test]
+    fn test_stuck_client_is_dropped() {
+        let server = Server::bind("127.0.0.1:0").unwrap();
+        let _client = TcpStream::connect(&server.address).unwrap();
+        wait_for(|| (server.client_count() == 1).then_some(()));
+
+        // Nothing is read, so the socket buffers and then the queue fill up
+        let line = "x".repeat(64 * 1024);
+        wait_for(|| {
+            server.broadcast(&line);
+            (server.client_count() == 0).then_some(())
+        });
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_unix_socket() {
+        let path = std::env::temp_dir().join(format!("tersoli-{}.sock", std::process::id()));
+        let path = path.to_str().unwrap();
+
+        let server = Server::bind(path).unwrap();
+        let mut client = UnixStream::connect(path).unwrap();
+        writeln!(client, "hello").unwrap();
+
+        assert_eq!(wait_for(|| server.poll().pop()).1, "hello");
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_unix_socket_keeps_other_files() {
+        let path = std::env::temp_dir().join(format!("tersoli-{}.txt", std::process::id()));
+        std::fs::write(&path, "notes").unwrap();
+
+        let err = Server::bind(path.to_str().unwrap()).err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::AddrInUse);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "notes");
+        std::fs::remove_file(path).unwrap();
+    }
+}
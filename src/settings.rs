@@ -0,0 +1,170 @@
+use crate::{card_view::CARD_STYLES, config::Config, i18n, keymap::KeyPreset, scoring::Scoring};
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Setting {
+    DrawCount,
+    PassLimit,
+    Scoring,
+    AutoMove,
+    Animations,
+    Theme,
+    CardStyle,
+    KeyPreset,
+}
+
+pub const SETTINGS: [Setting; 8] = [
+    Setting::DrawCount,
+    Setting::PassLimit,
+    Setting::Scoring,
+    Setting::AutoMove,
+    Setting::Animations,
+    Setting::Theme,
+    Setting::CardStyle,
+    Setting::KeyPreset,
+];
+
+const DRAW_COUNTS: [usize; 3] = [1, 2, 3];
+const PASS_LIMITS: [u32; 4] = [0, 1, 2, 3];
+
+impl Setting {
+    pub fn label(&self) -> &'static str {
+        let index = SETTINGS
+            .iter()
+            .position(|setting| setting == self)
+            .unwrap_or_default();
+        i18n::text().settings_labels[index]
+    }
+
+    // Changing the rules or the scoring in the middle of a deal would make it a different game
+    pub fn next_deal(&self) -> bool {
+        matches!(
+            self,
+            Setting::DrawCount | Setting::PassLimit | Setting::Scoring
+        )
+    }
+}
+
+// Steps through a list of values, starting over at the first one for a value not in it
+fn cycle<T: Clone + PartialEq>(values: &[T], current: &T, step: isize) -> T {
+    let index = match values.iter().position(|value| value == current) {
+        Some(index) => (index as isize + step).rem_euclid(values.len() as isize) as usize,
+        None => 0,
+    };
+    values[index].clone()
+}
+
+// An edited copy of the config, the app applies and saves every change as it is made
+pub struct Settings {
+    pub selected: usize,
+    pub config: Config,
+    pub themes: Vec<String>,
+}
+
+impl Settings {
+    pub fn new(config: Config, themes: Vec<String>) -> Self {
+        Self {
+            selected: 0,
+            config,
+            themes,
+        }
+    }
+
+    pub fn setting(&self) -> Setting {
+        SETTINGS[self.selected]
+    }
+
+    pub fn up(&mut self) {
+        self.selected = (self.selected + SETTINGS.len() - 1) % SETTINGS.len();
+    }
+
+    pub fn down(&mut self) {
+        self.selected = (self.selected + 1) % SETTINGS.len();
+    }
+
+    pub fn change(&mut self, step: isize) {
+        let setting = self.setting();
+        let config = &mut self.config;
+        match setting {
+            Setting::DrawCount => config.draw_count = cycle(&DRAW_COUNTS, &config.draw_count, step),
+            Setting::PassLimit => config.pass_limit = cycle(&PASS_LIMITS, &config.pass_limit, step),
+            Setting::Scoring => {
+                config.scoring = cycle(&[Scoring::Standard, Scoring::Vegas], &config.scoring, step)
+            }
+            Setting::AutoMove => config.auto_move = !config.auto_move,
+            Setting::Animations => config.animations = !config.animations,
+            Setting::Theme => config.theme = cycle(&self.themes, &config.theme, step),
+            Setting::CardStyle => config.card_style = cycle(&CARD_STYLES, &config.card_style, step),
+            Setting::KeyPreset => {
+                config.key_preset = cycle(
+                    &[KeyPreset::Standard, KeyPreset::Vim],
+                    &config.key_preset,
+                    step,
+                )
+            }
+        }
+    }
+
+    pub fn value(&self, setting: Setting) -> String {
+        let text = i18n::text();
+        let on_off = |on: bool| if on { text.on } else { text.off }.to_string();
+        let config = &self.config;
+        match setting {
+            Setting::DrawCount => config.draw_count.to_string(),
+            Setting::PassLimit => match config.pass_limit {
+                0 => text.unlimited.to_string(),
+                passes => passes.to_string(),
+            },
+            Setting::Scoring => config.scoring.to_string(),
+            Setting::AutoMove => on_off(config.auto_move),
+            Setting::Animations => on_off(config.animations),
+            Setting::Theme => config.theme.clone(),
+            Setting::CardStyle => config.card_style.name().to_string(),
+            Setting::KeyPreset => config.key_preset.name().to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card_view::CardStyle;
+
+    fn mock_settings() -> Settings {
+        Settings::new(
+            Config::default(),
+            vec!["classic".to_string(), "solarized".to_string()],
+        )
+    }
+
+    #[test]
+    fn test_change() {
+        let mut settings = mock_settings();
+
+        settings.change(-1);
+        assert_eq!(settings.config.draw_count, 3);
+        settings.down();
+        settings.change(1);
+        assert_eq!(settings.value(Setting::PassLimit), "1");
+
+        settings.selected = 5;
+        settings.change(1);
+        assert_eq!(settings.config.theme, "solarized");
+        settings.change(1);
+        assert_eq!(settings.config.theme, "classic");
+
+        settings.down();
+        settings.change(-1);
+        assert_eq!(settings.config.card_style, CardStyle::Thick);
+    }
+
+    #[test]
+    fn test_navigate() {
+        let mut settings = mock_settings();
+
+        settings.up();
+        assert_eq!(settings.setting(), Setting::KeyPreset);
+        assert!(!settings.setting().next_deal());
+        settings.down();
+        assert!(settings.setting().next_deal());
+    }
+}
@@ -0,0 +1,238 @@
+use std::{collections::HashSet, time::Instant};
+
+use crate::{game::Game, location::Location, moves::Move, rules::FoundationBase, suit::Suit};
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum Outcome {
+    Solved(Vec<Move>),
+    // Every position reachable from the deal was searched
+    Unsolvable,
+    // Ran out of time first
+    Unknown,
+}
+
+// Positions are told apart by their whole notation, a hash could mistake a new position for one
+// already searched. Without a pass limit, going through the stock again doesn't change what can
+// still be done, so the pass count is left out
+fn key(game: &Game) -> String {
+    let mut position = game.position();
+    if game.rules.pass_limit.is_none()
+        && let Some(end) = position.rfind(' ')
+    {
+        position.truncate(end);
+    }
+    position
+}
+
+// Higher is tried first: cards going up, then moves that turn over a card, then the rest
+fn priority(game: &Game, mv: Move) -> u8 {
+    match mv {
+        Move::WasteToFoundation(_) | Move::ReserveToFoundation(_) => 5,
+        Move::TableauToFoundation(column, _) => {
+            4 + (game.tableau.cards[column].len() == game.tableau.cutoffs[column] as usize + 1)
+                as u8
+        }
+        Move::ReserveToTableau(_) => 4,
+        Move::TableauToTableau(column, _, count) => {
+            let reveals =
+                game.tableau.cards[column].len() - count == game.tableau.cutoffs[column] as usize;
+            if reveals { 4 } else { 1 }
+        }
+        Move::WasteToTableau(_) => 3,
+        Move::Draw => 2,
+        Move::FoundationToTableau(_, _) => 0,
+    }
+}
+
+fn is_red(suit: Suit) -> bool {
+    matches!(suit, Suit::Hearts | Suit::Diamonds)
+}
+
+// Nothing could need a card in the tableau once the cards of the other color a rank below it
+// are all up, so it can go up without trying anything else. Only holds when foundations are
+// built from aces without wrapping around
+fn is_safe(game: &Game, mv: Move) -> bool {
+    let rules = game.rules;
+    if rules.foundation_base != FoundationBase::Rank(1) || rules.wrap_around {
+        return false;
+    }
+    let card = match mv {
+        Move::WasteToFoundation(_)
+        | Move::ReserveToFoundation(_)
+        | Move::TableauToFoundation(..) => game.get_top_card(mv.source()),
+        _ => None,
+    };
+    let Some(card) = card else {
+        return false;
+    };
+
+    let built = |suit: Suit| {
+        (0..game.foundation.cards.len())
+            .filter_map(|slot| game.get_top_card(Location::Foundation(slot)))
+            .filter(|top| top.suit == suit && top.rank + 1 >= card.rank)
+            .count()
+    };
+    card.rank <= 2
+        || [Suit::Spades, Suit::Hearts, Suit::Clubs, Suit::Diamonds]
+            .into_iter()
+            .filter(|suit| is_red(*suit) != is_red(card.suit))
+            .all(|suit| built(suit) == rules.decks)
+}
+
+// Moving a whole column to an empty one leaves everything as it was
+fn is_pointless(game: &Game, mv: Move) -> bool {
+    match mv {
+        Move::TableauToTableau(column, target, count) => {
+            count == game.tableau.cards[column].len() && game.tableau.cards[target].is_empty()
+        }
+        _ => false,
+    }
+}
+
+// Takes cards back off the foundations, or shuffles part of a run without turning over a card or
+// freeing one to go up. Solutions rarely need these, but they make up most of the moves
+fn is_rarely_needed(game: &Game, mv: Move) -> bool {
+    match mv {
+        Move::FoundationToTableau(_, _) => true,
+        Move::TableauToTableau(column, _, count) => {
+            let cards = &game.tableau.cards[column];
+            let left = cards.len() - count;
+            left > game.tableau.cutoffs[column] as usize
+                && game.foundation.find_slot(cards[left - 1]).is_none()
+        }
+        _ => false,
+    }
+}
+
+// Sorted so that the move to try first is popped first
+fn candidates(game: &Game, thorough: bool) -> Vec<Move> {
+    let moves = game.legal_moves();
+    if let Some(mv) = moves.iter().find(|mv| is_safe(game, **mv)) {
+        return vec![*mv];
+    }
+
+    let mut moves: Vec<Move> = moves
+        .into_iter()
+        .filter(|mv| !is_pointless(game, *mv))
+        .filter(|mv| thorough || !is_rarely_needed(game, *mv))
+        .collect();
+    moves.sort_by_key(|mv| priority(game, *mv));
+    moves
+}
+
+// A quick search without the moves that are rarely needed finds most solutions. Only a search
+// over every move can tell that there are none
+pub fn solve(game: &Game, deadline: Instant) -> Outcome {
+    match search(game, deadline, false) {
+        Outcome::Solved(moves) => Outcome::Solved(moves),
+        _ => search(game, deadline, true),
+    }
+}
+
+// Depth first search over the legal moves, never visiting the same position twice
+fn search(game: &Game, deadline: Instant, thorough: bool) -> Outcome {
+    let mut start = game.clone();
+    start.events.drain();
+
+    let mut seen = HashSet::from([key(&start)]);
+    let mut path = Vec::new();
+    // Each position on the current line with the moves from it still to be tried
+    let moves = candidates(&start, thorough);
+    let mut stack = vec![(start, moves)];
+
+    while let Some((game, moves)) = stack.last_mut() {
+        if game.is_won() {
+            return Outcome::Solved(path);
+        }
+        if Instant::now() >= deadline {
+            return Outcome::Unknown;
+        }
+
+        let Some(mv) = moves.pop() else {
+            stack.pop();
+            path.pop();
+            continue;
+        };
+
+        let mut next = game.clone();
+        if !next.apply(mv) {
+            continue;
+        }
+        // The search never reads them, and they'd pile up in every copy
+        next.events.drain();
+        if !seen.insert(key(&next)) {
+            continue;
+        }
+
+        let moves = candidates(&next, thorough);
+        path.push(mv);
+        stack.push((next, moves));
+    }
+
+    Outcome::Unsolvable
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::Rules;
+    use std::time::Duration;
+
+    fn mock_deadline() -> Instant {
+        Instant::now() + Duration::from_secs(10)
+    }
+
+    #[test]
+    fn test_solve() {
+        let game = Game::from_position(
+            Rules::klondike(),
+            "Kh|Qs/|Ks/-/-/-/-/- Qh - Js/Jh/Kc/Kd - A 0",
+        )
+        .unwrap();
+
+        let Outcome::Solved(moves) = solve(&game, mock_deadline()) else {
+            panic!("not solved");
+        };
+        let mut replayed = game.clone();
+        assert!(moves.iter().all(|mv| replayed.apply(*mv)));
+        assert!(replayed.is_won());
+    }
+
+    #[test]
+    fn test_is_safe() {
+        let mut game = Game::from_position(
+            Rules::klondike(),
+            "|3h/|3s/|4h/-/-/-/- 4s5s6s7s8s9sTsJsQsKs2h5h6h7h8h9hThJhQhKh3c4c5c6c7c8c9cTcJcQcKc3d4d5d6d7d8d9dTdJdQdKd - 2s/Ah/2c/2d - A 0",
+        )
+        .unwrap();
+
+        // Both black twos are up, but the two of hearts isn't
+        assert!(is_safe(&game, Move::TableauToFoundation(0, 1)));
+        assert!(!is_safe(&game, Move::TableauToFoundation(1, 0)));
+        assert!(!is_safe(&game, Move::TableauToTableau(1, 2, 1)));
+
+        game.rules.wrap_around = true;
+        assert!(!is_safe(&game, Move::TableauToFoundation(0, 1)));
+    }
+
+    #[test]
+    fn test_key() {
+        let position = "Kh|Qs/|Ks/-/-/-/-/- Qh - Js/Jh/Kc/Kd - A 0";
+        let game = Game::from_position(Rules::klondike(), position).unwrap();
+        assert_eq!(key(&game), position.rsplit_once(' ').unwrap().0);
+
+        let rules = Rules::from_spec("klondike,passes=3").unwrap();
+        let game = Game::from_position(rules, position).unwrap();
+        assert_eq!(key(&game), position);
+    }
+
+    #[test]
+    fn test_unsolvable() {
+        // The queen of spades is buried under the king, which has no empty column to go to
+        let rules = Rules::from_spec("klondike,columns=1").unwrap();
+        let game = Game::from_position(rules, "Qs|Ks - - Js/Kh/Kc/Kd - A 0").unwrap();
+
+        assert_eq!(solve(&game, mock_deadline()), Outcome::Unsolvable);
+        assert_eq!(solve(&game, Instant::now()), Outcome::Unknown);
+    }
+}
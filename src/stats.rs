@@ -0,0 +1,262 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    i18n::{self, fill},
+    rules::Rules,
+    storage::{data_path, read_json, write_json},
+    utils::format_duration,
+};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct GameResult {
+    pub won: bool,
+    pub secs: u64,
+    pub moves: u32,
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+pub struct Record {
+    pub games_played: u32,
+    pub wins: u32,
+    pub current_streak: u32,
+    pub best_streak: u32,
+    pub fastest_win_secs: Option<u64>,
+    pub fewest_moves: Option<u32>,
+    pub total_time_secs: u64,
+}
+
+impl Record {
+    pub fn add_game(&mut self, result: &GameResult) {
+        self.games_played += 1;
+        self.total_time_secs += result.secs;
+
+        if !result.won {
+            self.current_streak = 0;
+            return;
+        }
+
+        self.wins += 1;
+        self.current_streak += 1;
+        self.best_streak = self.best_streak.max(self.current_streak);
+        self.fastest_win_secs = Some(match self.fastest_win_secs {
+            Some(fastest) => fastest.min(result.secs),
+            None => result.secs,
+        });
+        self.fewest_moves = Some(match self.fewest_moves {
+            Some(fewest) => fewest.min(result.moves),
+            None => result.moves,
+        });
+    }
+
+    pub fn win_rate(&self) -> f64 {
+        if self.games_played == 0 {
+            return 0.0;
+        }
+
+        self.wins as f64 * 100.0 / self.games_played as f64
+    }
+
+    pub fn average_time_secs(&self) -> Option<u64> {
+        (self.games_played > 0).then(|| self.total_time_secs / self.games_played as u64)
+    }
+}
+
+#[derive(Serialize)]
+pub struct Summary {
+    pub variant: String,
+    pub rules: String,
+    pub games_played: u32,
+    pub wins: u32,
+    pub win_rate: f64,
+    pub current_streak: u32,
+    pub best_streak: u32,
+    pub fastest_win_secs: Option<u64>,
+    pub fewest_moves: Option<u32>,
+    pub average_time_secs: Option<u64>,
+}
+
+// Records are keyed by the rules spec, which always starts with the variant name
+#[derive(Serialize, Deserialize, Default)]
+pub struct Stats {
+    pub records: BTreeMap<String, Record>,
+}
+
+impl Stats {
+    pub fn path() -> PathBuf {
+        data_path("stats.json")
+    }
+
+    pub fn load() -> Self {
+        read_json(&Self::path())
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        write_json(&Self::path(), self)
+    }
+
+    pub fn record(&mut self, rules: &Rules, result: GameResult) {
+        self.records
+            .entry(rules.spec())
+            .or_default()
+            .add_game(&result);
+    }
+
+    pub fn summaries(&self) -> Vec<Summary> {
+        self.records
+            .iter()
+            .map(|(spec, record)| {
+                let (variant, rules) = spec.split_once(',').unwrap_or((spec, ""));
+                Summary {
+                    variant: variant.to_string(),
+                    rules: rules.to_string(),
+                    games_played: record.games_played,
+                    wins: record.wins,
+                    win_rate: record.win_rate(),
+                    current_streak: record.current_streak,
+                    best_streak: record.best_streak,
+                    fastest_win_secs: record.fastest_win_secs,
+                    fewest_moves: record.fewest_moves,
+                    average_time_secs: record.average_time_secs(),
+                }
+            })
+            .collect()
+    }
+
+    pub fn to_text(&self) -> String {
+        let catalog = i18n::text();
+        let summaries = self.summaries();
+        if summaries.is_empty() {
+            return format!("{}\n", catalog.no_games);
+        }
+
+        let mut text = String::new();
+        for summary in summaries {
+            text.push_str(&format!("{} ({})\n", summary.variant, summary.rules));
+            let lines = [
+                fill(
+                    catalog.stats_played,
+                    &[
+                        &summary.games_played,
+                        &summary.wins,
+                        &format!("{:.1}", summary.win_rate),
+                    ],
+                ),
+                fill(
+                    catalog.stats_streaks,
+                    &[&summary.current_streak, &summary.best_streak],
+                ),
+                fill(
+                    catalog.stats_bests,
+                    &[
+                        &optional_duration(summary.fastest_win_secs),
+                        &match summary.fewest_moves {
+                            Some(moves) => moves.to_string(),
+                            None => "-".to_string(),
+                        },
+                        &optional_duration(summary.average_time_secs),
+                    ],
+                ),
+            ];
+            for line in lines {
+                text.push_str(&line);
+                text.push('\n');
+            }
+        }
+        text
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(&self.summaries()).unwrap_or_default()
+    }
+}
+
+pub fn optional_duration(secs: Option<u64>) -> String {
+    match secs {
+        Some(secs) => format_duration(secs),
+        None => "-".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mock_stats() -> Stats {
+        let mut stats = Stats::default();
+        let rules = Rules::klondike();
+
+        stats.record(
+            &rules,
+            GameResult {
+                won: true,
+                secs: 300,
+                moves: 120,
+            },
+        );
+        stats.record(
+            &rules,
+            GameResult {
+                won: true,
+                secs: 200,
+                moves: 140,
+            },
+        );
+        stats.record(
+            &rules,
+            GameResult {
+                won: false,
+                secs: 100,
+                moves: 30,
+            },
+        );
+        stats.record(
+            &Rules::canfield(),
+            GameResult {
+                won: false,
+                secs: 60,
+                moves: 10,
+            },
+        );
+
+        stats
+    }
+
+    #[test]
+    fn test_record() {
+        let stats = mock_stats();
+        let record = &stats.records[&Rules::klondike().spec()];
+
+        assert_eq!(record.games_played, 3);
+        assert_eq!(record.wins, 2);
+        assert_eq!(record.current_streak, 0);
+        assert_eq!(record.best_streak, 2);
+        assert_eq!(record.fastest_win_secs, Some(200));
+        assert_eq!(record.fewest_moves, Some(120));
+        assert_eq!(record.average_time_secs(), Some(200));
+    }
+
+    #[test]
+    fn test_summaries() {
+        let stats = mock_stats();
+        let summaries = stats.summaries();
+
+        assert_eq!(summaries.len(), 2);
+        assert_eq!(summaries[0].variant, "canfield");
+        assert_eq!(summaries[1].variant, "klondike");
+        assert!(summaries[1].rules.starts_with("draw=1"));
+        assert!((summaries[1].win_rate - 66.666).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_to_text_and_json() {
+        let stats = mock_stats();
+
+        assert!(stats.to_text().contains("Fastest win: 3:20"));
+        assert!(Stats::default().to_text().contains("No games"));
+
+        let json: serde_json::Value = serde_json::from_str(&stats.to_json()).unwrap();
+        assert_eq!(json[1]["wins"], 2);
+    }
+}
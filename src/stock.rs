@@ -1,23 +1,37 @@
-use rand::prelude::*;
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 
 use crate::{card::Card, utils::get_suit_by_card_suit_index, waste::Waste};
 
+#[derive(Clone)]
 pub struct Stock {
     pub cards: Vec<Card>,
+    pub recycles: u32,
 }
 
 impl Stock {
-    pub fn new() -> Self {
-        let mut new_stock = Vec::with_capacity(52);
-        for i in 0..=3 {
-            for j in 1..=13 {
-                new_stock.push(Card::new(get_suit_by_card_suit_index(i), j));
+    pub fn new(decks: usize, seed: u64) -> Self {
+        let mut new_stock = Vec::with_capacity(52 * decks);
+        for _ in 0..decks {
+            for i in 0..=3 {
+                for j in 1..=13 {
+                    new_stock.push(Card::new(get_suit_by_card_suit_index(i), j));
+                }
             }
         }
 
-        new_stock.shuffle(&mut rand::rng());
+        // Seeds are shared and replayed, so the deal can't depend on the platform or the rand
+        // version. ChaCha8 gives the same numbers everywhere and the shuffle is done here
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        for i in (1..new_stock.len()).rev() {
+            let j = (rng.next_u64() % (i as u64 + 1)) as usize;
+            new_stock.swap(i, j);
+        }
 
-        Self { cards: new_stock }
+        Self {
+            cards: new_stock,
+            recycles: 0,
+        }
     }
 
     pub fn deal(&mut self) -> Card {
@@ -26,6 +40,7 @@ impl Stock {
 
     pub fn reset(&mut self, waste: &Waste) {
         self.cards = waste.cards.iter().rev().cloned().collect();
+        self.recycles += 1;
     }
 }
 
@@ -35,15 +50,42 @@ mod tests {
 
     #[test]
     fn test_deal() {
-        let mut mock_stock = Stock::new();
+        let mut mock_stock = Stock::new(1, rand::random());
         let mock_stock_size = mock_stock.cards.len();
         let _card = mock_stock.deal();
         assert_eq!(mock_stock_size - 1, mock_stock.cards.len());
     }
 
+    #[test]
+    fn test_new() {
+        assert_eq!(Stock::new(1, rand::random()).cards.len(), 52);
+        assert_eq!(Stock::new(2, rand::random()).cards.len(), 104);
+    }
+
+    #[test]
+    fn test_new_with_seed() {
+        let first = Stock::new(1, 5);
+        let second = Stock::new(1, 5);
+
+        for (a, b) in first.cards.iter().zip(second.cards.iter()) {
+            assert_eq!(a.suit, b.suit);
+            assert_eq!(a.rank, b.rank);
+        }
+    }
+
+    #[test]
+    fn test_seed_gives_a_fixed_deal() {
+        // If this changes, every saved replay, daily deal and shared seed gets other cards
+        let cards: String = Stock::new(1, 5).cards[..8]
+            .iter()
+            .map(|card| crate::notation::format_card(*card))
+            .collect();
+        assert_eq!(cards, "9dTh5d9c5sKc5c3s");
+    }
+
     #[test]
     fn test_reset() {
-        let mut mock_stock = Stock::new();
+        let mut mock_stock = Stock::new(1, rand::random());
         let mut mock_waste = Waste::new();
 
         let mock_stock_card = mock_stock.deal();
@@ -54,5 +96,6 @@ mod tests {
 
         assert_eq!(mock_stock.cards[0].rank, reversed_cards[0].rank);
         assert_eq!(mock_stock.cards[0].suit, reversed_cards[0].suit);
+        assert_eq!(mock_stock.recycles, 1);
     }
 }
@@ -0,0 +1,126 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Serialize, de::DeserializeOwned};
+
+pub fn data_dir() -> PathBuf {
+    if let Some(dir) = env::var_os("TERSOLI_DATA_DIR") {
+        return PathBuf::from(dir);
+    }
+
+    if let Some(dir) = env::var_os("XDG_DATA_HOME") {
+        return PathBuf::from(dir).join("tersoli");
+    }
+
+    match env::var_os("HOME") {
+        Some(home) => PathBuf::from(home).join(".local/share/tersoli"),
+        None => PathBuf::from(".tersoli"),
+    }
+}
+
+pub fn data_path(file_name: &str) -> PathBuf {
+    data_dir().join(file_name)
+}
+
+pub fn config_dir() -> PathBuf {
+    if let Some(dir) = env::var_os("TERSOLI_CONFIG_DIR") {
+        return PathBuf::from(dir);
+    }
+
+    if let Some(dir) = env::var_os("XDG_CONFIG_HOME") {
+        return PathBuf::from(dir).join("tersoli");
+    }
+
+    match env::var_os("HOME") {
+        Some(home) => PathBuf::from(home).join(".config/tersoli"),
+        None => PathBuf::from(".tersoli"),
+    }
+}
+
+pub fn config_path(file_name: &str) -> PathBuf {
+    config_dir().join(file_name)
+}
+
+// A file that can't be parsed is moved to <name>.bak, so saving the defaults doesn't lose it
+fn read_or_back_up<T: Default, E>(path: &Path, parse: impl FnOnce(&str) -> Result<T, E>) -> T {
+    let Ok(contents) = fs::read_to_string(path) else {
+        return T::default();
+    };
+
+    parse(&contents).unwrap_or_else(|_| {
+        let mut backup = path.as_os_str().to_owned();
+        backup.push(".bak");
+        let _ = fs::rename(path, backup);
+        T::default()
+    })
+}
+
+pub fn read_json<T: DeserializeOwned + Default>(path: &Path) -> T {
+    read_or_back_up(path, |contents| serde_json::from_str(contents))
+}
+
+pub fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    fs::write(path, serde_json::to_string_pretty(value)?)?;
+    Ok(())
+}
+
+pub fn read_toml<T: DeserializeOwned + Default>(path: &Path) -> T {
+    read_or_back_up(path, |contents| toml::from_str(contents))
+}
+
+pub fn write_toml<T: Serialize>(path: &Path, value: &T) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    fs::write(path, toml::to_string(value)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_read_and_write_json() {
+        let path =
+            env::temp_dir().join(format!("tersoli-storage-{}/test.json", std::process::id()));
+
+        let missing: BTreeMap<String, u32> = read_json(&path);
+        assert!(missing.is_empty());
+
+        let mut values = BTreeMap::new();
+        values.insert("wins".to_string(), 3);
+        write_json(&path, &values).unwrap();
+
+        let loaded: BTreeMap<String, u32> = read_json(&path);
+        assert_eq!(loaded, values);
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_corrupt_file_is_backed_up() {
+        let dir = env::temp_dir().join(format!("tersoli-corrupt-{}", std::process::id()));
+        let path = dir.join("config.toml");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(&path, "theme = ").unwrap();
+
+        let loaded: BTreeMap<String, u32> = read_toml(&path);
+        assert!(loaded.is_empty());
+        assert!(!path.exists());
+        assert_eq!(
+            fs::read_to_string(dir.join("config.toml.bak")).unwrap(),
+            "theme = "
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
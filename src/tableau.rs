@@ -1,30 +1,49 @@
-use crate::{card::Card, location::Location, stock::Stock, suit::Suit};
-
+use crate::{
+    card::Card,
+    location::Location,
+    rules::{EmptyColumn, Rules, Variant},
+    stock::Stock,
+    suit::Suit,
+};
+
+#[derive(Clone)]
 pub struct Tableau {
     pub cards: Vec<Vec<Card>>,
     pub cutoffs: Vec<u8>,
+    pub rules: Rules,
 }
 
 impl Tableau {
-    pub fn new() -> Self {
+    pub fn new(rules: Rules) -> Self {
         Self {
             cards: vec![],
-            cutoffs: vec![0, 1, 2, 3, 4, 5, 6],
+            cutoffs: (0..rules.tableau_columns)
+                .map(|column| match rules.variant {
+                    Variant::Klondike => column as u8,
+                    Variant::Canfield => 0,
+                })
+                .collect(),
+            rules,
         }
     }
 
     pub fn initialize(&mut self, stock: &mut Stock) {
         self.cards.clear();
-        for i in 0..7 {
+        for i in 0..self.rules.tableau_columns {
+            let amount = match self.rules.variant {
+                Variant::Klondike => i + 1,
+                Variant::Canfield => 1,
+            };
+
             let mut row = Vec::new();
-            for _j in 0..=i {
+            for _j in 0..amount {
                 row.push(stock.deal());
             }
             self.cards.push(row);
         }
     }
 
-    pub fn get_top_card(&mut self, location: Location) -> Option<Card> {
+    pub fn get_top_card(&self, location: Location) -> Option<Card> {
         if let Location::Tableau(index) = location {
             if self.cards[index].is_empty() {
                 return None;
@@ -80,29 +99,29 @@ impl Tableau {
         }
     }
 
-    pub fn add_card(&mut self, to: Location, card: Card) -> bool {
-        if let Location::Tableau(index) = to {
-            let parent_card = match self.get_top_card(to) {
-                Some(parent) => parent,
-                _ => {
-                    if card.rank == 13 {
-                        self.cards[index].push(card);
-                        return true;
-                    }
-                    return false;
-                }
-            };
+    fn can_stack(&self, parent_card: Card, card: Card) -> bool {
+        let is_parent_red = matches!(parent_card.suit, Suit::Hearts | Suit::Diamonds);
+        let is_card_red = matches!(card.suit, Suit::Hearts | Suit::Diamonds);
 
-            let is_parent_red = matches!(parent_card.suit, Suit::Hearts | Suit::Diamonds);
-            let is_card_red = matches!(card.suit, Suit::Hearts | Suit::Diamonds);
-            if is_parent_red == is_card_red {
-                return false;
-            }
+        is_parent_red != is_card_red
+            && Some(card.rank) == self.rules.previous_rank(parent_card.rank)
+    }
 
-            if card.rank != parent_card.rank - 1 {
-                return false;
-            }
+    // Checks a move without making it, so piles can be queried for where a card could go
+    pub fn can_add_card(&self, to: Location, card: Card) -> bool {
+        match to {
+            Location::Tableau(index) if index < self.cards.len() => match self.get_top_card(to) {
+                Some(parent_card) => self.can_stack(parent_card, card),
+                _ => self.rules.can_fill_empty_column(card.rank),
+            },
+            _ => false,
+        }
+    }
 
+    pub fn add_card(&mut self, to: Location, card: Card) -> bool {
+        if let Location::Tableau(index) = to
+            && self.can_add_card(to, card)
+        {
             self.cards[index].push(card);
             return true;
         }
@@ -113,6 +132,14 @@ impl Tableau {
         self.cards[column][(self.cutoffs[column] as usize)..].to_vec()
     }
 
+    pub fn hidden_count(&self) -> usize {
+        self.cards
+            .iter()
+            .zip(self.cutoffs.iter())
+            .map(|(column, cutoff)| (*cutoff as usize).min(column.len()))
+            .sum()
+    }
+
     pub fn update_cutoffs(&mut self, index: usize) {
         if index < self.cutoffs.len() && index < self.cards.len() {
             let cutoff = self.cutoffs[index];
@@ -124,41 +151,87 @@ impl Tableau {
         }
     }
 
-    fn move_cards(
-        &mut self,
-        needed_rank: u8,
-        needed_suit: Option<Suit>,
-        from: Location,
-        to: Location,
-    ) {
-        let card_index = match self.find_card(from, needed_rank, needed_suit) {
+    fn move_cards(&mut self, card_index: Option<usize>, from: Location, to: Location) -> bool {
+        let card_index = match card_index {
             Some(index) => index,
-            _ => return,
+            _ => return false,
         };
 
         let cards_to_move = self.take_cards_at_index(from, card_index);
+        let moved = !cards_to_move.is_empty();
 
         for card in cards_to_move {
             self.add_card(to, card);
         }
+
+        moved
     }
 
-    pub fn try_to_move_between_tableau(&mut self, from: Location, to: Location) {
+    fn find_run_start(&self, from: Location, to: Location) -> Option<usize> {
         let to_card: Card = match self.get_top_card(to) {
             Some(card) => card,
             _ => {
-                self.move_cards(13, None, from, to);
-                return;
+                return match (self.rules.empty_column, from) {
+                    (EmptyColumn::Rank(rank), _) => self.find_card(from, rank, None),
+                    (EmptyColumn::Any, Location::Tableau(column)) => {
+                        let visible = self.cutoffs[column] as usize;
+                        (visible < self.cards[column].len()).then_some(visible)
+                    }
+                    _ => None,
+                };
             }
         };
 
-        let needed_rank = to_card.rank - 1;
+        let needed_rank = self.rules.previous_rank(to_card.rank)?;
         let needed_suit = match to_card.suit {
             Suit::Clubs | Suit::Spades => Suit::Hearts,
             Suit::Hearts | Suit::Diamonds => Suit::Spades,
         };
 
-        self.move_cards(needed_rank, Some(needed_suit), from, to);
+        self.find_card(from, needed_rank, Some(needed_suit))
+    }
+
+    pub fn run_length(&self, from: Location, to: Location) -> Option<usize> {
+        match (from, to) {
+            (Location::Tableau(column), Location::Tableau(target))
+                if column != target && column < self.cards.len() && target < self.cards.len() =>
+            {
+                let start = self.find_run_start(from, to)?;
+                Some(self.cards[column].len() - start)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn can_move_run(&self, from: Location, to: Location, count: usize) -> bool {
+        let (column, target) = match (from, to) {
+            (Location::Tableau(column), Location::Tableau(target)) => (column, target),
+            _ => return false,
+        };
+
+        if column == target || column >= self.cards.len() || target >= self.cards.len() {
+            return false;
+        }
+
+        let length = self.cards[column].len();
+        if count == 0 || count > length || length - count < self.cutoffs[column] as usize {
+            return false;
+        }
+
+        let run = &self.cards[column][length - count..];
+        run.windows(2).all(|pair| self.can_stack(pair[0], pair[1])) && self.can_add_card(to, run[0])
+    }
+
+    pub fn move_run(&mut self, from: Location, to: Location, count: usize) -> bool {
+        if !self.can_move_run(from, to, count) {
+            return false;
+        }
+
+        let Location::Tableau(column) = from else {
+            return false;
+        };
+        let start = self.cards[column].len() - count;
+        self.move_cards(Some(start), from, to)
     }
 }
 
@@ -167,8 +240,8 @@ mod tests {
     use super::*;
 
     fn mock_tableau() -> Tableau {
-        let mut stock = Stock::new();
-        let mut tableau = Tableau::new();
+        let mut stock = Stock::new(1, rand::random());
+        let mut tableau = Tableau::new(Rules::klondike());
 
         tableau.initialize(&mut stock);
 
@@ -193,7 +266,7 @@ mod tests {
 
     #[test]
     fn test_get_top_card() {
-        let mut tableau = mock_tableau();
+        let tableau = mock_tableau();
         assert!(tableau.get_top_card(Location::Tableau(1)).is_some());
     }
 
@@ -241,8 +314,17 @@ mod tests {
     }
 
     #[test]
-    fn test_try_to_move_between_tableau() {
-        let mut tableau = Tableau::new();
+    fn test_add_card_with_wrap_around() {
+        let mut tableau = Tableau::new(Rules::canfield());
+        tableau.cards = vec![vec![Card::new(Suit::Hearts, 1)], vec![], vec![], vec![]];
+
+        assert!(tableau.add_card(Location::Tableau(0), Card::new(Suit::Spades, 13)));
+        assert!(tableau.add_card(Location::Tableau(1), Card::new(Suit::Spades, 5)));
+    }
+
+    #[test]
+    fn test_move_between_tableau() {
+        let mut tableau = Tableau::new(Rules::klondike());
 
         tableau.cards = vec![
             vec![Card::new(Suit::Clubs, 5)],
@@ -254,9 +336,69 @@ mod tests {
             vec![],
         ];
 
-        tableau.try_to_move_between_tableau(Location::Tableau(1), Location::Tableau(0));
+        let count = tableau.run_length(Location::Tableau(1), Location::Tableau(0));
+        assert_eq!(count, Some(1));
+        assert!(tableau.move_run(Location::Tableau(1), Location::Tableau(0), 1));
         assert_eq!(tableau.cards[0].len(), 2);
         assert_eq!(tableau.cards[0][1].rank, 4);
         assert_eq!(tableau.cards[1].len(), 1);
     }
+
+    #[test]
+    fn test_run_length_and_move_run() {
+        let mut tableau = Tableau::new(Rules::klondike());
+        tableau.cutoffs = vec![0; 7];
+
+        tableau.cards = vec![
+            vec![
+                Card::new(Suit::Clubs, 10),
+                Card::new(Suit::Clubs, 8),
+                Card::new(Suit::Hearts, 7),
+            ],
+            vec![Card::new(Suit::Diamonds, 9)],
+            vec![Card::new(Suit::Spades, 1)],
+            vec![],
+            vec![],
+            vec![],
+            vec![],
+        ];
+
+        assert_eq!(
+            tableau.run_length(Location::Tableau(0), Location::Tableau(1)),
+            Some(2)
+        );
+        assert_eq!(
+            tableau.run_length(Location::Tableau(2), Location::Tableau(3)),
+            None
+        );
+
+        assert!(!tableau.move_run(Location::Tableau(0), Location::Tableau(1), 1));
+        assert!(!tableau.move_run(Location::Tableau(0), Location::Tableau(1), 3));
+        assert!(!tableau.move_run(Location::Tableau(2), Location::Tableau(3), 1));
+
+        assert!(tableau.can_move_run(Location::Tableau(0), Location::Tableau(1), 2));
+        assert_eq!(tableau.cards[0].len(), 3);
+        assert!(tableau.move_run(Location::Tableau(0), Location::Tableau(1), 2));
+        assert_eq!(tableau.cards[0].len(), 1);
+        assert_eq!(tableau.cards[1].len(), 3);
+    }
+
+    #[test]
+    fn test_try_to_move_run_to_any_empty_column() {
+        let mut tableau = Tableau::new(Rules::canfield());
+
+        tableau.cards = vec![
+            vec![Card::new(Suit::Clubs, 9), Card::new(Suit::Hearts, 8)],
+            vec![],
+            vec![Card::new(Suit::Clubs, 7)],
+            vec![Card::new(Suit::Spades, 2)],
+        ];
+
+        let count = tableau.run_length(Location::Tableau(0), Location::Tableau(1));
+        assert_eq!(count, Some(2));
+        assert!(tableau.move_run(Location::Tableau(0), Location::Tableau(1), 2));
+        assert_eq!(tableau.cards[0].len(), 0);
+        assert_eq!(tableau.cards[1].len(), 2);
+        assert_eq!(tableau.cards[1][0].rank, 9);
+    }
 }
@@ -0,0 +1,341 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use ratatui::style::Color;
+use serde::Deserialize;
+
+use crate::{card_view::CardStyle, storage::config_path, suit::Suit};
+
+// Colors of everything on the board, user themes only need the keys they change
+#[derive(Deserialize, Clone, PartialEq, Debug)]
+#[serde(default)]
+pub struct Theme {
+    pub name: String,
+    pub pile: Color,
+    pub selected: Color,
+    pub active: Color,
+    pub selected_active: Color,
+    // Piles that would take the active cards
+    pub target: Color,
+    pub hearts: Color,
+    pub diamonds: Color,
+    pub clubs: Color,
+    pub spades: Color,
+    pub label: Color,
+    pub card_border: Color,
+    pub back: Color,
+    pub muted: Color,
+    // For themes without enough colors to tell the selected pile apart
+    pub bold_selection: bool,
+    // Without colors, piles are told apart by reverse video and bold, and red suits are underlined
+    #[serde(skip)]
+    pub colorless: bool,
+    // Chosen in the settings for every theme alike
+    #[serde(skip)]
+    pub card_style: CardStyle,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::classic()
+    }
+}
+
+impl Theme {
+    pub fn classic() -> Self {
+        Self {
+            name: "classic".to_string(),
+            pile: Color::White,
+            selected: Color::Blue,
+            active: Color::Red,
+            selected_active: Color::Green,
+            target: Color::LightMagenta,
+            hearts: Color::LightRed,
+            diamonds: Color::LightRed,
+            clubs: Color::LightGreen,
+            spades: Color::LightGreen,
+            label: Color::Yellow,
+            card_border: Color::White,
+            back: Color::Blue,
+            muted: Color::DarkGray,
+            bold_selection: false,
+            colorless: false,
+            card_style: CardStyle::default(),
+        }
+    }
+
+    pub fn high_contrast() -> Self {
+        Self {
+            name: "high-contrast".to_string(),
+            pile: Color::Gray,
+            selected: Color::LightCyan,
+            active: Color::LightYellow,
+            selected_active: Color::LightMagenta,
+            target: Color::LightGreen,
+            hearts: Color::LightRed,
+            diamonds: Color::LightRed,
+            clubs: Color::White,
+            spades: Color::White,
+            label: Color::LightYellow,
+            card_border: Color::White,
+            back: Color::LightCyan,
+            muted: Color::Gray,
+            bold_selection: true,
+            colorless: false,
+            card_style: CardStyle::default(),
+        }
+    }
+
+    pub fn solarized() -> Self {
+        Self {
+            name: "solarized".to_string(),
+            pile: Color::Rgb(0x58, 0x6e, 0x75),
+            selected: Color::Rgb(0x26, 0x8b, 0xd2),
+            active: Color::Rgb(0xcb, 0x4b, 0x16),
+            selected_active: Color::Rgb(0x85, 0x99, 0x00),
+            target: Color::Rgb(0x6c, 0x71, 0xc4),
+            hearts: Color::Rgb(0xdc, 0x32, 0x2f),
+            diamonds: Color::Rgb(0xdc, 0x32, 0x2f),
+            clubs: Color::Rgb(0x93, 0xa1, 0xa1),
+            spades: Color::Rgb(0x93, 0xa1, 0xa1),
+            label: Color::Rgb(0xb5, 0x89, 0x00),
+            card_border: Color::Rgb(0x83, 0x94, 0x96),
+            back: Color::Rgb(0x2a, 0xa1, 0x98),
+            muted: Color::Rgb(0x58, 0x6e, 0x75),
+            bold_selection: false,
+            colorless: false,
+            card_style: CardStyle::default(),
+        }
+    }
+
+    pub fn monochrome() -> Self {
+        Self {
+            name: "monochrome".to_string(),
+            pile: Color::DarkGray,
+            selected: Color::Gray,
+            active: Color::White,
+            selected_active: Color::White,
+            target: Color::Gray,
+            hearts: Color::White,
+            diamonds: Color::White,
+            clubs: Color::Gray,
+            spades: Color::Gray,
+            label: Color::White,
+            card_border: Color::Gray,
+            back: Color::DarkGray,
+            muted: Color::DarkGray,
+            bold_selection: true,
+            colorless: false,
+            card_style: CardStyle::default(),
+        }
+    }
+
+    // Every suit gets its own color, like the four-color decks used in online poker
+    pub fn four_color() -> Self {
+        Self {
+            name: "four-color".to_string(),
+            hearts: Color::LightRed,
+            diamonds: Color::LightBlue,
+            clubs: Color::LightGreen,
+            spades: Color::White,
+            ..Self::classic()
+        }
+    }
+
+    // Used instead of every other theme when colors are turned off
+    pub fn no_color() -> Self {
+        Self {
+            name: "no-color".to_string(),
+            pile: Color::Reset,
+            selected: Color::Reset,
+            active: Color::Reset,
+            selected_active: Color::Reset,
+            target: Color::Reset,
+            hearts: Color::Reset,
+            diamonds: Color::Reset,
+            clubs: Color::Reset,
+            spades: Color::Reset,
+            label: Color::Reset,
+            card_border: Color::Reset,
+            back: Color::Reset,
+            muted: Color::Reset,
+            bold_selection: false,
+            colorless: true,
+            card_style: CardStyle::default(),
+        }
+    }
+
+    pub fn builtin() -> Vec<Self> {
+        vec![
+            Self::classic(),
+            Self::high_contrast(),
+            Self::solarized(),
+            Self::monochrome(),
+            Self::four_color(),
+        ]
+    }
+
+    pub fn suit_color(&self, suit: Suit) -> Color {
+        match suit {
+            Suit::Hearts => self.hearts,
+            Suit::Diamonds => self.diamonds,
+            Suit::Clubs => self.clubs,
+            Suit::Spades => self.spades,
+        }
+    }
+}
+
+// Built-in themes followed by the user's, which are cycled through at runtime
+pub struct Themes {
+    pub themes: Vec<Theme>,
+    pub current: usize,
+    // Files that couldn't be loaded, with the reason
+    pub errors: Vec<String>,
+}
+
+impl Themes {
+    pub fn dir() -> PathBuf {
+        config_path("themes")
+    }
+
+    // Every .toml file in the themes directory, named after the file unless it says otherwise.
+    // A user theme with a built-in theme's name replaces it
+    pub fn load(name: &str) -> Self {
+        Self::load_from(&Self::dir(), name)
+    }
+
+    pub fn load_from(dir: &Path, name: &str) -> Self {
+        let mut themes = Theme::builtin();
+        let mut errors = Vec::new();
+
+        let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+            .map(|entries| entries.flatten().map(|entry| entry.path()).collect())
+            .unwrap_or_default();
+        paths.sort();
+        for path in paths {
+            if path.extension().is_none_or(|extension| extension != "toml") {
+                continue;
+            }
+            let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+            let contents = match fs::read_to_string(&path) {
+                Ok(contents) => contents,
+                Err(err) => {
+                    errors.push(format!("{}: {}", file_name, err));
+                    continue;
+                }
+            };
+            let mut theme = match toml::from_str::<Theme>(&contents) {
+                Ok(theme) => theme,
+                Err(err) => {
+                    errors.push(format!("{}: {}", file_name, err.message()));
+                    continue;
+                }
+            };
+            let named = contents
+                .parse::<toml::Table>()
+                .is_ok_and(|table| table.contains_key("name"));
+            if !named {
+                theme.name = path
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().to_string())
+                    .unwrap_or_default();
+            }
+            themes.retain(|existing| existing.name != theme.name);
+            themes.push(theme);
+        }
+
+        Self {
+            errors,
+            ..Self::from_themes(themes, name)
+        }
+    }
+
+    pub fn from_themes(themes: Vec<Theme>, name: &str) -> Self {
+        let current = themes
+            .iter()
+            .position(|theme| theme.name == name)
+            .unwrap_or_default();
+        Self {
+            themes,
+            current,
+            errors: Vec::new(),
+        }
+    }
+
+    pub fn select(&mut self, name: &str) {
+        if let Some(current) = self.themes.iter().position(|theme| theme.name == name) {
+            self.current = current;
+        }
+    }
+
+    pub fn set_card_style(&mut self, card_style: CardStyle) {
+        for theme in &mut self.themes {
+            theme.card_style = card_style;
+        }
+    }
+
+    pub fn current(&self) -> &Theme {
+        &self.themes[self.current]
+    }
+
+    pub fn next(&mut self) -> &Theme {
+        self.current = (self.current + 1) % self.themes.len();
+        self.current()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_user_theme() {
+        let theme: Theme =
+            toml::from_str("name = \"dusk\"\nback = \"magenta\"\nhearts = \"#ff8800\"").unwrap();
+
+        assert_eq!(theme.name, "dusk");
+        assert_eq!(theme.back, Color::Magenta);
+        assert_eq!(theme.hearts, Color::Rgb(0xff, 0x88, 0x00));
+        assert_eq!(theme.clubs, Theme::classic().clubs);
+        assert!(toml::from_str::<Theme>("back = \"not a color\"").is_err());
+    }
+
+    #[test]
+    fn test_load_reports_errors() {
+        let dir = std::env::temp_dir().join(format!("tersoli-themes-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("dusk.toml"), "back = \"magenta\"").unwrap();
+        fs::write(dir.join("broken.toml"), "back = \"not a color\"").unwrap();
+
+        let themes = Themes::load_from(&dir, "dusk");
+        assert_eq!(themes.current().name, "dusk");
+        assert_eq!(themes.errors.len(), 1);
+        assert!(themes.errors[0].starts_with("broken.toml: "));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_cycle_themes() {
+        let mut themes = Themes::from_themes(Theme::builtin(), "monochrome");
+
+        assert_eq!(themes.current().name, "monochrome");
+        assert_eq!(themes.next().name, "four-color");
+        assert_eq!(themes.next().name, "classic");
+        assert_eq!(Themes::from_themes(Theme::builtin(), "missing").current, 0);
+    }
+
+    #[test]
+    fn test_four_color() {
+        let theme = Theme::four_color();
+        let mut colors: Vec<Color> = [Suit::Hearts, Suit::Diamonds, Suit::Clubs, Suit::Spades]
+            .into_iter()
+            .map(|suit| theme.suit_color(suit))
+            .collect();
+        colors.dedup();
+
+        assert_eq!(colors.len(), 4);
+    }
+}
@@ -1,14 +1,5 @@
 use crate::{foundation::Foundation, suit::Suit};
 
-pub fn get_card_suit_index(suit: Suit) -> usize {
-    match suit {
-        Suit::Spades => 0,
-        Suit::Hearts => 1,
-        Suit::Clubs => 2,
-        Suit::Diamonds => 3,
-    }
-}
-
 pub fn get_suit_by_card_suit_index(index: usize) -> Suit {
     match index {
         0 => Suit::Spades,
@@ -20,31 +11,38 @@ pub fn get_suit_by_card_suit_index(index: usize) -> Suit {
 }
 
 pub fn check_win(foundation: &Foundation) -> bool {
-    foundation
-        .cards
-        .iter()
-        .all(|pile| pile.iter().filter(|card| card.is_some()).count() == 13)
+    foundation.is_complete()
+}
+
+pub fn format_duration(secs: u64) -> String {
+    if secs >= 3600 {
+        format!("{}:{:02}:{:02}", secs / 3600, secs % 3600 / 60, secs % 60)
+    } else {
+        format!("{}:{:02}", secs / 60, secs % 60)
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::card::Card;
+    use crate::{card::Card, rules::Rules};
 
     use super::*;
 
     #[test]
-    fn test_get_card_suit_index() {
-        assert_eq!(get_card_suit_index(Suit::Hearts), 1);
+    fn test_get_suit_by_card_suit_index() {
+        assert_eq!(get_suit_by_card_suit_index(2), Suit::Clubs);
     }
 
     #[test]
-    fn test_get_suit_by_card_suit_index() {
-        assert_eq!(get_suit_by_card_suit_index(2), Suit::Clubs);
+    fn test_format_duration() {
+        assert_eq!(format_duration(59), "0:59");
+        assert_eq!(format_duration(200), "3:20");
+        assert_eq!(format_duration(3725), "1:02:05");
     }
 
     #[test]
     fn test_check_win() {
-        let mut mock_foundation = Foundation::new();
+        let mut mock_foundation = Foundation::new(Rules::klondike());
 
         assert!(!check_win(&mock_foundation));
 
@@ -57,13 +55,24 @@ mod tests {
             let club = Card::new(Suit::Clubs, rank);
             let diamond = Card::new(Suit::Diamonds, rank);
 
-            mock_foundation.add_card(spade, Suit::Spades);
-            mock_foundation.add_card(heart, Suit::Hearts);
-            mock_foundation.add_card(club, Suit::Clubs);
-            mock_foundation.add_card(diamond, Suit::Diamonds);
+            mock_foundation.add_card(spade, 0);
+            mock_foundation.add_card(heart, 1);
+            mock_foundation.add_card(club, 2);
+            mock_foundation.add_card(diamond, 3);
             index += 1;
         }
 
         assert!(check_win(&mock_foundation));
+
+        let mut double_foundation = Foundation::new(Rules::double_klondike());
+        for slot in 0..4 {
+            double_foundation.cards[slot] = mock_foundation.cards[slot].clone();
+        }
+        assert!(!check_win(&double_foundation));
+
+        for slot in 4..8 {
+            double_foundation.cards[slot] = mock_foundation.cards[slot - 4].clone();
+        }
+        assert!(check_win(&double_foundation));
     }
 }
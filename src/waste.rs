@@ -2,6 +2,7 @@ use std::cmp::min;
 
 use crate::card::Card;
 
+#[derive(Clone)]
 pub struct Waste {
     pub cards: Vec<Card>,
 }